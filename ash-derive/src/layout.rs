@@ -0,0 +1,169 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{self, Attribute, DeriveInput, Ident, Meta, NestedMeta, Type};
+
+use named_fields;
+
+pub struct Rules {
+    name: &'static str,
+    layout_trait: &'static str,
+    element_trait: &'static str,
+    array_align: &'static str,
+    struct_align: &'static str,
+}
+
+pub const STD140: Rules = Rules {
+    name: "std140",
+    layout_trait: "Std140",
+    element_trait: "Std140ArrayElement",
+    array_align: "std140_array_align",
+    struct_align: "std140_struct_align",
+};
+
+pub const STD430: Rules = Rules {
+    name: "std430",
+    layout_trait: "Std430",
+    element_trait: "Std430ArrayElement",
+    array_align: "std430_array_align",
+    struct_align: "std430_struct_align",
+};
+
+pub fn derive_layout(input: TokenStream, rules: &Rules) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match layout_impl(&input, rules) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn layout_impl(input: &DeriveInput, rules: &Rules) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    if !is_repr_c(&input.attrs) {
+        return Err(syn::Error::new_spanned(
+            name,
+            "Layouts can only be derived for #[repr(C)] structs",
+        ));
+    }
+    let fields = named_fields(input, "Layouts")?;
+    if fields.is_empty() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "GLSL doesn't allow empty structs",
+        ));
+    }
+
+    let layout_trait = Ident::new(rules.layout_trait, name.span());
+    let element_trait = Ident::new(rules.element_trait, name.span());
+    let array_align = Ident::new(rules.array_align, name.span());
+    let struct_align = Ident::new(rules.struct_align, name.span());
+
+    let mut member_align = quote!(1);
+    let mut end = quote!(0);
+    let mut checks = Vec::new();
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let (align, size) = if is_layout_array(&field.attrs)? {
+            match *ty {
+                Type::Array(ref array) => {
+                    let element = &array.elem;
+                    let len = &array.len;
+                    let align = quote!(::ash::layout::#array_align(
+                        <#element as ::ash::layout::#layout_trait>::ALIGN
+                    ));
+                    let size = quote!((#len) * ::ash::layout::array_stride(
+                        <#element as ::ash::layout::#layout_trait>::SIZE,
+                        #align
+                    ));
+                    (align, size)
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "#[layout(array)] needs an array type",
+                    ))
+                }
+            }
+        } else {
+            (
+                quote!(<#ty as ::ash::layout::#layout_trait>::ALIGN),
+                quote!(<#ty as ::ash::layout::#layout_trait>::SIZE),
+            )
+        };
+        let offset_message = format!(
+            "`{}::{}` is not at its {} offset",
+            name, field_name, rules.name
+        );
+        let size_message = format!(
+            "`{}::{}` doesn't have its {} size",
+            name, field_name, rules.name
+        );
+        checks.push(quote! {
+            let offset = ::ash::layout::align_up(offset, #align);
+            assert!(offset == ::std::mem::offset_of!(#name, #field_name), #offset_message);
+            assert!(::std::mem::size_of::<#ty>() == #size, #size_message);
+            let offset = offset + #size;
+        });
+        member_align = quote!(::ash::layout::max(#member_align, #align));
+        end = quote!(::ash::layout::align_up(#end, #align) + #size);
+    }
+    let struct_size_message = format!(
+        "The size of `{}` is not its {} size, which is rounded up to the alignment of the \
+         struct. Add padding at the end of the struct",
+        name, rules.name
+    );
+
+    Ok(quote! {
+        impl ::ash::layout::#layout_trait for #name {
+            const ALIGN: usize = ::ash::layout::#struct_align(#member_align);
+            const SIZE: usize = ::ash::layout::align_up(
+                #end,
+                <#name as ::ash::layout::#layout_trait>::ALIGN
+            );
+        }
+
+        impl ::ash::layout::#element_trait for #name {}
+
+        const _: () = {
+            let offset: usize = 0;
+            #(#checks)*
+            let _ = offset;
+            assert!(
+                ::std::mem::size_of::<#name>() == <#name as ::ash::layout::#layout_trait>::SIZE,
+                #struct_size_message
+            );
+        };
+    })
+}
+
+fn is_repr_c(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| attr.parse_meta().ok())
+        .any(|meta| match meta {
+            Meta::List(list) => list.nested.iter().any(|nested| match *nested {
+                NestedMeta::Meta(Meta::Path(ref path)) => path.is_ident("C"),
+                _ => false,
+            }),
+            _ => false,
+        })
+}
+
+/// Whether the field has `#[layout(array)]`.
+fn is_layout_array(attrs: &[Attribute]) -> syn::Result<bool> {
+    let attr = match attrs.iter().find(|attr| attr.path.is_ident("layout")) {
+        Some(attr) => attr,
+        None => return Ok(false),
+    };
+    if let Meta::List(ref list) = attr.parse_meta()? {
+        if list.nested.len() == 1 {
+            if let NestedMeta::Meta(Meta::Path(ref path)) = list.nested[0] {
+                if path.is_ident("array") {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+    Err(syn::Error::new_spanned(attr, "Expected #[layout(array)]"))
+}
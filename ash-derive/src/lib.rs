@@ -0,0 +1,194 @@
+//! Derive macros for the layout traits in `ash::layout` and the `Vertex` trait in `ash::vertex`.
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{Data, DeriveInput, Field, Fields};
+
+mod layout;
+mod vertex;
+
+/// Implements `ash::layout::Std140` and checks the layout of the struct against std140 at
+/// compile time.
+///
+/// A `vec3` after a scalar is at offset 16, not 4:
+///
+/// ```compile_fail,E0080
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[repr(C)]
+/// #[derive(Std140)]
+/// struct Light {
+///     intensity: f32,
+///     position: [f32; 3],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Arrays of scalars have a stride of 16 bytes, so `#[layout(array)]` on `[f32; N]` fails:
+///
+/// ```compile_fail,E0080
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[repr(C)]
+/// #[derive(Std140)]
+/// struct Weights {
+///     #[layout(array)]
+///     values: [f32; 8],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The struct has to be padded to a multiple of its alignment, which is 16 bytes:
+///
+/// ```compile_fail,E0080
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[repr(C)]
+/// #[derive(Std140)]
+/// struct Material {
+///     color: [f32; 4],
+///     roughness: f32,
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Without `#[repr(C)]` the field order isn't fixed:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[derive(Std140)]
+/// struct Light {
+///     position: [f32; 4],
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(Std140, attributes(layout))]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+    layout::derive_layout(input, &layout::STD140)
+}
+
+/// Implements `ash::layout::Std430` and checks the layout of the struct against std430 at
+/// compile time.
+///
+/// `#[layout(array)]` only applies to arrays:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[repr(C)]
+/// #[derive(Std430)]
+/// struct Weights {
+///     #[layout(array)]
+///     value: f32,
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(Std430, attributes(layout))]
+pub fn derive_std430(input: TokenStream) -> TokenStream {
+    layout::derive_layout(input, &layout::STD430)
+}
+
+/// Implements `ash::vertex::Vertex` with an attribute for every field.
+///
+/// `format` has to name a `vk::Format` variant:
+///
+/// ```compile_fail,E0599
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[derive(Vertex)]
+/// struct Vertex {
+///     #[vertex(format = "R8g8b8a8Unknown")]
+///     color: [u8; 4],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// `location` has to be an integer:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[derive(Vertex)]
+/// struct Vertex {
+///     #[vertex(location = "1")]
+///     pos: [f32; 3],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// And there are no other options:
+///
+/// ```compile_fail
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[derive(Vertex)]
+/// struct Vertex {
+///     #[vertex(binding = 1)]
+///     pos: [f32; 3],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Neither is a type without a format unless one is given:
+///
+/// ```compile_fail,E0277
+/// # #[macro_use]
+/// # extern crate ash_derive;
+/// # extern crate ash;
+/// #[derive(Vertex)]
+/// struct Vertex {
+///     pos: [f32; 5],
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    vertex::derive_vertex(input)
+}
+
+/// The fields of a struct without generics. `derived` names what is derived, for the errors.
+fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derived: &str,
+) -> syn::Result<&'a Punctuated<Field, Comma>> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            format!(
+                "{} can only be derived for structs without generics",
+                derived
+            ),
+        ));
+    }
+    match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => Ok(&fields.named),
+            _ => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "{} can only be derived for structs with named fields",
+                    derived
+                ),
+            )),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("{} can only be derived for structs", derived),
+        )),
+    }
+}
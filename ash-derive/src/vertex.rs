@@ -0,0 +1,122 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{self, Attribute, DeriveInput, Ident, Lit, Meta, NestedMeta};
+
+use named_fields;
+
+/// The options of `#[vertex(...)]` on a field.
+struct FieldOptions {
+    format: Option<Ident>,
+    location: Option<u32>,
+}
+
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match vertex_impl(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn vertex_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = named_fields(input, "Vertex")?;
+
+    let mut attributes = Vec::new();
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let options = field_options(&field.attrs)?;
+        let format = match options.format {
+            Some(format) => quote!(::ash::vk::Format::#format),
+            None => quote!(<#ty as ::ash::vertex::VertexFormat>::FORMAT),
+        };
+        let location = match options.location {
+            Some(location) => quote!(#location),
+            None => quote!(location),
+        };
+        attributes.push(quote! {
+            let format = #format;
+            let location = #location;
+            attributes.push(::ash::vk::VertexInputAttributeDescription {
+                location: location,
+                binding: binding,
+                format: format,
+                offset: ::std::mem::offset_of!(#name, #field_name) as ::ash::vk::uint32_t,
+            });
+            let location = location + ::ash::vertex::location_count(format);
+        });
+    }
+    let attribute_count = attributes.len();
+
+    Ok(quote! {
+        impl ::ash::vertex::Vertex for #name {
+            fn binding_description(
+                binding: ::ash::vk::uint32_t,
+                input_rate: ::ash::vk::VertexInputRate,
+            ) -> ::ash::vk::VertexInputBindingDescription {
+                ::ash::vk::VertexInputBindingDescription {
+                    binding: binding,
+                    stride: ::std::mem::size_of::<#name>() as ::ash::vk::uint32_t,
+                    input_rate: input_rate,
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn attribute_descriptions(
+                binding: ::ash::vk::uint32_t,
+            ) -> Vec<::ash::vk::VertexInputAttributeDescription> {
+                let mut attributes = Vec::with_capacity(#attribute_count);
+                let location: ::ash::vk::uint32_t = 0;
+                #(#attributes)*
+                attributes
+            }
+        }
+    })
+}
+
+fn field_options(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions {
+        format: None,
+        location: None,
+    };
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("vertex")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "Expected #[vertex(format = \"...\")] or #[vertex(location = N)]",
+                ))
+            }
+        };
+        for nested in &list.nested {
+            match *nested {
+                NestedMeta::Meta(Meta::NameValue(ref value)) if value.path.is_ident("format") => {
+                    match value.lit {
+                        Lit::Str(ref format) => options.format = Some(format.parse()?),
+                        ref lit => {
+                            return Err(syn::Error::new_spanned(
+                                lit,
+                                "Expected the name of a vk::Format variant as a string",
+                            ))
+                        }
+                    }
+                }
+                NestedMeta::Meta(Meta::NameValue(ref value)) if value.path.is_ident("location") => {
+                    match value.lit {
+                        Lit::Int(ref location) => options.location = Some(location.base10_parse()?),
+                        ref lit => return Err(syn::Error::new_spanned(lit, "Expected a location")),
+                    }
+                }
+                ref nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "Expected format = \"...\" or location = N",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(options)
+}
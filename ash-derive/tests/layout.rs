@@ -0,0 +1,67 @@
+extern crate ash;
+#[macro_use]
+extern crate ash_derive;
+
+use ash::layout::{Std140, Std430};
+
+#[repr(C)]
+#[derive(Clone, Copy, Std140, Std430)]
+struct Light {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Std140)]
+struct Std140Block {
+    model: [[f32; 4]; 4],
+    // `float weights[4]`, with a stride of 16 bytes.
+    weights: [[f32; 4]; 4],
+    lights: [Light; 2],
+    count: u32,
+    // Padding has to be scalars, because `[u32; 3]` is a `uvec3` aligned to 16 bytes.
+    _pad0: u32,
+    _pad1: u32,
+    _pad2: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Std430)]
+struct Std430Block {
+    #[layout(array)]
+    weights: [f32; 5],
+    count: u32,
+    offset: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Std140, Std430)]
+struct Nested {
+    scale: f32,
+    _pad0: f32,
+    _pad1: f32,
+    _pad2: f32,
+    light: Light,
+}
+
+#[test]
+fn vectors_and_structs() {
+    assert_eq!(<Light as Std140>::ALIGN, 16);
+    assert_eq!(<Light as Std140>::SIZE, 32);
+    assert_eq!(<Light as Std430>::SIZE, 32);
+    assert_eq!(<Nested as Std140>::SIZE, 48);
+    assert_eq!(<Nested as Std430>::SIZE, 48);
+}
+
+#[test]
+fn std140_arrays() {
+    assert_eq!(<Std140Block as Std140>::ALIGN, 16);
+    assert_eq!(<Std140Block as Std140>::SIZE, 64 + 64 + 64 + 16);
+}
+
+#[test]
+fn std430_scalar_arrays() {
+    assert_eq!(<Std430Block as Std430>::ALIGN, 8);
+    assert_eq!(<Std430Block as Std430>::SIZE, 32);
+}
@@ -0,0 +1,101 @@
+extern crate ash;
+#[macro_use]
+extern crate ash_derive;
+
+use ash::vertex::Vertex;
+use ash::vk;
+use ash::vk::Format::*;
+use std::mem::{offset_of, size_of};
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Simple {
+    pos: [f32; 3],
+    uv: [f32; 2],
+    #[vertex(format = "R8g8b8a8Unorm")]
+    color: [u8; 4],
+    index: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Locations {
+    pos: [f32; 2],
+    #[vertex(location = 4)]
+    normal: [f32; 3],
+    tangent: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Vertex)]
+struct Doubles {
+    pos: [f64; 3],
+    weight: f64,
+    color: [f64; 4],
+    id: [i32; 2],
+}
+
+fn attributes<V: Vertex>(binding: vk::uint32_t) -> Vec<(u32, u32, vk::Format, usize)> {
+    V::attribute_descriptions(binding)
+        .into_iter()
+        .map(|attribute| {
+            (
+                attribute.location,
+                attribute.binding,
+                attribute.format,
+                attribute.offset as usize,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn binding_description() {
+    let binding = Simple::binding_description(3, vk::VertexInputRate::Instance);
+    assert_eq!(binding.binding, 3);
+    assert_eq!(binding.stride as usize, size_of::<Simple>());
+    assert_eq!(binding.stride, 12 + 8 + 4 + 4);
+    assert_eq!(binding.input_rate, vk::VertexInputRate::Instance);
+    assert_eq!(
+        Doubles::binding_description(0, vk::VertexInputRate::Vertex).stride as usize,
+        size_of::<Doubles>()
+    );
+}
+
+#[test]
+fn inferred_and_overridden_formats() {
+    assert_eq!(
+        attributes::<Simple>(1),
+        vec![
+            (0, 1, R32g32b32Sfloat, offset_of!(Simple, pos)),
+            (1, 1, R32g32Sfloat, offset_of!(Simple, uv)),
+            (2, 1, R8g8b8a8Unorm, offset_of!(Simple, color)),
+            (3, 1, R32Uint, offset_of!(Simple, index)),
+        ]
+    );
+}
+
+#[test]
+fn explicit_location() {
+    assert_eq!(
+        attributes::<Locations>(0),
+        vec![
+            (0, 0, R32g32Sfloat, offset_of!(Locations, pos)),
+            (4, 0, R32g32b32Sfloat, offset_of!(Locations, normal)),
+            (5, 0, R32g32b32Sfloat, offset_of!(Locations, tangent)),
+        ]
+    );
+}
+
+#[test]
+fn double_locations() {
+    assert_eq!(
+        attributes::<Doubles>(0),
+        vec![
+            (0, 0, R64g64b64Sfloat, offset_of!(Doubles, pos)),
+            (2, 0, R64Sfloat, offset_of!(Doubles, weight)),
+            (3, 0, R64g64b64a64Sfloat, offset_of!(Doubles, color)),
+            (5, 0, R32g32Sint, offset_of!(Doubles, id)),
+        ]
+    );
+}
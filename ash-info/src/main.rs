@@ -0,0 +1,61 @@
+//! Prints everything Vulkan reports about each physical device.
+//!
+//! ```text
+//! ash-info          readable text
+//! ash-info --json   a JSON array with one object per device
+//! ```
+extern crate ash;
+
+use ash::Entry;
+use ash::builder::InstanceBuilder;
+use ash::report::DeviceReport;
+use ash::version::{InstanceV1_0, V1_0};
+use std::env;
+use std::ffi::CString;
+use std::process;
+
+fn main() {
+    let json = env::args().skip(1).any(|arg| arg == "--json");
+    let entry: Entry<V1_0> = match Entry::new() {
+        Ok(entry) => entry,
+        Err(err) => {
+            eprintln!("Unable to load Vulkan: {:?}", err);
+            process::exit(1);
+        }
+    };
+    let app_name = CString::new("ash-info").unwrap();
+    let (instance, _) = match unsafe {
+        InstanceBuilder::new()
+            .application_name(&app_name)
+            .build(&entry, None)
+    } {
+        Ok(instance) => instance,
+        Err(err) => {
+            eprintln!("Unable to create an instance: {}", err);
+            process::exit(1);
+        }
+    };
+    let reports = DeviceReport::query_all(&instance);
+    unsafe {
+        instance.destroy_instance(None);
+    }
+    let reports = match reports {
+        Ok(reports) => reports,
+        Err(err) => {
+            eprintln!("Unable to query the physical devices: {:?}", err);
+            process::exit(1);
+        }
+    };
+    if json {
+        let reports: Vec<String> = reports
+            .iter()
+            .map(|report| report.to_json().trim().to_owned())
+            .collect();
+        println!("[\n{}\n]", reports.join(",\n"));
+    } else {
+        for (index, report) in reports.iter().enumerate() {
+            println!("Device {}: {}", index, report.device_name());
+            println!("{}", report);
+        }
+    }
+}
@@ -1,119 +1,575 @@
-#![allow(dead_code)]
-use vk;
+//! Routes the host allocations of the driver through a Rust allocator and counts them.
+//!
+//! ```rust,ignore
+//! let host_allocator = HostAllocationCallbacks::new();
+//! let instance = entry.create_instance(&create_info, Some(host_allocator.callbacks()))?;
+//! // ...
+//! println!("{:?}", host_allocator.stats().scope(vk::SystemAllocationScope::Instance));
+//! ```
+use std::alloc::{self, GlobalAlloc, Layout};
+use std::mem;
 use std::ptr;
-pub trait VkAllocation {
-    unsafe extern "system" fn allocation(
-        *mut (),
-        vk::size_t,
-        vk::size_t,
-        vk::SystemAllocationScope,
-    ) -> *mut ();
-    unsafe extern "system" fn reallocation(
-        *mut vk::c_void,
-        *mut vk::c_void,
-        vk::size_t,
-        vk::size_t,
-        vk::SystemAllocationScope,
-    ) -> *mut vk::c_void;
-    unsafe extern "system" fn free(*mut vk::c_void, *mut vk::c_void);
-    unsafe extern "system" fn internal_allocation(
-        *mut vk::c_void,
-        vk::size_t,
-        vk::InternalAllocationType,
-        vk::SystemAllocationScope,
-    );
-    unsafe extern "system" fn internal_free(
-        *mut vk::c_void,
-        vk::size_t,
-        vk::InternalAllocationType,
-        vk::SystemAllocationScope,
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vk;
+
+/// Forwards to the global allocator of the program, which is the system allocator unless
+/// `#[global_allocator]` replaces it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustAllocator;
+
+unsafe impl GlobalAlloc for RustAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        alloc::alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        alloc::dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        alloc::realloc(ptr, layout, new_size)
+    }
+}
+
+const SCOPE_COUNT: usize = 5;
+
+const SCOPES: [vk::SystemAllocationScope; SCOPE_COUNT] = [
+    vk::SystemAllocationScope::Command,
+    vk::SystemAllocationScope::Object,
+    vk::SystemAllocationScope::Cache,
+    vk::SystemAllocationScope::Device,
+    vk::SystemAllocationScope::Instance,
+];
+
+/// Allocations of one `vk::SystemAllocationScope`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScopeStats {
+    /// Bytes that are currently allocated.
+    pub bytes: usize,
+    /// The highest value `bytes` has reached.
+    pub peak_bytes: usize,
+    /// Allocations that are currently alive.
+    pub allocations: usize,
+    /// Every allocation so far, including reallocations.
+    pub total_allocations: usize,
+    /// Bytes of executable memory that the driver allocated itself and reported through the
+    /// internal allocation notification.
+    pub internal_bytes: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostAllocationStats {
+    pub scopes: [ScopeStats; SCOPE_COUNT],
+    /// Allocations that failed, including those with an invalid alignment.
+    pub failed_allocations: usize,
+}
+
+impl HostAllocationStats {
+    pub fn scope(&self, scope: vk::SystemAllocationScope) -> ScopeStats {
+        self.scopes
+            .get(scope as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The sum over every scope. `peak_bytes` is the sum of the peaks of each scope, which can be
+    /// more than the highest total that was ever allocated at once.
+    pub fn total(&self) -> ScopeStats {
+        self.scopes
+            .iter()
+            .fold(ScopeStats::default(), |total, scope| ScopeStats {
+                bytes: total.bytes + scope.bytes,
+                peak_bytes: total.peak_bytes + scope.peak_bytes,
+                allocations: total.allocations + scope.allocations,
+                total_allocations: total.total_allocations + scope.total_allocations,
+                internal_bytes: total.internal_bytes + scope.internal_bytes,
+            })
+    }
+}
+
+#[derive(Default)]
+struct ScopeCounters {
+    bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    allocations: AtomicUsize,
+    total_allocations: AtomicUsize,
+    internal_bytes: AtomicUsize,
+}
+
+impl ScopeCounters {
+    fn add(&self, size: usize) {
+        let bytes = self.bytes.fetch_add(size, Ordering::Relaxed) + size;
+        let mut peak = self.peak_bytes.load(Ordering::Relaxed);
+        while bytes > peak {
+            match self.peak_bytes.compare_exchange_weak(
+                peak,
+                bytes,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => peak = current,
+            }
+        }
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        self.total_allocations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn remove(&self, size: usize) {
+        saturating_sub(&self.bytes, size);
+        saturating_sub(&self.allocations, 1);
+    }
+
+    fn stats(&self) -> ScopeStats {
+        ScopeStats {
+            bytes: self.bytes.load(Ordering::Relaxed),
+            peak_bytes: self.peak_bytes.load(Ordering::Relaxed),
+            allocations: self.allocations.load(Ordering::Relaxed),
+            total_allocations: self.total_allocations.load(Ordering::Relaxed),
+            internal_bytes: self.internal_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Subtracts without wrapping around, so that an unmatched free can't make a counter huge.
+fn saturating_sub(counter: &AtomicUsize, value: usize) {
+    let mut current = counter.load(Ordering::Relaxed);
+    loop {
+        match counter.compare_exchange_weak(
+            current,
+            current.saturating_sub(value),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => break,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+struct Inner<A> {
+    allocator: A,
+    callbacks: vk::AllocationCallbacks,
+    scopes: [ScopeCounters; SCOPE_COUNT],
+    failed_allocations: AtomicUsize,
+}
+
+impl<A> Inner<A> {
+    /// The counters of `scope`. The callbacks must not panic, so a value that isn't a known
+    /// scope is counted as `Instance` instead.
+    fn counters(&self, scope: vk::SystemAllocationScope) -> &ScopeCounters {
+        self.scopes
+            .get(scope as usize)
+            .unwrap_or(&self.scopes[vk::SystemAllocationScope::Instance as usize])
+    }
+}
+
+/// Stored in front of every allocation, because the free and reallocation callbacks only get
+/// the pointer.
+#[repr(C)]
+struct Header {
+    size: usize,
+    align: usize,
+    scope: vk::SystemAllocationScope,
+}
+
+/// A `vk::AllocationCallbacks` whose allocations go to `A`.
+///
+/// The callbacks point into this struct, so it has to outlive every object that was created
+/// with them, and the objects have to be destroyed with the same callbacks.
+pub struct HostAllocationCallbacks<A: GlobalAlloc = RustAllocator> {
+    inner: Box<Inner<A>>,
+}
+
+unsafe impl<A: GlobalAlloc + Send + Sync> Send for HostAllocationCallbacks<A> {}
+unsafe impl<A: GlobalAlloc + Send + Sync> Sync for HostAllocationCallbacks<A> {}
+
+impl HostAllocationCallbacks<RustAllocator> {
+    pub fn new() -> HostAllocationCallbacks<RustAllocator> {
+        HostAllocationCallbacks::with_allocator(RustAllocator)
+    }
+}
+
+impl Default for HostAllocationCallbacks<RustAllocator> {
+    fn default() -> HostAllocationCallbacks<RustAllocator> {
+        HostAllocationCallbacks::new()
+    }
+}
+
+impl<A: GlobalAlloc> HostAllocationCallbacks<A> {
+    pub fn with_allocator(allocator: A) -> HostAllocationCallbacks<A> {
+        let mut inner = Box::new(Inner {
+            allocator: allocator,
+            callbacks: vk::AllocationCallbacks {
+                p_user_data: ptr::null_mut(),
+                pfn_allocation: allocation::<A>,
+                pfn_reallocation: reallocation::<A>,
+                pfn_free: free::<A>,
+                pfn_internal_allocation: internal_allocation::<A>,
+                pfn_internal_free: internal_free::<A>,
+            },
+            scopes: Default::default(),
+            failed_allocations: AtomicUsize::new(0),
+        });
+        inner.callbacks.p_user_data = &*inner as *const Inner<A> as *mut vk::c_void;
+        HostAllocationCallbacks { inner: inner }
+    }
+
+    /// The callbacks to pass as `allocation_callbacks`.
+    pub fn callbacks(&self) -> &vk::AllocationCallbacks {
+        &self.inner.callbacks
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.inner.allocator
+    }
+
+    pub fn stats(&self) -> HostAllocationStats {
+        let mut stats = HostAllocationStats::default();
+        for (index, scope) in self.inner.scopes.iter().enumerate() {
+            stats.scopes[index] = scope.stats();
+        }
+        stats.failed_allocations = self.inner.failed_allocations.load(Ordering::Relaxed);
+        stats
+    }
+
+    /// All scopes in the order of their values, to go with `HostAllocationStats::scopes`.
+    pub fn scopes() -> &'static [vk::SystemAllocationScope] {
+        &SCOPES
+    }
+}
+
+/// The layout of the whole block and the offset of the user pointer in it.
+fn block_layout(size: usize, align: usize) -> Option<(Layout, usize)> {
+    if size == 0 || !align.is_power_of_two() {
+        return None;
+    }
+    let align = align.max(mem::align_of::<Header>());
+    let header_size = mem::size_of::<Header>();
+    let offset = (header_size + align - 1) / align * align;
+    let total = offset.checked_add(size)?;
+    Layout::from_size_align(total, align)
+        .ok()
+        .map(|layout| (layout, offset))
+}
+
+unsafe fn header<'a>(memory: *mut vk::c_void) -> &'a mut Header {
+    &mut *((memory as *mut u8).offset(-(mem::size_of::<Header>() as isize)) as *mut Header)
+}
+
+unsafe fn allocate<A: GlobalAlloc>(
+    inner: &Inner<A>,
+    size: usize,
+    align: usize,
+    scope: vk::SystemAllocationScope,
+) -> *mut vk::c_void {
+    let (layout, offset) = match block_layout(size, align) {
+        Some(layout) => layout,
+        None => {
+            inner.failed_allocations.fetch_add(1, Ordering::Relaxed);
+            return ptr::null_mut();
+        }
+    };
+    let block = inner.allocator.alloc(layout);
+    if block.is_null() {
+        inner.failed_allocations.fetch_add(1, Ordering::Relaxed);
+        return ptr::null_mut();
+    }
+    let memory = block.offset(offset as isize) as *mut vk::c_void;
+    *header(memory) = Header {
+        size: size,
+        align: align,
+        scope: scope,
+    };
+    inner.counters(scope).add(size);
+    memory
+}
+
+/// Frees `memory`. A corrupted header can't be reported from a callback, because unwinding out of
+/// it aborts, so the memory is leaked instead.
+unsafe fn deallocate<A: GlobalAlloc>(inner: &Inner<A>, memory: *mut vk::c_void) {
+    let (size, align, scope) = {
+        let header = header(memory);
+        (header.size, header.align, header.scope)
+    };
+    let (layout, offset) = match block_layout(size, align) {
+        Some(layout) => layout,
+        None => return,
+    };
+    inner.counters(scope).remove(size);
+    inner
+        .allocator
+        .dealloc((memory as *mut u8).offset(-(offset as isize)), layout);
+}
+
+unsafe extern "system" fn allocation<A: GlobalAlloc>(
+    user_data: *mut vk::c_void,
+    size: vk::size_t,
+    align: vk::size_t,
+    scope: vk::SystemAllocationScope,
+) -> *mut vk::c_void {
+    let inner = &*(user_data as *const Inner<A>);
+    allocate(inner, size, align, scope)
+}
+
+unsafe extern "system" fn reallocation<A: GlobalAlloc>(
+    user_data: *mut vk::c_void,
+    original: *mut vk::c_void,
+    size: vk::size_t,
+    align: vk::size_t,
+    scope: vk::SystemAllocationScope,
+) -> *mut vk::c_void {
+    let inner = &*(user_data as *const Inner<A>);
+    if original.is_null() {
+        return allocate(inner, size, align, scope);
+    }
+    if size == 0 {
+        deallocate(inner, original);
+        return ptr::null_mut();
+    }
+    let (old_size, old_align, old_scope) = {
+        let header = header(original);
+        (header.size, header.align, header.scope)
+    };
+    // The spec requires the same alignment as the original allocation. If a driver asks for a
+    // different one, fall back to a fresh allocation and a copy.
+    if old_align != align {
+        let memory = allocate(inner, size, align, scope);
+        if !memory.is_null() {
+            ptr::copy_nonoverlapping(original as *const u8, memory as *mut u8, old_size.min(size));
+            deallocate(inner, original);
+        }
+        return memory;
+    }
+    let (old_layout, offset) = match block_layout(old_size, old_align) {
+        Some(layout) => layout,
+        None => {
+            // A corrupted header, which fails like an allocation instead of panicking.
+            inner.failed_allocations.fetch_add(1, Ordering::Relaxed);
+            return ptr::null_mut();
+        }
+    };
+    let new_total = match offset.checked_add(size) {
+        Some(new_total) => new_total,
+        None => {
+            inner.failed_allocations.fetch_add(1, Ordering::Relaxed);
+            return ptr::null_mut();
+        }
+    };
+    let block = inner.allocator.realloc(
+        (original as *mut u8).offset(-(offset as isize)),
+        old_layout,
+        new_total,
     );
-    fn create_allocation_callback() -> Option<vk::AllocationCallbacks> {
-        let alloc = vk::AllocationCallbacks {
-            p_user_data: ptr::null_mut(),
-            pfn_allocation: Self::allocation,
-            pfn_reallocation: Self::reallocation,
-            pfn_free: Self::free,
-            pfn_internal_allocation: Self::internal_allocation,
-            pfn_internal_free: Self::internal_free,
-        };
-        Some(alloc)
-    }
-}
-
-pub struct DefaultAllocatorCallback;
-pub struct TestAlloc;
-
-impl VkAllocation for TestAlloc {
-    unsafe extern "system" fn allocation(
-        _: *mut (),
-        _: vk::size_t,
-        _: vk::size_t,
-        _: vk::SystemAllocationScope,
-    ) -> *mut () {
-        ptr::null_mut()
-    }
-
-    unsafe extern "system" fn reallocation(
-        _: *mut vk::c_void,
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::size_t,
-        _: vk::SystemAllocationScope,
+    if block.is_null() {
+        // The original allocation is left untouched, as the spec requires.
+        inner.failed_allocations.fetch_add(1, Ordering::Relaxed);
+        return ptr::null_mut();
+    }
+    inner.counters(old_scope).remove(old_size);
+    let memory = block.offset(offset as isize) as *mut vk::c_void;
+    *header(memory) = Header {
+        size: size,
+        align: align,
+        scope: scope,
+    };
+    inner.counters(scope).add(size);
+    memory
+}
+
+unsafe extern "system" fn free<A: GlobalAlloc>(
+    user_data: *mut vk::c_void,
+    memory: *mut vk::c_void,
+) {
+    if memory.is_null() {
+        return;
+    }
+    let inner = &*(user_data as *const Inner<A>);
+    deallocate(inner, memory);
+}
+
+unsafe extern "system" fn internal_allocation<A: GlobalAlloc>(
+    user_data: *mut vk::c_void,
+    size: vk::size_t,
+    _: vk::InternalAllocationType,
+    scope: vk::SystemAllocationScope,
+) {
+    let inner = &*(user_data as *const Inner<A>);
+    inner
+        .counters(scope)
+        .internal_bytes
+        .fetch_add(size, Ordering::Relaxed);
+}
+
+unsafe extern "system" fn internal_free<A: GlobalAlloc>(
+    user_data: *mut vk::c_void,
+    size: vk::size_t,
+    _: vk::InternalAllocationType,
+    scope: vk::SystemAllocationScope,
+) {
+    let inner = &*(user_data as *const Inner<A>);
+    saturating_sub(&inner.counters(scope).internal_bytes, size);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn allocate(
+        host: &HostAllocationCallbacks,
+        size: usize,
+        align: usize,
+        scope: vk::SystemAllocationScope,
     ) -> *mut vk::c_void {
-        ptr::null_mut()
-    }
-    unsafe extern "system" fn free(_: *mut vk::c_void, _: *mut vk::c_void) {}
-    unsafe extern "system" fn internal_allocation(
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::InternalAllocationType,
-        _: vk::SystemAllocationScope,
-    ) {
-    }
-    unsafe extern "system" fn internal_free(
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::InternalAllocationType,
-        _: vk::SystemAllocationScope,
-    ) {
-    }
-}
-impl VkAllocation for DefaultAllocatorCallback {
-    unsafe extern "system" fn allocation(
-        _: *mut (),
-        _: vk::size_t,
-        _: vk::size_t,
-        _: vk::SystemAllocationScope,
-    ) -> *mut () {
-        ptr::null_mut()
-    }
-
-    unsafe extern "system" fn reallocation(
-        _: *mut vk::c_void,
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::size_t,
-        _: vk::SystemAllocationScope,
+        let callbacks = host.callbacks();
+        (callbacks.pfn_allocation)(callbacks.p_user_data, size, align, scope)
+    }
+
+    unsafe fn reallocate(
+        host: &HostAllocationCallbacks,
+        original: *mut vk::c_void,
+        size: usize,
+        align: usize,
+        scope: vk::SystemAllocationScope,
     ) -> *mut vk::c_void {
-        ptr::null_mut()
-    }
-    unsafe extern "system" fn free(_: *mut vk::c_void, _: *mut vk::c_void) {}
-    unsafe extern "system" fn internal_allocation(
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::InternalAllocationType,
-        _: vk::SystemAllocationScope,
-    ) {
-    }
-    unsafe extern "system" fn internal_free(
-        _: *mut vk::c_void,
-        _: vk::size_t,
-        _: vk::InternalAllocationType,
-        _: vk::SystemAllocationScope,
-    ) {
-    }
-    fn create_allocation_callback() -> Option<vk::AllocationCallbacks> {
-        None
+        let callbacks = host.callbacks();
+        (callbacks.pfn_reallocation)(callbacks.p_user_data, original, size, align, scope)
+    }
+
+    unsafe fn free(host: &HostAllocationCallbacks, memory: *mut vk::c_void) {
+        let callbacks = host.callbacks();
+        (callbacks.pfn_free)(callbacks.p_user_data, memory)
+    }
+
+    #[test]
+    fn alignment() {
+        let host = HostAllocationCallbacks::new();
+        for &align in &[1, 2, 8, 16, 64, 256, 4096] {
+            unsafe {
+                let memory = allocate(&host, 24, align, vk::SystemAllocationScope::Object);
+                assert!(!memory.is_null());
+                assert_eq!(memory as usize % align, 0);
+                ptr::write_bytes(memory as *mut u8, 0xab, 24);
+                free(&host, memory);
+            }
+        }
+        assert_eq!(
+            host.stats().total(),
+            ScopeStats {
+                total_allocations: 7,
+                peak_bytes: 24,
+                ..ScopeStats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn scope_counts() {
+        let host = HostAllocationCallbacks::new();
+        let object = vk::SystemAllocationScope::Object;
+        let command = vk::SystemAllocationScope::Command;
+        unsafe {
+            let first = allocate(&host, 100, 8, object);
+            let second = allocate(&host, 50, 8, command);
+            assert_eq!(
+                host.stats().scope(object),
+                ScopeStats {
+                    bytes: 100,
+                    peak_bytes: 100,
+                    allocations: 1,
+                    total_allocations: 1,
+                    internal_bytes: 0,
+                }
+            );
+
+            let first = reallocate(&host, first, 300, 8, object);
+            assert!(!first.is_null());
+            let first = reallocate(&host, first, 200, 8, object);
+            assert_eq!(
+                host.stats().scope(object),
+                ScopeStats {
+                    bytes: 200,
+                    peak_bytes: 300,
+                    allocations: 1,
+                    total_allocations: 3,
+                    internal_bytes: 0,
+                }
+            );
+
+            free(&host, first);
+            free(&host, ptr::null_mut());
+            assert_eq!(
+                host.stats().scope(object),
+                ScopeStats {
+                    bytes: 0,
+                    peak_bytes: 300,
+                    allocations: 0,
+                    total_allocations: 3,
+                    internal_bytes: 0,
+                }
+            );
+            assert_eq!(
+                host.stats().scope(command),
+                ScopeStats {
+                    bytes: 50,
+                    peak_bytes: 50,
+                    allocations: 1,
+                    total_allocations: 1,
+                    internal_bytes: 0,
+                }
+            );
+
+            // A reallocation to a size of 0 frees, and one of null allocates.
+            assert!(reallocate(&host, second, 0, 8, command).is_null());
+            let third = reallocate(&host, ptr::null_mut(), 10, 8, command);
+            assert_eq!(host.stats().scope(command).bytes, 10);
+            free(&host, third);
+        }
+        assert_eq!(host.stats().total().bytes, 0);
+        assert_eq!(host.stats().failed_allocations, 0);
+    }
+
+    #[test]
+    fn reallocation_with_other_alignment() {
+        let host = HostAllocationCallbacks::new();
+        let scope = vk::SystemAllocationScope::Device;
+        unsafe {
+            let memory = allocate(&host, 16, 8, scope) as *mut u8;
+            for i in 0..16 {
+                *memory.offset(i) = i as u8;
+            }
+            let memory = reallocate(&host, memory as *mut vk::c_void, 32, 256, scope) as *mut u8;
+            assert!(!memory.is_null());
+            assert_eq!(memory as usize % 256, 0);
+            for i in 0..16 {
+                assert_eq!(*memory.offset(i), i as u8);
+            }
+            let stats = host.stats().scope(scope);
+            assert_eq!((stats.bytes, stats.allocations), (32, 1));
+            free(&host, memory as *mut vk::c_void);
+        }
+        assert_eq!(host.stats().scope(scope).bytes, 0);
+    }
+
+    #[test]
+    fn invalid_alignment() {
+        let host = HostAllocationCallbacks::new();
+        let scope = vk::SystemAllocationScope::Cache;
+        unsafe {
+            assert!(allocate(&host, 16, 3, scope).is_null());
+            assert!(allocate(&host, 16, 0, scope).is_null());
+
+            // A failed reallocation leaves the original allocation untouched.
+            let memory = allocate(&host, 16, 8, scope);
+            *(memory as *mut u8) = 7;
+            assert!(reallocate(&host, memory, 32, 12, scope).is_null());
+            assert_eq!(*(memory as *mut u8), 7);
+            assert_eq!(host.stats().scope(scope).bytes, 16);
+            free(&host, memory);
+        }
+        let stats = host.stats();
+        assert_eq!(stats.failed_allocations, 3);
+        assert_eq!(stats.scope(scope).bytes, 0);
+        assert_eq!(stats.scope(scope).total_allocations, 1);
     }
 }
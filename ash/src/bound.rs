@@ -0,0 +1,304 @@
+//! Handles that borrow the device that created them.
+//!
+//! `Device::scope` gives a device a lifetime `'id` that no other device has. A `Buffer<'d, 'id>`
+//! borrows that `Device<'id, D>` for `'d` and carries its `'id`, so:
+//!
+//! * passing it to a different device doesn't compile, because the `'id`s don't match, and
+//!   neither does passing it to a device that isn't bound;
+//! * destroying the device while its objects are alive doesn't compile, because
+//!   `Device::destroy` takes the device by value, and `DeviceV1_0::destroy_device` can't be
+//!   called on a bound device at all;
+//! * using an object after it was destroyed doesn't compile, because destroying it takes it by
+//!   value.
+//!
+//! Unlike the wrappers in `ash::owned`, these are only a handle, and nothing is destroyed on drop.
+//!
+//! The `DeviceV1_0` methods that destroy one of these objects take `impl IntoHandle`, so they
+//! accept the wrapper by value or the raw handle. The methods that take a single object
+//! otherwise, including the ones that record commands, take `impl AsHandle`, so they accept a
+//! reference to the wrapper or the raw handle. Handles in slices and in create infos, command
+//! buffers, queues and descriptor sets are still raw, so use `handle()` there. Raw handles are
+//! never checked, and neither is a clone of the device that is destroyed outside of the scope.
+//!
+//! ```rust,no_run
+//! # extern crate ash;
+//! # use ash::{bound, vk};
+//! # use ash::version::V1_0;
+//! # unsafe fn f(device: ash::Device<V1_0>, buffer_info: &vk::BufferCreateInfo,
+//! #             allocate_info: &vk::MemoryAllocateInfo) -> ash::prelude::VkResult<()> {
+//! use ash::version::DeviceV1_0;
+//!
+//! bound::Device::scope(device, |device| {
+//!     let buffer = bound::Buffer::create(&device, buffer_info, None)?;
+//!     let memory = bound::DeviceMemory::allocate(&device, allocate_info, None)?;
+//!     device.bind_buffer_memory(&buffer, &memory, 0)?;
+//!     device.destroy_buffer(buffer, None);
+//!     device.free_memory(memory, None);
+//!     device.destroy(None);
+//!     Ok(())
+//! })
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! Destroying the device while one of its objects is alive doesn't compile:
+//!
+//! ```rust,compile_fail,E0505
+//! # extern crate ash;
+//! # use ash::{bound, vk};
+//! # use ash::version::{DeviceV1_0, V1_0};
+//! # unsafe fn f(device: ash::Device<V1_0>, allocate_info: &vk::MemoryAllocateInfo) {
+//! bound::Device::scope(device, |device| {
+//!     let memory = bound::DeviceMemory::allocate(&device, allocate_info, None).unwrap();
+//!     device.destroy(None);
+//!     let _ = memory.handle();
+//! })
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! ```rust,compile_fail,E0277
+//! # extern crate ash;
+//! # use ash::{bound, vk};
+//! # use ash::version::{DeviceV1_0, V1_0};
+//! # unsafe fn f(device: ash::Device<V1_0>) {
+//! bound::Device::scope(device, |device| device.destroy_device(None))
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! Neither does passing an object to another device:
+//!
+//! ```rust,compile_fail,E0521
+//! # extern crate ash;
+//! # use ash::{bound, vk};
+//! # use ash::version::{DeviceV1_0, V1_0};
+//! # unsafe fn f(a: ash::Device<V1_0>, b: ash::Device<V1_0>, info: &vk::FenceCreateInfo) {
+//! bound::Device::scope(a, |a| {
+//!     bound::Device::scope(b, |b| {
+//!         let fence = bound::Fence::create(&a, info, None).unwrap();
+//!         b.destroy_fence(fence, None);
+//!     })
+//! })
+//! # }
+//! # fn main() {}
+//! ```
+//!
+//! ```rust,compile_fail,E0277
+//! # extern crate ash;
+//! # use ash::{bound, vk};
+//! # use ash::version::{DeviceV1_0, V1_0};
+//! # unsafe fn f(device: ash::Device<V1_0>, info: &vk::FenceCreateInfo) {
+//! let unbound = device.clone();
+//! bound::Device::scope(device, |device| {
+//!     let fence = bound::Fence::create(&device, info, None).unwrap();
+//!     unbound.destroy_fence(fence, None);
+//! })
+//! # }
+//! # fn main() {}
+//! ```
+use device::DeviceV1_0;
+use prelude::*;
+use std::marker::PhantomData;
+use version::FunctionPointers;
+use vk;
+
+/// A handle that a `DeviceV1_0` method of `D` uses but doesn't destroy.
+pub trait AsHandle<H, D: ?Sized> {
+    fn as_handle(self) -> H;
+}
+
+/// A handle that a `DeviceV1_0` method of `D` destroys.
+pub trait IntoHandle<H, D: ?Sized> {
+    fn into_handle(self) -> H;
+}
+
+/// The devices that `DeviceV1_0::destroy_device` destroys. A bound `Device` isn't one of them,
+/// because its objects borrow it, and it's destroyed with `Device::destroy` instead.
+pub trait Unbound {}
+
+impl<V: FunctionPointers> Unbound for ::Device<V> {}
+
+/// A device with a lifetime `'id` that no other device has.
+pub struct Device<'id, D> {
+    device: D,
+    // Invariant, so that `'id` can't be shortened or lengthened to the `'id` of another device.
+    _id: PhantomData<fn(&'id ()) -> &'id ()>,
+}
+
+impl<D: DeviceV1_0> Device<'static, D> {
+    /// Calls `f` with `device`, bound to a lifetime that only lives as long as the call.
+    pub fn scope<R, F>(device: D, f: F) -> R
+    where
+        F: for<'id> FnOnce(Device<'id, D>) -> R,
+    {
+        f(Device {
+            device: device,
+            _id: PhantomData,
+        })
+    }
+}
+
+impl<'id, D: DeviceV1_0> Device<'id, D> {
+    /// Destroys the device, which doesn't compile while one of its objects is alive.
+    pub unsafe fn destroy(self, allocation_callbacks: Option<&vk::AllocationCallbacks>)
+    where
+        D: Unbound,
+    {
+        self.device.destroy_device(allocation_callbacks);
+    }
+}
+
+impl<'id, D: DeviceV1_0> DeviceV1_0 for Device<'id, D> {
+    fn handle(&self) -> vk::Device {
+        self.device.handle()
+    }
+
+    fn fp_v1_0(&self) -> &vk::DeviceFnV1_0 {
+        self.device.fp_v1_0()
+    }
+}
+
+macro_rules! bound_handle {
+    ($name: ident) => {
+        #[derive(Debug)]
+        pub struct $name<'d, 'id: 'd> {
+            handle: vk::$name,
+            _device: PhantomData<&'d Device<'id, ()>>,
+        }
+
+        impl<'d, 'id> $name<'d, 'id> {
+            /// Binds `handle`, which was created from `device`, to `device`.
+            pub unsafe fn from_raw<D: DeviceV1_0>(
+                device: &'d Device<'id, D>,
+                handle: vk::$name,
+            ) -> $name<'d, 'id> {
+                let _ = device;
+                $name {
+                    handle: handle,
+                    _device: PhantomData,
+                }
+            }
+
+            pub fn handle(&self) -> vk::$name {
+                self.handle
+            }
+        }
+
+        impl<'a, 'd, 'id, D> AsHandle<vk::$name, Device<'id, D>> for &'a $name<'d, 'id> {
+            fn as_handle(self) -> vk::$name {
+                self.handle
+            }
+        }
+
+        impl<'d, 'id, D> IntoHandle<vk::$name, Device<'id, D>> for $name<'d, 'id> {
+            fn into_handle(self) -> vk::$name {
+                self.handle
+            }
+        }
+
+        impl<D: ?Sized> AsHandle<vk::$name, D> for vk::$name {
+            fn as_handle(self) -> vk::$name {
+                self
+            }
+        }
+
+        impl<D: ?Sized> IntoHandle<vk::$name, D> for vk::$name {
+            fn into_handle(self) -> vk::$name {
+                self
+            }
+        }
+    };
+    ($name: ident, $method: ident = $create: ident($create_info: ident)) => {
+        bound_handle!($name);
+
+        impl<'d, 'id> $name<'d, 'id> {
+            pub unsafe fn $method<D: DeviceV1_0>(
+                device: &'d Device<'id, D>,
+                create_info: &vk::$create_info,
+                allocation_callbacks: Option<&vk::AllocationCallbacks>,
+            ) -> VkResult<$name<'d, 'id>> {
+                let handle = device.$create(create_info, allocation_callbacks)?;
+                Ok($name::from_raw(device, handle))
+            }
+        }
+    };
+}
+
+bound_handle!(Buffer, create = create_buffer(BufferCreateInfo));
+bound_handle!(BufferView, create = create_buffer_view(BufferViewCreateInfo));
+bound_handle!(CommandPool, create = create_command_pool(CommandPoolCreateInfo));
+bound_handle!(DescriptorPool, create = create_descriptor_pool(DescriptorPoolCreateInfo));
+bound_handle!(
+    DescriptorSetLayout,
+    create = create_descriptor_set_layout(DescriptorSetLayoutCreateInfo)
+);
+bound_handle!(DeviceMemory, allocate = allocate_memory(MemoryAllocateInfo));
+bound_handle!(Event, create = create_event(EventCreateInfo));
+bound_handle!(Fence, create = create_fence(FenceCreateInfo));
+bound_handle!(Framebuffer, create = create_framebuffer(FramebufferCreateInfo));
+bound_handle!(Image, create = create_image(ImageCreateInfo));
+bound_handle!(ImageView, create = create_image_view(ImageViewCreateInfo));
+bound_handle!(PipelineCache, create = create_pipeline_cache(PipelineCacheCreateInfo));
+bound_handle!(PipelineLayout, create = create_pipeline_layout(PipelineLayoutCreateInfo));
+bound_handle!(QueryPool, create = create_query_pool(QueryPoolCreateInfo));
+bound_handle!(RenderPass, create = create_render_pass(RenderPassCreateInfo));
+bound_handle!(Sampler, create = create_sampler(SamplerCreateInfo));
+bound_handle!(Semaphore, create = create_semaphore(SemaphoreCreateInfo));
+bound_handle!(ShaderModule, create = create_shader_module(ShaderModuleCreateInfo));
+bound_handle!(Pipeline);
+
+impl<'d, 'id> Pipeline<'d, 'id> {
+    /// If any pipeline fails, the ones that were created are destroyed again.
+    pub unsafe fn create_graphics_pipelines<D, C>(
+        device: &'d Device<'id, D>,
+        pipeline_cache: C,
+        create_infos: &[vk::GraphicsPipelineCreateInfo],
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline<'d, 'id>>>
+    where
+        D: DeviceV1_0,
+        C: AsHandle<vk::PipelineCache, Device<'id, D>>,
+    {
+        let result =
+            device.create_graphics_pipelines(pipeline_cache, create_infos, allocation_callbacks);
+        Pipeline::wrap(device, result, allocation_callbacks)
+    }
+
+    /// If any pipeline fails, the ones that were created are destroyed again.
+    pub unsafe fn create_compute_pipelines<D, C>(
+        device: &'d Device<'id, D>,
+        pipeline_cache: C,
+        create_infos: &[vk::ComputePipelineCreateInfo],
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline<'d, 'id>>>
+    where
+        D: DeviceV1_0,
+        C: AsHandle<vk::PipelineCache, Device<'id, D>>,
+    {
+        let result =
+            device.create_compute_pipelines(pipeline_cache, create_infos, allocation_callbacks);
+        Pipeline::wrap(device, result, allocation_callbacks)
+    }
+
+    unsafe fn wrap<D: DeviceV1_0>(
+        device: &'d Device<'id, D>,
+        result: Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)>,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline<'d, 'id>>> {
+        match result {
+            Ok(pipelines) => Ok(pipelines
+                .into_iter()
+                .map(|handle| Pipeline::from_raw(device, handle))
+                .collect()),
+            Err((pipelines, err_code)) => {
+                for pipeline in pipelines {
+                    if pipeline != vk::Pipeline::null() {
+                        device.destroy_pipeline(pipeline, allocation_callbacks);
+                    }
+                }
+                Err(err_code)
+            }
+        }
+    }
+}
@@ -0,0 +1,652 @@
+//! Builders that check layer and extension names before creating an instance or a device.
+//!
+//! ```rust,ignore
+//! let (instance, names) = InstanceBuilder::new()
+//!     .application_name(&app_name)
+//!     .api_version(vk::Version::new(1, 0, 61))
+//!     .extensions(required_surface_extensions(&window_handle))
+//!     .optional_layer(&validation_layer)
+//!     .optional_extension(DebugReport::name())
+//!     .build(&entry, None)?;
+//! for layer in &names.dropped_layers {
+//!     println!("{:?} is not available", layer);
+//! }
+//!
+//! let (device, queues, _) = DeviceBuilder::new()
+//!     .graphics_queue()
+//!     .present_queue(&surface_loader, surface)
+//!     .extension(Swapchain::name())
+//!     .features(vk::PhysicalDeviceFeatures {
+//!         shader_clip_distance: 1,
+//!         ..Default::default()
+//!     })
+//!     .build(&instance, physical_device, None)?;
+//! let graphics_queue = queues[0].queue;
+//! let present_queue = queues[1].queue;
+//! ```
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::ptr;
+use prelude::*;
+use vk;
+use instance::{DeviceError, Instance};
+use device::Device;
+use entry::InstanceError;
+use extensions::Surface;
+use version::{DeviceV1_0, EntryV1_0, InstanceV1_0};
+
+/// The outcome of checking the names given to a builder.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameReport {
+    pub enabled_layers: Vec<CString>,
+    pub enabled_extensions: Vec<CString>,
+    /// Optional layers that are not available and were left out.
+    pub dropped_layers: Vec<CString>,
+    /// Optional extensions that are not available and were left out.
+    pub dropped_extensions: Vec<CString>,
+}
+
+#[derive(Debug)]
+pub enum InstanceBuilderError {
+    /// Required names that are not available. Nothing was created.
+    MissingNames {
+        layers: Vec<CString>,
+        extensions: Vec<CString>,
+    },
+    /// Enumerating the available layers or extensions failed.
+    VkError(vk::Result),
+    InstanceError(InstanceError),
+}
+
+impl fmt::Display for InstanceBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InstanceBuilderError::{:?}", self)
+    }
+}
+
+impl Error for InstanceBuilderError {
+    fn description(&self) -> &str {
+        "InstanceBuilderError"
+    }
+}
+
+/// Builds an `InstanceCreateInfo` from names and checks them against the layers and extensions
+/// that the loader reports.
+///
+/// Extensions that are provided by one of the enabled layers count as available.
+#[derive(Debug, Clone)]
+pub struct InstanceBuilder<'a> {
+    application_name: Option<&'a CStr>,
+    application_version: vk::Version,
+    engine_name: Option<&'a CStr>,
+    engine_version: vk::Version,
+    api_version: vk::Version,
+    layers: Vec<(&'a CStr, bool)>,
+    extensions: Vec<(&'a CStr, bool)>,
+}
+
+impl<'a> Default for InstanceBuilder<'a> {
+    fn default() -> InstanceBuilder<'a> {
+        InstanceBuilder::new()
+    }
+}
+
+impl<'a> InstanceBuilder<'a> {
+    pub fn new() -> InstanceBuilder<'a> {
+        InstanceBuilder {
+            application_name: None,
+            application_version: vk::Version::default(),
+            engine_name: None,
+            engine_version: vk::Version::default(),
+            api_version: vk::Version::new(1, 0, 0),
+            layers: Vec::new(),
+            extensions: Vec::new(),
+        }
+    }
+
+    pub fn application_name(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.application_name = Some(name);
+        self
+    }
+
+    pub fn application_version(mut self, version: vk::Version) -> InstanceBuilder<'a> {
+        self.application_version = version;
+        self
+    }
+
+    pub fn engine_name(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.engine_name = Some(name);
+        self
+    }
+
+    pub fn engine_version(mut self, version: vk::Version) -> InstanceBuilder<'a> {
+        self.engine_version = version;
+        self
+    }
+
+    pub fn api_version(mut self, version: vk::Version) -> InstanceBuilder<'a> {
+        self.api_version = version;
+        self
+    }
+
+    pub fn layer(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.layers.push((name, true));
+        self
+    }
+
+    /// A layer that is enabled if it is available and dropped otherwise.
+    pub fn optional_layer(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.layers.push((name, false));
+        self
+    }
+
+    pub fn extension(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.extensions.push((name, true));
+        self
+    }
+
+    pub fn extensions<I>(mut self, names: I) -> InstanceBuilder<'a>
+    where
+        I: IntoIterator<Item = &'a CStr>,
+    {
+        self.extensions
+            .extend(names.into_iter().map(|name| (name, true)));
+        self
+    }
+
+    /// An extension that is enabled if it is available and dropped otherwise.
+    pub fn optional_extension(mut self, name: &'a CStr) -> InstanceBuilder<'a> {
+        self.extensions.push((name, false));
+        self
+    }
+
+    /// Checks the names without creating anything.
+    pub fn check<E: EntryV1_0>(&self, entry: &E) -> Result<NameReport, InstanceBuilderError> {
+        let available_layers = entry
+            .enumerate_instance_layer_properties()
+            .map_err(|err| InstanceBuilderError::VkError(err))?;
+        let available_layers: Vec<&CStr> = available_layers
+            .iter()
+            .map(|layer| unsafe { CStr::from_ptr(layer.layer_name.as_ptr()) })
+            .collect();
+        let (enabled_layers, missing_layers, dropped_layers) =
+            sort_names(&self.layers, &available_layers);
+
+        let mut extension_properties = entry
+            .enumerate_instance_extension_properties()
+            .map_err(|err| InstanceBuilderError::VkError(err))?;
+        for layer in &enabled_layers {
+            let layer_extensions = enumerate_layer_extension_properties(entry, layer)
+                .map_err(|err| InstanceBuilderError::VkError(err))?;
+            extension_properties.extend(layer_extensions);
+        }
+        let available_extensions: Vec<&CStr> = extension_properties
+            .iter()
+            .map(|extension| unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) })
+            .collect();
+        let (enabled_extensions, missing_extensions, dropped_extensions) =
+            sort_names(&self.extensions, &available_extensions);
+
+        if !missing_layers.is_empty() || !missing_extensions.is_empty() {
+            return Err(InstanceBuilderError::MissingNames {
+                layers: missing_layers,
+                extensions: missing_extensions,
+            });
+        }
+        Ok(NameReport {
+            enabled_layers: enabled_layers,
+            enabled_extensions: enabled_extensions,
+            dropped_layers: dropped_layers,
+            dropped_extensions: dropped_extensions,
+        })
+    }
+
+    /// Checks the names and creates the instance with every required name and the available
+    /// optional ones.
+    pub unsafe fn build<E: EntryV1_0>(
+        &self,
+        entry: &E,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Result<(Instance<E::Fp>, NameReport), InstanceBuilderError> {
+        let names = self.check(entry)?;
+        let layer_names: Vec<*const vk::c_char> =
+            names.enabled_layers.iter().map(|name| name.as_ptr()).collect();
+        let extension_names: Vec<*const vk::c_char> = names
+            .enabled_extensions
+            .iter()
+            .map(|name| name.as_ptr())
+            .collect();
+        let application_info = vk::ApplicationInfo {
+            s_type: vk::StructureType::ApplicationInfo,
+            p_next: ptr::null(),
+            p_application_name: self.application_name
+                .map(|name| name.as_ptr())
+                .unwrap_or(ptr::null()),
+            application_version: self.application_version.raw(),
+            p_engine_name: self.engine_name
+                .map(|name| name.as_ptr())
+                .unwrap_or(ptr::null()),
+            engine_version: self.engine_version.raw(),
+            api_version: self.api_version.raw(),
+        };
+        let create_info = vk::InstanceCreateInfo {
+            s_type: vk::StructureType::InstanceCreateInfo,
+            p_next: ptr::null(),
+            flags: Default::default(),
+            p_application_info: &application_info,
+            enabled_layer_count: layer_names.len() as u32,
+            pp_enabled_layer_names: layer_names.as_ptr(),
+            enabled_extension_count: extension_names.len() as u32,
+            pp_enabled_extension_names: extension_names.as_ptr(),
+        };
+        let instance = entry
+            .create_instance(&create_info, allocation_callbacks)
+            .map_err(|err| InstanceBuilderError::InstanceError(err))?;
+        Ok((instance, names))
+    }
+}
+
+/// What a queue requested from a `DeviceBuilder` has to be able to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueCapability {
+    Graphics,
+    /// A family with compute but without graphics, for work that runs next to rendering.
+    ComputeOnly,
+    /// A family with transfer but without graphics or compute, usually a dedicated copy engine.
+    TransferOnly,
+    /// A family that can present to the surface.
+    Present(vk::SurfaceKHR),
+}
+
+/// Where a requested queue ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueuePlan {
+    pub capability: QueueCapability,
+    pub family_index: vk::uint32_t,
+    pub queue_index: vk::uint32_t,
+}
+
+/// A requested queue of a created device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedQueue {
+    pub capability: QueueCapability,
+    pub family_index: vk::uint32_t,
+    pub queue_index: vk::uint32_t,
+    pub queue: vk::Queue,
+}
+
+/// The outcome of checking a `DeviceBuilder` against a physical device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevicePlan {
+    /// One entry per requested queue, in the order they were requested.
+    pub queues: Vec<QueuePlan>,
+    /// `enabled_layers` and `dropped_layers` are always empty.
+    pub extensions: NameReport,
+}
+
+#[derive(Debug)]
+pub enum DeviceBuilderError {
+    /// Everything the physical device can't provide. Nothing was created.
+    Unsupported {
+        extensions: Vec<CString>,
+        /// Names of the `vk::PhysicalDeviceFeatures` fields that were requested but aren't
+        /// supported.
+        features: Vec<&'static str>,
+        queues: Vec<QueueCapability>,
+    },
+    /// Enumerating the available extensions failed.
+    VkError(vk::Result),
+    DeviceError(DeviceError),
+}
+
+impl fmt::Display for DeviceBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DeviceBuilderError::{:?}", self)
+    }
+}
+
+impl Error for DeviceBuilderError {
+    fn description(&self) -> &str {
+        "DeviceBuilderError"
+    }
+}
+
+#[derive(Clone, Copy)]
+enum QueueRequest<'a> {
+    Graphics,
+    ComputeOnly,
+    TransferOnly,
+    Present(&'a Surface, vk::SurfaceKHR),
+}
+
+impl<'a> QueueRequest<'a> {
+    fn capability(&self) -> QueueCapability {
+        match *self {
+            QueueRequest::Graphics => QueueCapability::Graphics,
+            QueueRequest::ComputeOnly => QueueCapability::ComputeOnly,
+            QueueRequest::TransferOnly => QueueCapability::TransferOnly,
+            QueueRequest::Present(_, surface) => QueueCapability::Present(surface),
+        }
+    }
+}
+
+/// Builds a `DeviceCreateInfo` from queue requests, extension names and features, and checks
+/// them against what the physical device supports.
+///
+/// Each request gets its own queue while its family has queues left, after that requests share
+/// the queues of the family. A graphics queue prefers a family that can also present to every
+/// requested surface, and a present queue prefers a family with graphics, so that the two end up
+/// in the same family where the hardware allows it.
+#[derive(Clone)]
+pub struct DeviceBuilder<'a> {
+    queues: Vec<QueueRequest<'a>>,
+    extensions: Vec<(&'a CStr, bool)>,
+    features: vk::PhysicalDeviceFeatures,
+}
+
+impl<'a> Default for DeviceBuilder<'a> {
+    fn default() -> DeviceBuilder<'a> {
+        DeviceBuilder::new()
+    }
+}
+
+impl<'a> DeviceBuilder<'a> {
+    pub fn new() -> DeviceBuilder<'a> {
+        DeviceBuilder {
+            queues: Vec::new(),
+            extensions: Vec::new(),
+            features: Default::default(),
+        }
+    }
+
+    pub fn graphics_queue(mut self) -> DeviceBuilder<'a> {
+        self.queues.push(QueueRequest::Graphics);
+        self
+    }
+
+    pub fn compute_only_queue(mut self) -> DeviceBuilder<'a> {
+        self.queues.push(QueueRequest::ComputeOnly);
+        self
+    }
+
+    pub fn transfer_only_queue(mut self) -> DeviceBuilder<'a> {
+        self.queues.push(QueueRequest::TransferOnly);
+        self
+    }
+
+    pub fn present_queue(
+        mut self,
+        surface_loader: &'a Surface,
+        surface: vk::SurfaceKHR,
+    ) -> DeviceBuilder<'a> {
+        self.queues.push(QueueRequest::Present(surface_loader, surface));
+        self
+    }
+
+    pub fn extension(mut self, name: &'a CStr) -> DeviceBuilder<'a> {
+        self.extensions.push((name, true));
+        self
+    }
+
+    pub fn extensions<I>(mut self, names: I) -> DeviceBuilder<'a>
+    where
+        I: IntoIterator<Item = &'a CStr>,
+    {
+        self.extensions
+            .extend(names.into_iter().map(|name| (name, true)));
+        self
+    }
+
+    /// An extension that is enabled if it is available and dropped otherwise.
+    pub fn optional_extension(mut self, name: &'a CStr) -> DeviceBuilder<'a> {
+        self.extensions.push((name, false));
+        self
+    }
+
+    /// The features to enable. Every feature that is set has to be supported.
+    pub fn features(mut self, features: vk::PhysicalDeviceFeatures) -> DeviceBuilder<'a> {
+        self.features = features;
+        self
+    }
+
+    /// Checks the requests against `physical_device` without creating anything.
+    pub fn check<I: InstanceV1_0>(
+        &self,
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+    ) -> Result<DevicePlan, DeviceBuilderError> {
+        let extension_properties = instance
+            .enumerate_device_extension_properties(physical_device)
+            .map_err(|err| DeviceBuilderError::VkError(err))?;
+        let available_extensions: Vec<&CStr> = extension_properties
+            .iter()
+            .map(|extension| unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) })
+            .collect();
+        let (enabled_extensions, missing_extensions, dropped_extensions) =
+            sort_names(&self.extensions, &available_extensions);
+
+        let supported_features = instance.get_physical_device_features(physical_device);
+        let missing_features = self.features.missing_from(&supported_features);
+
+        let (queues, missing_queues) = self.plan_queues(instance, physical_device);
+
+        if !missing_extensions.is_empty() || !missing_features.is_empty()
+            || !missing_queues.is_empty()
+        {
+            return Err(DeviceBuilderError::Unsupported {
+                extensions: missing_extensions,
+                features: missing_features,
+                queues: missing_queues,
+            });
+        }
+        Ok(DevicePlan {
+            queues: queues,
+            extensions: NameReport {
+                enabled_layers: Vec::new(),
+                enabled_extensions: enabled_extensions,
+                dropped_layers: Vec::new(),
+                dropped_extensions: dropped_extensions,
+            },
+        })
+    }
+
+    /// Checks the requests and creates the device. The queues are returned in the order they were
+    /// requested.
+    pub unsafe fn build<I: InstanceV1_0>(
+        &self,
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Result<(Device<I::Fp>, Vec<ResolvedQueue>, NameReport), DeviceBuilderError>
+    where
+        Device<I::Fp>: DeviceV1_0,
+    {
+        let plan = self.check(instance, physical_device)?;
+        let mut families: Vec<(vk::uint32_t, Vec<f32>)> = Vec::new();
+        for queue in &plan.queues {
+            let count = queue.queue_index as usize + 1;
+            match families
+                .iter()
+                .position(|&(family_index, _)| family_index == queue.family_index)
+            {
+                Some(position) => {
+                    let priorities = &mut families[position].1;
+                    if priorities.len() < count {
+                        priorities.resize(count, 1.0);
+                    }
+                }
+                None => families.push((queue.family_index, vec![1.0; count])),
+            }
+        }
+        let queue_create_infos: Vec<vk::DeviceQueueCreateInfo> = families
+            .iter()
+            .map(|&(family_index, ref priorities)| vk::DeviceQueueCreateInfo {
+                s_type: vk::StructureType::DeviceQueueCreateInfo,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                queue_family_index: family_index,
+                queue_count: priorities.len() as u32,
+                p_queue_priorities: priorities.as_ptr(),
+            })
+            .collect();
+        let extension_names: Vec<*const vk::c_char> = plan.extensions
+            .enabled_extensions
+            .iter()
+            .map(|name| name.as_ptr())
+            .collect();
+        let create_info = vk::DeviceCreateInfo {
+            s_type: vk::StructureType::DeviceCreateInfo,
+            p_next: ptr::null(),
+            flags: Default::default(),
+            queue_create_info_count: queue_create_infos.len() as u32,
+            p_queue_create_infos: queue_create_infos.as_ptr(),
+            enabled_layer_count: 0,
+            pp_enabled_layer_names: ptr::null(),
+            enabled_extension_count: extension_names.len() as u32,
+            pp_enabled_extension_names: extension_names.as_ptr(),
+            p_enabled_features: &self.features,
+        };
+        let device = instance
+            .create_device(physical_device, &create_info, allocation_callbacks)
+            .map_err(|err| DeviceBuilderError::DeviceError(err))?;
+        let queues = plan.queues
+            .iter()
+            .map(|queue| ResolvedQueue {
+                capability: queue.capability,
+                family_index: queue.family_index,
+                queue_index: queue.queue_index,
+                queue: device.get_device_queue(queue.family_index, queue.queue_index),
+            })
+            .collect();
+        Ok((device, queues, plan.extensions))
+    }
+
+    /// Picks a family for every request. Returns the plans and the capabilities that no family
+    /// provides.
+    fn plan_queues<I: InstanceV1_0>(
+        &self,
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+    ) -> (Vec<QueuePlan>, Vec<QueueCapability>) {
+        let families = instance.get_physical_device_queue_family_properties(physical_device);
+        let presents_to_all = |family_index: usize| {
+            self.queues.iter().all(|request| match *request {
+                QueueRequest::Present(surface_loader, surface) => surface_loader
+                    .get_physical_device_surface_support_khr(
+                        physical_device,
+                        family_index as vk::uint32_t,
+                        surface,
+                    ),
+                _ => true,
+            })
+        };
+        let mut used_queues = vec![0u32; families.len()];
+        let mut plans = Vec::new();
+        let mut missing = Vec::new();
+        for request in &self.queues {
+            let candidates: Vec<usize> = families
+                .iter()
+                .enumerate()
+                .filter(|&(family_index, family)| {
+                    let flags = family.queue_flags;
+                    family.queue_count > 0 && match *request {
+                        QueueRequest::Graphics => flags.subset(vk::QUEUE_GRAPHICS_BIT),
+                        QueueRequest::ComputeOnly => {
+                            flags.subset(vk::QUEUE_COMPUTE_BIT)
+                                && !flags.intersects(vk::QUEUE_GRAPHICS_BIT)
+                        }
+                        QueueRequest::TransferOnly => {
+                            flags.subset(vk::QUEUE_TRANSFER_BIT)
+                                && !flags.intersects(vk::QUEUE_GRAPHICS_BIT | vk::QUEUE_COMPUTE_BIT)
+                        }
+                        QueueRequest::Present(surface_loader, surface) => surface_loader
+                            .get_physical_device_surface_support_khr(
+                                physical_device,
+                                family_index as vk::uint32_t,
+                                surface,
+                            ),
+                    }
+                })
+                .map(|(family_index, _)| family_index)
+                .collect();
+            let preferred = match *request {
+                QueueRequest::Graphics => candidates
+                    .iter()
+                    .cloned()
+                    .find(|&family_index| presents_to_all(family_index)),
+                QueueRequest::Present(..) => candidates.iter().cloned().find(|&family_index| {
+                    families[family_index]
+                        .queue_flags
+                        .subset(vk::QUEUE_GRAPHICS_BIT)
+                }),
+                _ => None,
+            };
+            match preferred.or(candidates.first().cloned()) {
+                Some(family_index) => {
+                    let queue_index =
+                        used_queues[family_index] % families[family_index].queue_count;
+                    used_queues[family_index] += 1;
+                    plans.push(QueuePlan {
+                        capability: request.capability(),
+                        family_index: family_index as vk::uint32_t,
+                        queue_index: queue_index,
+                    });
+                }
+                None => missing.push(request.capability()),
+            }
+        }
+        (plans, missing)
+    }
+}
+
+/// Splits `requested` into the available names, the missing required names and the missing
+/// optional names, dropping duplicates.
+fn sort_names(
+    requested: &[(&CStr, bool)],
+    available: &[&CStr],
+) -> (Vec<CString>, Vec<CString>, Vec<CString>) {
+    let mut enabled: Vec<CString> = Vec::new();
+    let mut missing: Vec<CString> = Vec::new();
+    let mut dropped: Vec<CString> = Vec::new();
+    for &(name, required) in requested {
+        if enabled.iter().chain(&missing).any(|seen| seen.as_c_str() == name) {
+            continue;
+        }
+        if available.contains(&name) {
+            dropped.retain(|seen| seen.as_c_str() != name);
+            enabled.push(name.to_owned());
+        } else if required {
+            dropped.retain(|seen| seen.as_c_str() != name);
+            missing.push(name.to_owned());
+        } else if !dropped.iter().any(|seen| seen.as_c_str() == name) {
+            dropped.push(name.to_owned());
+        }
+    }
+    (enabled, missing, dropped)
+}
+
+fn enumerate_layer_extension_properties<E: EntryV1_0>(
+    entry: &E,
+    layer_name: &CStr,
+) -> VkResult<Vec<vk::ExtensionProperties>> {
+    unsafe {
+        let mut num = 0;
+        entry.fp_v1_0().enumerate_instance_extension_properties(
+            layer_name.as_ptr(),
+            &mut num,
+            ptr::null_mut(),
+        );
+        let mut data = Vec::with_capacity(num as usize);
+        let err_code = entry.fp_v1_0().enumerate_instance_extension_properties(
+            layer_name.as_ptr(),
+            &mut num,
+            data.as_mut_ptr(),
+        );
+        data.set_len(num as usize);
+        match err_code {
+            vk::Result::Success => Ok(data),
+            _ => Err(err_code),
+        }
+    }
+}
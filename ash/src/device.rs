@@ -3,232 +3,321 @@ use prelude::*;
 use std::mem;
 use vk;
 use RawPtr;
-use version::{FunctionPointers, V1_0};
+use version::{DeviceLoader, FunctionPointers, V1_0};
+use extensions::{missing_extensions, DeviceExtension, ExtensionError};
+use std::ffi::CString;
+use memory::MappedMemory;
+use bound::{AsHandle, IntoHandle, Unbound};
+use tracker;
 
 #[allow(non_camel_case_types)]
 pub trait DeviceV1_0 {
     fn handle(&self) -> vk::Device;
     fn fp_v1_0(&self) -> &vk::DeviceFnV1_0;
-    unsafe fn destroy_device(&self, allocation_callbacks: Option<&vk::AllocationCallbacks>) {
+    /// A `bound::Device` is destroyed with `bound::Device::destroy` instead, which takes it by
+    /// value.
+    unsafe fn destroy_device(&self, allocation_callbacks: Option<&vk::AllocationCallbacks>)
+    where
+        Self: Sized + Unbound,
+    {
+        tracker::parent_destroyed(self.handle());
         self.fp_v1_0().destroy_device(
             self.handle(),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_sampler(
+    unsafe fn destroy_sampler<H: IntoHandle<vk::Sampler, Self>>(
         &self,
-        sampler: vk::Sampler,
+        sampler: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_sampler(
             self.handle(),
-            sampler,
+            tracker::destroyed(self.handle(), sampler.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn free_memory(
+    unsafe fn free_memory<H: IntoHandle<vk::DeviceMemory, Self>>(
         &self,
-        memory: vk::DeviceMemory,
+        memory: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().free_memory(
             self.handle(),
-            memory,
+            tracker::destroyed(self.handle(), memory.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn free_command_buffers(
+    unsafe fn free_command_buffers<H: AsHandle<vk::CommandPool, Self>>(
         &self,
-        command_pool: vk::CommandPool,
+        command_pool: H,
         command_buffers: &[vk::CommandBuffer],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().free_command_buffers(
             self.handle(),
-            command_pool,
+            tracker::used(self.handle(), command_pool.as_handle()),
             command_buffers.len() as vk::uint32_t,
             command_buffers.as_ptr(),
         );
     }
 
-    unsafe fn destroy_fence(
+    unsafe fn destroy_fence<H: IntoHandle<vk::Fence, Self>>(
         &self,
-        fence: vk::Fence,
+        fence: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_fence(
             self.handle(),
-            fence,
+            tracker::destroyed(self.handle(), fence.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_image(
+    unsafe fn destroy_image<H: IntoHandle<vk::Image, Self>>(
         &self,
-        image: vk::Image,
+        image: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_image(
             self.handle(),
-            image,
+            tracker::destroyed(self.handle(), image.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_command_pool(
+    unsafe fn destroy_command_pool<H: IntoHandle<vk::CommandPool, Self>>(
         &self,
-        pool: vk::CommandPool,
+        pool: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_command_pool(
             self.handle(),
-            pool,
+            tracker::destroyed(self.handle(), pool.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_image_view(
+    unsafe fn destroy_image_view<H: IntoHandle<vk::ImageView, Self>>(
         &self,
-        image_view: vk::ImageView,
+        image_view: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_image_view(
             self.handle(),
-            image_view,
+            tracker::destroyed(self.handle(), image_view.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_render_pass(
+    unsafe fn destroy_render_pass<H: IntoHandle<vk::RenderPass, Self>>(
         &self,
-        renderpass: vk::RenderPass,
+        renderpass: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_render_pass(
             self.handle(),
-            renderpass,
+            tracker::destroyed(self.handle(), renderpass.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_framebuffer(
+    unsafe fn destroy_framebuffer<H: IntoHandle<vk::Framebuffer, Self>>(
         &self,
-        framebuffer: vk::Framebuffer,
+        framebuffer: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_framebuffer(
             self.handle(),
-            framebuffer,
+            tracker::destroyed(self.handle(), framebuffer.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_pipeline_layout(
+    unsafe fn destroy_pipeline_layout<H: IntoHandle<vk::PipelineLayout, Self>>(
         &self,
-        pipeline_layout: vk::PipelineLayout,
+        pipeline_layout: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_pipeline_layout(
             self.handle(),
-            pipeline_layout,
+            tracker::destroyed(self.handle(), pipeline_layout.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_pipeline_cache(
+    unsafe fn destroy_pipeline_cache<H: IntoHandle<vk::PipelineCache, Self>>(
         &self,
-        pipeline_cache: vk::PipelineCache,
+        pipeline_cache: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_pipeline_cache(
             self.handle(),
-            pipeline_cache,
+            tracker::destroyed(self.handle(), pipeline_cache.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_buffer(
+    unsafe fn destroy_buffer<H: IntoHandle<vk::Buffer, Self>>(
         &self,
-        buffer: vk::Buffer,
+        buffer: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_buffer(
             self.handle(),
-            buffer,
+            tracker::destroyed(self.handle(), buffer.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_shader_module(
+    unsafe fn destroy_shader_module<H: IntoHandle<vk::ShaderModule, Self>>(
         &self,
-        shader: vk::ShaderModule,
+        shader: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_shader_module(
             self.handle(),
-            shader,
+            tracker::destroyed(self.handle(), shader.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_pipeline(
+    unsafe fn destroy_pipeline<H: IntoHandle<vk::Pipeline, Self>>(
         &self,
-        pipeline: vk::Pipeline,
+        pipeline: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_pipeline(
             self.handle(),
-            pipeline,
+            tracker::destroyed(self.handle(), pipeline.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_semaphore(
+    unsafe fn destroy_semaphore<H: IntoHandle<vk::Semaphore, Self>>(
         &self,
-        semaphore: vk::Semaphore,
+        semaphore: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_semaphore(
             self.handle(),
-            semaphore,
+            tracker::destroyed(self.handle(), semaphore.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_descriptor_pool(
+    unsafe fn destroy_descriptor_pool<H: IntoHandle<vk::DescriptorPool, Self>>(
         &self,
-        pool: vk::DescriptorPool,
+        pool: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_descriptor_pool(
             self.handle(),
-            pool,
+            tracker::destroyed(self.handle(), pool.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn destroy_descriptor_set_layout(
+    unsafe fn destroy_descriptor_set_layout<H: IntoHandle<vk::DescriptorSetLayout, Self>>(
         &self,
-        layout: vk::DescriptorSetLayout,
+        layout: H,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().destroy_descriptor_set_layout(
             self.handle(),
-            layout,
+            tracker::destroyed(self.handle(), layout.into_handle()),
+            allocation_callbacks.as_raw_ptr(),
+        );
+    }
+
+    unsafe fn destroy_buffer_view<H: IntoHandle<vk::BufferView, Self>>(
+        &self,
+        buffer_view: H,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) where
+        Self: Sized,
+    {
+        self.fp_v1_0().destroy_buffer_view(
+            self.handle(),
+            tracker::destroyed(self.handle(), buffer_view.into_handle()),
+            allocation_callbacks.as_raw_ptr(),
+        );
+    }
+
+    unsafe fn destroy_event<H: IntoHandle<vk::Event, Self>>(
+        &self,
+        event: H,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) where
+        Self: Sized,
+    {
+        self.fp_v1_0().destroy_event(
+            self.handle(),
+            tracker::destroyed(self.handle(), event.into_handle()),
+            allocation_callbacks.as_raw_ptr(),
+        );
+    }
+
+    unsafe fn destroy_query_pool<H: IntoHandle<vk::QueryPool, Self>>(
+        &self,
+        query_pool: H,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) where
+        Self: Sized,
+    {
+        self.fp_v1_0().destroy_query_pool(
+            self.handle(),
+            tracker::destroyed(self.handle(), query_pool.into_handle()),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 
-    unsafe fn free_descriptor_sets(
+    unsafe fn free_descriptor_sets<H: AsHandle<vk::DescriptorPool, Self>>(
         &self,
-        pool: vk::DescriptorPool,
+        pool: H,
         descriptor_sets: &[vk::DescriptorSet],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().free_descriptor_sets(
             self.handle(),
-            pool,
+            tracker::used(self.handle(), pool.as_handle()),
             descriptor_sets.len() as u32,
             descriptor_sets.as_ptr(),
         );
@@ -261,130 +350,144 @@ pub trait DeviceV1_0 {
             &mut sampler,
         );
         match err_code {
-            vk::Result::Success => Ok(sampler),
+            vk::Result::Success => Ok(tracker::created(self.handle(), sampler)),
             _ => Err(err_code),
         }
     }
 
-    unsafe fn cmd_resolve_image(
+    unsafe fn cmd_resolve_image<S: AsHandle<vk::Image, Self>, D: AsHandle<vk::Image, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        src_image: vk::Image,
+        src_image: S,
         src_image_layout: vk::ImageLayout,
-        dst_image: vk::Image,
+        dst_image: D,
         dst_image_layout: vk::ImageLayout,
         regions: &[vk::ImageResolve],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_resolve_image(
             command_buffer,
-            src_image,
+            tracker::used(self.handle(), src_image.as_handle()),
             src_image_layout,
-            dst_image,
+            tracker::used(self.handle(), dst_image.as_handle()),
             dst_image_layout,
             regions.len() as u32,
             regions.as_ptr(),
         );
     }
 
-    unsafe fn cmd_fill_buffer(
+    unsafe fn cmd_fill_buffer<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
         size: vk::DeviceSize,
         data: vk::uint32_t,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_fill_buffer(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
             size,
             data,
         );
     }
 
-    unsafe fn cmd_update_buffer(
+    unsafe fn cmd_update_buffer<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
         data: &[u8],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_update_buffer(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
             data.len() as u64,
             data.as_ptr() as _,
         );
     }
 
-    unsafe fn cmd_copy_buffer(
+    unsafe fn cmd_copy_buffer<S: AsHandle<vk::Buffer, Self>, D: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        src_buffer: vk::Buffer,
-        dst_buffer: vk::Buffer,
+        src_buffer: S,
+        dst_buffer: D,
         regions: &[vk::BufferCopy],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_copy_buffer(
             command_buffer,
-            src_buffer,
-            dst_buffer,
+            tracker::used(self.handle(), src_buffer.as_handle()),
+            tracker::used(self.handle(), dst_buffer.as_handle()),
             regions.len() as u32,
             regions.as_ptr(),
         );
     }
 
-    unsafe fn cmd_copy_image_to_buffer(
+    unsafe fn cmd_copy_image_to_buffer<I: AsHandle<vk::Image, Self>, B: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        src_image: vk::Image,
+        src_image: I,
         src_image_layout: vk::ImageLayout,
-        dst_buffer: vk::Buffer,
+        dst_buffer: B,
         regions: &[vk::BufferImageCopy],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_copy_image_to_buffer(
             command_buffer,
-            src_image,
+            tracker::used(self.handle(), src_image.as_handle()),
             src_image_layout,
-            dst_buffer,
+            tracker::used(self.handle(), dst_buffer.as_handle()),
             regions.len() as vk::uint32_t,
             regions.as_ptr(),
         );
     }
 
-    unsafe fn cmd_copy_buffer_to_image(
+    unsafe fn cmd_copy_buffer_to_image<B: AsHandle<vk::Buffer, Self>, I: AsHandle<vk::Image, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        src_buffer: vk::Buffer,
-        dst_image: vk::Image,
+        src_buffer: B,
+        dst_image: I,
         dst_image_layout: vk::ImageLayout,
         regions: &[vk::BufferImageCopy],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_copy_buffer_to_image(
             command_buffer,
-            src_buffer,
-            dst_image,
+            tracker::used(self.handle(), src_buffer.as_handle()),
+            tracker::used(self.handle(), dst_image.as_handle()),
             dst_image_layout,
             regions.len() as u32,
             regions.as_ptr(),
         );
     }
 
-    unsafe fn cmd_copy_image(
+    unsafe fn cmd_copy_image<S: AsHandle<vk::Image, Self>, D: AsHandle<vk::Image, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        src_image: vk::Image,
+        src_image: S,
         src_image_layout: vk::ImageLayout,
-        dst_image: vk::Image,
+        dst_image: D,
         dst_image_layout: vk::ImageLayout,
         regions: &[vk::ImageCopy],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_copy_image(
             command_buffer,
-            src_image,
+            tracker::used(self.handle(), src_image.as_handle()),
             src_image_layout,
-            dst_image,
+            tracker::used(self.handle(), dst_image.as_handle()),
             dst_image_layout,
             regions.len() as u32,
             regions.as_ptr(),
@@ -422,7 +525,7 @@ pub trait DeviceV1_0 {
             &mut layout,
         );
         match err_code {
-            vk::Result::Success => Ok(layout),
+            vk::Result::Success => Ok(tracker::created(self.handle(), layout)),
             _ => Err(err_code),
         }
     }
@@ -450,19 +553,22 @@ pub trait DeviceV1_0 {
             &mut pool,
         );
         match err_code {
-            vk::Result::Success => Ok(pool),
+            vk::Result::Success => Ok(tracker::created(self.handle(), pool)),
             _ => Err(err_code),
         }
     }
 
-    unsafe fn reset_descriptor_pool(
+    unsafe fn reset_descriptor_pool<H: AsHandle<vk::DescriptorPool, Self>>(
         &self,
-        pool: vk::DescriptorPool,
+        pool: H,
         flags: vk::DescriptorPoolResetFlags,
-    ) -> VkResult<()> {
+    ) -> VkResult<()>
+    where
+        Self: Sized,
+    {
         let err_code = self.fp_v1_0().reset_descriptor_pool(
             self.handle(),
-            pool,
+            tracker::used(self.handle(), pool.as_handle()),
             flags,
         );
         match err_code {
@@ -471,14 +577,17 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn reset_command_pool(
+    unsafe fn reset_command_pool<H: AsHandle<vk::CommandPool, Self>>(
         &self,
-        command_pool: vk::CommandPool,
+        command_pool: H,
         flags: vk::CommandPoolResetFlags,
-    ) -> VkResult<()> {
+    ) -> VkResult<()>
+    where
+        Self: Sized,
+    {
         let err_code = self.fp_v1_0().reset_command_pool(
             self.handle(),
-            command_pool,
+            tracker::used(self.handle(), command_pool.as_handle()),
             flags,
         );
         match err_code {
@@ -501,6 +610,7 @@ pub trait DeviceV1_0 {
     }
 
     unsafe fn reset_fences(&self, fences: &[vk::Fence]) -> VkResult<()> {
+        tracker::used_all(self.handle(), fences);
         let err_code = self.fp_v1_0().reset_fences(
             self.handle(),
             fences.len() as vk::uint32_t,
@@ -512,32 +622,36 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn cmd_bind_index_buffer(
+    unsafe fn cmd_bind_index_buffer<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
         index_type: vk::IndexType,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_bind_index_buffer(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
             index_type,
         );
     }
 
-    unsafe fn cmd_clear_color_image(
+    unsafe fn cmd_clear_color_image<H: AsHandle<vk::Image, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        image: vk::Image,
+        image: H,
         image_layout: vk::ImageLayout,
         clear_color_value: &vk::ClearColorValue,
         ranges: &[vk::ImageSubresourceRange],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_clear_color_image(
             command_buffer,
-            image,
+            tracker::used(self.handle(), image.as_handle()),
             image_layout,
             clear_color_value,
             ranges.len() as vk::uint32_t,
@@ -545,17 +659,19 @@ pub trait DeviceV1_0 {
         );
     }
 
-    unsafe fn cmd_clear_depth_stencil_image(
+    unsafe fn cmd_clear_depth_stencil_image<H: AsHandle<vk::Image, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        image: vk::Image,
+        image: H,
         image_layout: vk::ImageLayout,
         clear_depth_stencil_value: &vk::ClearDepthStencilValue,
         ranges: &[vk::ImageSubresourceRange],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_clear_depth_stencil_image(
             command_buffer,
-            image,
+            tracker::used(self.handle(), image.as_handle()),
             image_layout,
             clear_depth_stencil_value,
             ranges.len() as vk::uint32_t,
@@ -598,17 +714,19 @@ pub trait DeviceV1_0 {
         );
     }
 
-    unsafe fn cmd_draw_indexed_indirect(
+    unsafe fn cmd_draw_indexed_indirect<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
         draw_count: vk::uint32_t,
         stride: vk::uint32_t,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_draw_indexed_indirect(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
             draw_count,
             stride,
@@ -628,19 +746,21 @@ pub trait DeviceV1_0 {
         );
     }
 
-    unsafe fn cmd_bind_descriptor_sets(
+    unsafe fn cmd_bind_descriptor_sets<H: AsHandle<vk::PipelineLayout, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
         pipeline_bind_point: vk::PipelineBindPoint,
-        layout: vk::PipelineLayout,
+        layout: H,
         first_set: vk::uint32_t,
         descriptor_sets: &[vk::DescriptorSet],
         dynamic_offsets: &[vk::uint32_t],
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_bind_descriptor_sets(
             command_buffer,
             pipeline_bind_point,
-            layout,
+            tracker::used(self.handle(), layout.as_handle()),
             first_set,
             descriptor_sets.len() as vk::uint32_t,
             descriptor_sets.as_ptr(),
@@ -649,17 +769,19 @@ pub trait DeviceV1_0 {
         )
     }
 
-    unsafe fn cmd_push_constants(
+    unsafe fn cmd_push_constants<H: AsHandle<vk::PipelineLayout, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        layout: vk::PipelineLayout,
+        layout: H,
         stage_flags: vk::ShaderStageFlags,
         offset: vk::uint32_t,
         constants: &[u32]
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_push_constants(
             command_buffer,
-            layout,
+            tracker::used(self.handle(), layout.as_handle()),
             stage_flags,
             offset,
             constants.len() as vk::uint32_t,
@@ -688,16 +810,18 @@ pub trait DeviceV1_0 {
         self.fp_v1_0().cmd_next_subpass(command_buffer, contents);
     }
 
-    unsafe fn cmd_bind_pipeline(
+    unsafe fn cmd_bind_pipeline<H: AsHandle<vk::Pipeline, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
         pipeline_bind_point: vk::PipelineBindPoint,
-        pipeline: vk::Pipeline,
-    ) {
+        pipeline: H,
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_bind_pipeline(
             command_buffer,
             pipeline_bind_point,
-            pipeline,
+            tracker::used(self.handle(), pipeline.as_handle()),
         );
     }
 
@@ -718,6 +842,7 @@ pub trait DeviceV1_0 {
         offsets: &[vk::DeviceSize],
     ) {
         debug_assert_eq!(buffers.len(), offsets.len());
+        tracker::used_all(self.handle(), buffers);
         self.fp_v1_0().cmd_bind_vertex_buffers(
             command_buffer,
             first_binding,
@@ -748,17 +873,19 @@ pub trait DeviceV1_0 {
         );
     }
 
-    unsafe fn cmd_draw_indirect(
+    unsafe fn cmd_draw_indirect<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
         draw_count: vk::uint32_t,
         stride: vk::uint32_t,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_draw_indirect(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
             draw_count,
             stride,
@@ -780,15 +907,17 @@ pub trait DeviceV1_0 {
         );
     }
 
-    unsafe fn cmd_dispatch_indirect(
+    unsafe fn cmd_dispatch_indirect<H: AsHandle<vk::Buffer, Self>>(
         &self,
         command_buffer: vk::CommandBuffer,
-        buffer: vk::Buffer,
+        buffer: H,
         offset: vk::DeviceSize,
-    ) {
+    ) where
+        Self: Sized,
+    {
         self.fp_v1_0().cmd_dispatch_indirect(
             command_buffer,
-            buffer,
+            tracker::used(self.handle(), buffer.as_handle()),
             offset,
         );
     }
@@ -843,21 +972,24 @@ pub trait DeviceV1_0 {
             &mut semaphore,
         );
         match err_code {
-            vk::Result::Success => Ok(semaphore),
+            vk::Result::Success => Ok(tracker::created(self.handle(), semaphore)),
             _ => Err(err_code),
         }
     }
 
-    unsafe fn create_graphics_pipelines(
+    unsafe fn create_graphics_pipelines<H: AsHandle<vk::PipelineCache, Self>>(
         &self,
-        pipeline_cache: vk::PipelineCache,
+        pipeline_cache: H,
         create_infos: &[vk::GraphicsPipelineCreateInfo],
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) -> Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)> {
+    ) -> Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)>
+    where
+        Self: Sized,
+    {
         let mut pipelines = Vec::with_capacity(create_infos.len());
         let err_code = self.fp_v1_0().create_graphics_pipelines(
             self.handle(),
-            pipeline_cache,
+            tracker::used(self.handle(), pipeline_cache.as_handle()),
             create_infos.len() as vk::uint32_t,
             create_infos.as_ptr(),
             allocation_callbacks.as_raw_ptr(),
@@ -865,21 +997,24 @@ pub trait DeviceV1_0 {
         );
         pipelines.set_len(create_infos.len());
         match err_code {
-            vk::Result::Success => Ok(pipelines),
-            _ => Err((pipelines, err_code)),
+            vk::Result::Success => Ok(tracker::created_all(self.handle(), pipelines)),
+            _ => Err((tracker::created_all(self.handle(), pipelines), err_code)),
         }
     }
 
-    unsafe fn create_compute_pipelines(
+    unsafe fn create_compute_pipelines<H: AsHandle<vk::PipelineCache, Self>>(
         &self,
-        pipeline_cache: vk::PipelineCache,
+        pipeline_cache: H,
         create_infos: &[vk::ComputePipelineCreateInfo],
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
-    ) -> Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)> {
+    ) -> Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)>
+    where
+        Self: Sized,
+    {
         let mut pipelines = Vec::with_capacity(create_infos.len());
         let err_code = self.fp_v1_0().create_compute_pipelines(
             self.handle(),
-            pipeline_cache,
+            tracker::used(self.handle(), pipeline_cache.as_handle()),
             create_infos.len() as vk::uint32_t,
             create_infos.as_ptr(),
             allocation_callbacks.as_raw_ptr(),
@@ -887,8 +1022,8 @@ pub trait DeviceV1_0 {
         );
         pipelines.set_len(create_infos.len());
         match err_code {
-            vk::Result::Success => Ok(pipelines),
-            _ => Err((pipelines, err_code)),
+            vk::Result::Success => Ok(tracker::created_all(self.handle(), pipelines)),
+            _ => Err((tracker::created_all(self.handle(), pipelines), err_code)),
         }
     }
 
@@ -905,7 +1040,7 @@ pub trait DeviceV1_0 {
             &mut buffer,
         );
         match err_code {
-            vk::Result::Success => Ok(buffer),
+            vk::Result::Success => Ok(tracker::created(self.handle(), buffer)),
             _ => Err(err_code),
         }
     }
@@ -923,7 +1058,7 @@ pub trait DeviceV1_0 {
             &mut pipeline_layout,
         );
         match err_code {
-            vk::Result::Success => Ok(pipeline_layout),
+            vk::Result::Success => Ok(tracker::created(self.handle(), pipeline_layout)),
             _ => Err(err_code),
         }
     }
@@ -942,23 +1077,26 @@ pub trait DeviceV1_0 {
         );
 
         match err_code {
-            vk::Result::Success => Ok(pipeline_cache),
+            vk::Result::Success => Ok(tracker::created(self.handle(), pipeline_cache)),
             _ => Err(err_code),
         }
     }
 
-    unsafe fn map_memory(
+    unsafe fn map_memory<H: AsHandle<vk::DeviceMemory, Self>>(
         &self,
-        memory: vk::DeviceMemory,
+        memory: H,
         offset: vk::DeviceSize,
         size: vk::DeviceSize,
         flags: vk::MemoryMapFlags,
-    ) -> VkResult<*mut vk::c_void> {
+    ) -> VkResult<*mut vk::c_void>
+    where
+        Self: Sized,
+    {
 
         let mut data: *mut vk::c_void = mem::uninitialized();
         let err_code = self.fp_v1_0().map_memory(
             self.handle(),
-            memory,
+            tracker::used(self.handle(), memory.as_handle()),
             offset,
             size,
             flags,
@@ -970,8 +1108,12 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn unmap_memory(&self, memory: vk::DeviceMemory) {
-        self.fp_v1_0().unmap_memory(self.handle(), memory);
+    unsafe fn unmap_memory<H: AsHandle<vk::DeviceMemory, Self>>(&self, memory: H)
+    where
+        Self: Sized,
+    {
+        self.fp_v1_0()
+            .unmap_memory(self.handle(), tracker::used(self.handle(), memory.as_handle()));
     }
 
     unsafe fn invalidate_mapped_memory_ranges(&self, ranges: &[vk::MappedMemoryRange])
@@ -1002,6 +1144,31 @@ pub trait DeviceV1_0 {
         }
     }
 
+    /// Maps `size` bytes at `offset` and returns a guard that gives bounds checked views of them
+    /// and unmaps them when it is dropped. `property_flags` are the flags of the memory type of
+    /// `memory`, and decide whether the mapped range has to be flushed and invalidated in
+    /// multiples of `non_coherent_atom_size` from the device limits.
+    unsafe fn map_memory_guard<'a, H: AsHandle<vk::DeviceMemory, Self>>(
+        &'a self,
+        memory: H,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+        property_flags: vk::MemoryPropertyFlags,
+        non_coherent_atom_size: vk::DeviceSize,
+    ) -> VkResult<MappedMemory<'a, Self>>
+    where
+        Self: Sized,
+    {
+        MappedMemory::map(
+            self,
+            tracker::used(self.handle(), memory.as_handle()),
+            offset,
+            size,
+            property_flags,
+            non_coherent_atom_size,
+        )
+    }
+
     unsafe fn create_framebuffer(
         &self,
         create_info: &vk::FramebufferCreateInfo,
@@ -1015,7 +1182,7 @@ pub trait DeviceV1_0 {
             &mut framebuffer,
         );
         match err_code {
-            vk::Result::Success => Ok(framebuffer),
+            vk::Result::Success => Ok(tracker::created(self.handle(), framebuffer)),
             _ => Err(err_code),
         }
     }
@@ -1072,7 +1239,7 @@ pub trait DeviceV1_0 {
             &mut renderpass,
         );
         match err_code {
-            vk::Result::Success => Ok(renderpass),
+            vk::Result::Success => Ok(tracker::created(self.handle(), renderpass)),
             _ => Err(err_code),
         }
     }
@@ -1106,6 +1273,7 @@ pub trait DeviceV1_0 {
         wait_all: bool,
         timeout: vk::uint64_t,
     ) -> VkResult<()> {
+        tracker::used_all(self.handle(), fences);
         let err_code = self.fp_v1_0().wait_for_fences(
             self.handle(),
             fences.len() as vk::uint32_t,
@@ -1119,8 +1287,13 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn get_fence_status(&self, fence: vk::Fence) -> VkResult<()> {
-        let err_code = self.fp_v1_0().get_fence_status(self.handle(), fence);
+    unsafe fn get_fence_status<H: AsHandle<vk::Fence, Self>>(&self, fence: H) -> VkResult<()>
+    where
+        Self: Sized,
+    {
+        let err_code = self
+            .fp_v1_0()
+            .get_fence_status(self.handle(), tracker::used(self.handle(), fence.as_handle()));
         match err_code {
             vk::Result::Success => Ok(()),
             _ => Err(err_code),
@@ -1135,17 +1308,21 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn queue_submit(
+    unsafe fn queue_submit<H: AsHandle<vk::Fence, Self>>(
         &self,
         queue: vk::Queue,
         submits: &[vk::SubmitInfo],
-        fence: vk::Fence,
-    ) -> VkResult<()> {
+        fence: H,
+    ) -> VkResult<()>
+    where
+        Self: Sized,
+    {
+        tracker::used_by_submits(self.handle(), submits);
         let err_code = self.fp_v1_0().queue_submit(
             queue,
             submits.len() as vk::uint32_t,
             submits.as_ptr(),
-            fence,
+            tracker::used(self.handle(), fence.as_handle()),
         );
         match err_code {
             vk::Result::Success => Ok(()),
@@ -1166,7 +1343,43 @@ pub trait DeviceV1_0 {
             &mut buffer_view,
         );
         match err_code {
-            vk::Result::Success => Ok(buffer_view),
+            vk::Result::Success => Ok(tracker::created(self.handle(), buffer_view)),
+            _ => Err(err_code),
+        }
+    }
+
+    unsafe fn create_event(
+        &self,
+        create_info: &vk::EventCreateInfo,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::Event> {
+        let mut event = mem::uninitialized();
+        let err_code = self.fp_v1_0().create_event(
+            self.handle(),
+            create_info,
+            allocation_callbacks.as_raw_ptr(),
+            &mut event,
+        );
+        match err_code {
+            vk::Result::Success => Ok(tracker::created(self.handle(), event)),
+            _ => Err(err_code),
+        }
+    }
+
+    unsafe fn create_query_pool(
+        &self,
+        create_info: &vk::QueryPoolCreateInfo,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::QueryPool> {
+        let mut query_pool = mem::uninitialized();
+        let err_code = self.fp_v1_0().create_query_pool(
+            self.handle(),
+            create_info,
+            allocation_callbacks.as_raw_ptr(),
+            &mut query_pool,
+        );
+        match err_code {
+            vk::Result::Success => Ok(tracker::created(self.handle(), query_pool)),
             _ => Err(err_code),
         }
     }
@@ -1176,6 +1389,7 @@ pub trait DeviceV1_0 {
         create_info: &vk::ImageViewCreateInfo,
         allocation_callbacks: Option<&vk::AllocationCallbacks>,
     ) -> VkResult<vk::ImageView> {
+        tracker::used(self.handle(), create_info.image);
         let mut image_view = mem::uninitialized();
         let err_code = self.fp_v1_0().create_image_view(
             self.handle(),
@@ -1184,7 +1398,7 @@ pub trait DeviceV1_0 {
             &mut image_view,
         );
         match err_code {
-            vk::Result::Success => Ok(image_view),
+            vk::Result::Success => Ok(tracker::created(self.handle(), image_view)),
             _ => Err(err_code),
         }
     }
@@ -1220,7 +1434,7 @@ pub trait DeviceV1_0 {
             &mut pool,
         );
         match err_code {
-            vk::Result::Success => Ok(pool),
+            vk::Result::Success => Ok(tracker::created(self.handle(), pool)),
             _ => Err(err_code),
         }
     }
@@ -1238,29 +1452,41 @@ pub trait DeviceV1_0 {
             &mut image,
         );
         match err_code {
-            vk::Result::Success => Ok(image),
+            vk::Result::Success => Ok(tracker::created(self.handle(), image)),
             _ => Err(err_code),
         }
     }
 
-    fn get_image_memory_requirements(&self, image: vk::Image) -> vk::MemoryRequirements {
+    fn get_image_memory_requirements<H: AsHandle<vk::Image, Self>>(
+        &self,
+        image: H,
+    ) -> vk::MemoryRequirements
+    where
+        Self: Sized,
+    {
         unsafe {
             let mut mem_req = mem::uninitialized();
             self.fp_v1_0().get_image_memory_requirements(
                 self.handle(),
-                image,
+                tracker::used(self.handle(), image.as_handle()),
                 &mut mem_req,
             );
             mem_req
         }
     }
 
-    fn get_buffer_memory_requirements(&self, buffer: vk::Buffer) -> vk::MemoryRequirements {
+    fn get_buffer_memory_requirements<H: AsHandle<vk::Buffer, Self>>(
+        &self,
+        buffer: H,
+    ) -> vk::MemoryRequirements
+    where
+        Self: Sized,
+    {
         unsafe {
             let mut mem_req = mem::uninitialized();
             self.fp_v1_0().get_buffer_memory_requirements(
                 self.handle(),
-                buffer,
+                tracker::used(self.handle(), buffer.as_handle()),
                 &mut mem_req,
             );
             mem_req
@@ -1280,7 +1506,7 @@ pub trait DeviceV1_0 {
             &mut memory,
         );
         match err_code {
-            vk::Result::Success => Ok(memory),
+            vk::Result::Success => Ok(tracker::created(self.handle(), memory)),
             _ => Err(err_code),
         }
     }
@@ -1298,7 +1524,7 @@ pub trait DeviceV1_0 {
             &mut shader,
         );
         match err_code {
-            vk::Result::Success => Ok(shader),
+            vk::Result::Success => Ok(tracker::created(self.handle(), shader)),
             _ => Err(err_code),
         }
     }
@@ -1316,21 +1542,26 @@ pub trait DeviceV1_0 {
             &mut fence,
         );
         match err_code {
-            vk::Result::Success => Ok(fence),
+            vk::Result::Success => Ok(tracker::created(self.handle(), fence)),
             _ => Err(err_code),
         }
     }
 
-    unsafe fn bind_buffer_memory(
+    unsafe fn bind_buffer_memory<B, M>(
         &self,
-        buffer: vk::Buffer,
-        device_memory: vk::DeviceMemory,
+        buffer: B,
+        device_memory: M,
         offset: vk::DeviceSize,
-    ) -> VkResult<()> {
+    ) -> VkResult<()>
+    where
+        Self: Sized,
+        B: AsHandle<vk::Buffer, Self>,
+        M: AsHandle<vk::DeviceMemory, Self>,
+    {
         let err_code = self.fp_v1_0().bind_buffer_memory(
             self.handle(),
-            buffer,
-            device_memory,
+            tracker::used(self.handle(), buffer.as_handle()),
+            tracker::used(self.handle(), device_memory.as_handle()),
             offset,
         );
         match err_code {
@@ -1339,16 +1570,21 @@ pub trait DeviceV1_0 {
         }
     }
 
-    unsafe fn bind_image_memory(
+    unsafe fn bind_image_memory<I, M>(
         &self,
-        image: vk::Image,
-        device_memory: vk::DeviceMemory,
+        image: I,
+        device_memory: M,
         offset: vk::DeviceSize,
-    ) -> VkResult<()> {
+    ) -> VkResult<()>
+    where
+        Self: Sized,
+        I: AsHandle<vk::Image, Self>,
+        M: AsHandle<vk::DeviceMemory, Self>,
+    {
         let err_code = self.fp_v1_0().bind_image_memory(
             self.handle(),
-            image,
-            device_memory,
+            tracker::used(self.handle(), image.as_handle()),
+            tracker::used(self.handle(), device_memory.as_handle()),
             offset,
         );
         match err_code {
@@ -1362,6 +1598,7 @@ pub trait DeviceV1_0 {
 pub struct Device<V: FunctionPointers> {
     handle: vk::Device,
     device_fn: V::DeviceFp,
+    enabled_extensions: Option<Vec<CString>>,
 }
 
 impl DeviceV1_0 for Device<V1_0> {
@@ -1383,6 +1620,58 @@ impl<V: FunctionPointers> Device<V> {
         Device {
             handle: handle,
             device_fn: device_fn,
+            enabled_extensions: None,
         }
     }
+
+    /// Records the extensions that `load` checks against. Device extensions can require
+    /// instance extensions, so this should contain the extensions enabled on the instance as well
+    /// as the ones enabled on the device.
+    pub fn with_enabled_extensions(mut self, enabled_extensions: Vec<CString>) -> Self {
+        self.enabled_extensions = Some(enabled_extensions);
+        self
+    }
+
+    /// The instance and device extensions that `load` checks against, if they are known.
+    pub fn enabled_extensions(&self) -> Option<&[CString]> {
+        self.enabled_extensions.as_ref().map(|names| names.as_slice())
+    }
+
+    /// Loads a device extension after checking that it and the extensions it requires were
+    /// enabled.
+    ///
+    /// ```rust,ignore
+    /// let swapchain_loader = device.load::<Swapchain>()?;
+    /// ```
+    pub fn load<E: DeviceExtension>(&self) -> Result<E, ExtensionError> {
+        if let Some(ref enabled_extensions) = self.enabled_extensions {
+            let missing = missing_extensions::<E>(enabled_extensions);
+            if !missing.is_empty() {
+                return Err(ExtensionError::NotEnabled(missing));
+            }
+        }
+        let handle = self.handle;
+        let instance_fn = self.device_fn.instance_fn();
+        E::load_with(handle, |name| unsafe {
+            mem::transmute(instance_fn.get_device_proc_addr(handle, name.as_ptr()))
+        }).map_err(|err| ExtensionError::LoadError(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeviceV1_0;
+    use prelude::*;
+
+    fn wait_idle(device: &dyn DeviceV1_0) -> VkResult<()> {
+        device.device_wait_idle()
+    }
+
+    /// The methods that are generic over the handle types require `Self: Sized`, so that
+    /// `DeviceV1_0` can still be used as a trait object.
+    #[test]
+    fn trait_object() {
+        let wait_idle: fn(&dyn DeviceV1_0) -> VkResult<()> = wait_idle;
+        let _ = wait_idle;
+    }
 }
@@ -2,12 +2,14 @@ use prelude::*;
 use std::mem;
 use std::ptr;
 use vk;
+use tracker;
 use instance::Instance;
 use shared_library::dynamic_library::DynamicLibrary;
 use std::path::Path;
 use std::error::Error;
 use std::fmt;
 use RawPtr;
+use extensions::extension_names_from_raw;
 use version::{EntryLoader, FunctionPointers, InstanceLoader, V1_0};
 
 #[cfg(windows)]
@@ -92,12 +94,17 @@ pub trait EntryV1_0 {
         if err_code != vk::Result::Success {
             return Err(InstanceError::VkError(err_code));
         }
+        let instance = tracker::created_instance(instance);
         let instance_fp =
             <<Self as EntryV1_0>::Fp as FunctionPointers>::InstanceFp::load(
                 &self.static_fn(),
                 instance,
             ).map_err(|err| InstanceError::LoadError(err))?;
-        Ok(Instance::from_raw(instance, instance_fp))
+        let enabled_extensions = extension_names_from_raw(
+            create_info.pp_enabled_extension_names,
+            create_info.enabled_extension_count,
+        );
+        Ok(Instance::from_raw(instance, instance_fp).with_enabled_extensions(enabled_extensions))
     }
 
     fn enumerate_instance_layer_properties(&self) -> VkResult<Vec<vk::LayerProperties>> {
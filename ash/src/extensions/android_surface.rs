@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct AndroidSurface {
@@ -17,15 +19,11 @@ impl AndroidSurface {
         entry: &E,
         instance: &I,
     ) -> Result<AndroidSurface, Vec<&'static str>> {
-        let surface_fn = vk::AndroidSurfaceFn::load(|name| unsafe {
+        AndroidSurface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(AndroidSurface {
-            handle: instance.handle(),
-            android_surface_fn: surface_fn,
         })
     }
 
@@ -46,8 +44,28 @@ impl AndroidSurface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
 }
+
+impl Extension for AndroidSurface {
+    const NAME: &'static str = vk::VK_KHR_ANDROID_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_ANDROID_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for AndroidSurface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<AndroidSurface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::AndroidSurfaceFn::load(f)?;
+        Ok(AndroidSurface {
+            handle: instance,
+            android_surface_fn: surface_fn,
+        })
+    }
+}
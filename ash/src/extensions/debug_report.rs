@@ -1,10 +1,16 @@
 #![allow(dead_code)]
 use prelude::*;
+use std::borrow::Cow;
+use std::fmt;
 use std::mem;
+use std::ptr;
 use vk;
+use tracker;
+use raw;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct DebugReport {
@@ -12,20 +18,103 @@ pub struct DebugReport {
     debug_report_fn: vk::DebugReportFn,
 }
 
+/// A message passed to a closure registered with `create_debug_report_callback`.
+#[derive(Debug, Clone)]
+pub struct DebugReportMessage<'a> {
+    pub flags: vk::DebugReportFlagsEXT,
+    pub object_type: vk::DebugReportObjectTypeEXT,
+    pub object: vk::uint64_t,
+    pub location: vk::size_t,
+    pub message_code: vk::int32_t,
+    pub layer_prefix: Cow<'a, str>,
+    pub message: Cow<'a, str>,
+}
+
+impl<'a> fmt::Display for DebugReportMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.layer_prefix, self.message)
+    }
+}
+
+/// The closure of a `DebugReportCallback`, or of a create info from
+/// `DebugReport::closure_create_info`.
+pub type DebugReportClosure = Box<Fn(&DebugReportMessage) + Send + Sync>;
+
+/// A debug report callback that calls a Rust closure. The closure is kept alive until the
+/// callback is passed to `destroy_debug_report_callback`; if that never happens the closure is
+/// leaked rather than freed while Vulkan can still call it.
+pub struct DebugReportCallback {
+    handle: vk::DebugReportCallbackEXT,
+    closure: *mut DebugReportClosure,
+}
+
+unsafe impl Send for DebugReportCallback {}
+unsafe impl Sync for DebugReportCallback {}
+
+impl DebugReportCallback {
+    pub fn handle(&self) -> vk::DebugReportCallbackEXT {
+        self.handle
+    }
+}
+
+unsafe extern "system" fn debug_report_closure_callback(
+    flags: vk::DebugReportFlagsEXT,
+    object_type: vk::DebugReportObjectTypeEXT,
+    object: vk::uint64_t,
+    location: vk::size_t,
+    message_code: vk::int32_t,
+    p_layer_prefix: *const vk::c_char,
+    p_message: *const vk::c_char,
+    p_user_data: *mut vk::c_void,
+) -> vk::Bool32 {
+    let closure = &*(p_user_data as *const DebugReportClosure);
+    let message = DebugReportMessage {
+        flags: flags,
+        object_type: object_type,
+        object: object,
+        location: location,
+        message_code: message_code,
+        layer_prefix: raw::lossy_str(p_layer_prefix),
+        message: raw::lossy_str(p_message),
+    };
+    raw::call_callback(|| closure(&message));
+    vk::VK_FALSE
+}
+
+/// Forwards `message` to the `log` crate, with the level picked from the most severe flag.
+///
+/// ```rust,ignore
+/// let callback = debug_report.create_debug_report_callback(
+///     vk::DebugReportFlagsEXT::all(),
+///     log_debug_report_message,
+///     None,
+/// )?;
+/// ```
+#[cfg(feature = "log")]
+pub fn log_debug_report_message(message: &DebugReportMessage) {
+    if message.flags.intersects(vk::DEBUG_REPORT_ERROR_BIT_EXT) {
+        error!("{}", message);
+    } else if message.flags.intersects(vk::DEBUG_REPORT_WARNING_BIT_EXT)
+        || message.flags.intersects(vk::DEBUG_REPORT_PERFORMANCE_WARNING_BIT_EXT)
+    {
+        warn!("{}", message);
+    } else if message.flags.intersects(vk::DEBUG_REPORT_INFORMATION_BIT_EXT) {
+        info!("{}", message);
+    } else {
+        debug!("{}", message);
+    }
+}
+
 impl DebugReport {
     pub fn new<E: EntryV1_0, I: InstanceV1_0>(
         entry: &E,
         instance: &I,
     ) -> Result<DebugReport, Vec<&'static str>> {
-        let debug_report_fn = vk::DebugReportFn::load(|name| unsafe {
+        DebugReport::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(DebugReport {
-            handle: instance.handle(),
-            debug_report_fn: debug_report_fn,
         })
     }
 
@@ -40,7 +129,7 @@ impl DebugReport {
     ) {
         self.debug_report_fn.destroy_debug_report_callback_ext(
             self.handle,
-            debug,
+            tracker::destroyed(self.handle, debug),
             allocation_callbacks.as_raw_ptr(),
         );
     }
@@ -58,8 +147,103 @@ impl DebugReport {
             &mut debug_cb,
         );
         match err_code {
-            vk::Result::Success => Ok(debug_cb),
+            vk::Result::Success => Ok(tracker::created(self.handle, debug_cb)),
             _ => Err(err_code),
         }
     }
+
+    /// A create info that calls `closure` for the messages selected by `flags`. Chained into
+    /// `InstanceCreateInfo::p_next`, it reports the messages of `create_instance` and
+    /// `destroy_instance`, which no registered callback can.
+    ///
+    /// The create info points to `closure`, which therefore has to outlive the instance.
+    pub unsafe fn closure_create_info(
+        flags: vk::DebugReportFlagsEXT,
+        closure: &DebugReportClosure,
+    ) -> vk::DebugReportCallbackCreateInfoEXT {
+        vk::DebugReportCallbackCreateInfoEXT {
+            s_type: vk::StructureType::DebugReportCallbackCreateInfoExt,
+            p_next: ptr::null(),
+            flags: flags,
+            pfn_callback: debug_report_closure_callback,
+            p_user_data: closure as *const _ as *mut vk::c_void,
+        }
+    }
+
+    /// Registers `callback` for the messages selected by `flags`.
+    pub unsafe fn create_debug_report_callback<F>(
+        &self,
+        flags: vk::DebugReportFlagsEXT,
+        callback: F,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<DebugReportCallback>
+    where
+        F: Fn(&DebugReportMessage) + Send + Sync + 'static,
+    {
+        let closure: DebugReportClosure = Box::new(callback);
+        let closure = Box::into_raw(Box::new(closure));
+        let create_info = DebugReport::closure_create_info(flags, &*closure);
+        match self.create_debug_report_callback_ext(&create_info, allocation_callbacks) {
+            Ok(handle) => Ok(DebugReportCallback {
+                handle: handle,
+                closure: closure,
+            }),
+            Err(err_code) => {
+                drop(Box::from_raw(closure));
+                Err(err_code)
+            }
+        }
+    }
+
+    /// Destroys `callback` and frees its closure.
+    pub unsafe fn destroy_debug_report_callback(
+        &self,
+        callback: DebugReportCallback,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.destroy_debug_report_callback_ext(callback.handle, allocation_callbacks);
+        drop(Box::from_raw(callback.closure));
+    }
+
+    pub unsafe fn debug_report_message_ext(
+        &self,
+        flags: vk::DebugReportFlagsEXT,
+        object_type: vk::DebugReportObjectTypeEXT,
+        object: vk::uint64_t,
+        location: vk::size_t,
+        message_code: vk::int32_t,
+        layer_prefix: &CStr,
+        message: &CStr,
+    ) {
+        self.debug_report_fn.debug_report_message_ext(
+            self.handle,
+            flags,
+            object_type,
+            object,
+            location,
+            message_code,
+            layer_prefix.as_ptr(),
+            message.as_ptr(),
+        );
+    }
+}
+
+impl Extension for DebugReport {
+    const NAME: &'static str = vk::VK_EXT_DEBUG_REPORT_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_EXT_DEBUG_REPORT_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[];
+}
+
+impl InstanceExtension for DebugReport {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<DebugReport, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let debug_report_fn = vk::DebugReportFn::load(f)?;
+        Ok(DebugReport {
+            handle: instance,
+            debug_report_fn: debug_report_fn,
+        })
+    }
 }
@@ -0,0 +1,331 @@
+#![allow(dead_code)]
+use prelude::*;
+use std::borrow::Cow;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use vk;
+use tracker;
+use raw;
+use std::ffi::CStr;
+use RawPtr;
+use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
+
+#[derive(Clone)]
+pub struct DebugUtils {
+    handle: vk::Instance,
+    debug_utils_fn: vk::DebugUtilsFn,
+}
+
+/// A message passed to a closure registered with `create_debug_utils_messenger`.
+#[derive(Debug, Clone)]
+pub struct DebugUtilsMessage<'a> {
+    pub severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    pub message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    pub message_id_name: Cow<'a, str>,
+    pub message_id_number: vk::int32_t,
+    pub message: Cow<'a, str>,
+    pub queue_labels: Vec<Cow<'a, str>>,
+    pub cmd_buf_labels: Vec<Cow<'a, str>>,
+    pub objects: Vec<DebugUtilsMessageObject<'a>>,
+}
+
+/// An object that a `DebugUtilsMessage` refers to, with the name it was given through
+/// `set_object_name` if there is one.
+#[derive(Debug, Clone)]
+pub struct DebugUtilsMessageObject<'a> {
+    pub object_type: vk::ObjectType,
+    pub object_handle: vk::uint64_t,
+    pub object_name: Option<Cow<'a, str>>,
+}
+
+impl<'a> fmt::Display for DebugUtilsMessage<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.message_id_name, self.message)
+    }
+}
+
+type DebugUtilsClosure = Box<Fn(&DebugUtilsMessage) + Send + Sync>;
+
+/// A debug messenger that calls a Rust closure. The closure is kept alive until the messenger is
+/// passed to `destroy_debug_utils_messenger`; if that never happens the closure is leaked rather
+/// than freed while Vulkan can still call it.
+pub struct DebugUtilsMessenger {
+    handle: vk::DebugUtilsMessengerEXT,
+    closure: *mut DebugUtilsClosure,
+}
+
+unsafe impl Send for DebugUtilsMessenger {}
+unsafe impl Sync for DebugUtilsMessenger {}
+
+impl DebugUtilsMessenger {
+    pub fn handle(&self) -> vk::DebugUtilsMessengerEXT {
+        self.handle
+    }
+}
+
+unsafe extern "system" fn debug_utils_closure_callback(
+    message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+    p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+    p_user_data: *mut vk::c_void,
+) -> vk::Bool32 {
+    let closure = &*(p_user_data as *const DebugUtilsClosure);
+    let data = &*p_callback_data;
+    let message = DebugUtilsMessage {
+        severity: message_severity,
+        message_type: message_type,
+        message_id_name: raw::lossy_str(data.p_message_id_name),
+        message_id_number: data.message_id_number,
+        message: raw::lossy_str(data.p_message),
+        queue_labels: raw::array(data.p_queue_labels, data.queue_label_count as usize)
+            .iter()
+            .map(|label| raw::lossy_str(label.p_label_name))
+            .collect(),
+        cmd_buf_labels: raw::array(data.p_cmd_buf_labels, data.cmd_buf_label_count as usize)
+            .iter()
+            .map(|label| raw::lossy_str(label.p_label_name))
+            .collect(),
+        objects: raw::array(data.p_objects, data.object_count as usize)
+            .iter()
+            .map(|object| DebugUtilsMessageObject {
+                object_type: object.object_type,
+                object_handle: object.object_handle,
+                object_name: if object.p_object_name.is_null() {
+                    None
+                } else {
+                    Some(raw::lossy_str(object.p_object_name))
+                },
+            })
+            .collect(),
+    };
+    raw::call_callback(|| closure(&message));
+    vk::VK_FALSE
+}
+
+/// Forwards `message` to the `log` crate, with the level picked from the severity.
+#[cfg(feature = "log")]
+pub fn log_debug_utils_message(message: &DebugUtilsMessage) {
+    if message.severity.intersects(vk::DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT) {
+        error!("{}", message);
+    } else if message.severity.intersects(vk::DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT) {
+        warn!("{}", message);
+    } else if message.severity.intersects(vk::DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT) {
+        info!("{}", message);
+    } else {
+        debug!("{}", message);
+    }
+}
+
+impl DebugUtils {
+    pub fn new<E: EntryV1_0, I: InstanceV1_0>(
+        entry: &E,
+        instance: &I,
+    ) -> Result<DebugUtils, Vec<&'static str>> {
+        DebugUtils::load_with(instance.handle(), |name| unsafe {
+            mem::transmute(entry.get_instance_proc_addr(
+                instance.handle(),
+                name.as_ptr(),
+            ))
+        })
+    }
+
+    pub fn name() -> &'static CStr {
+        CStr::from_bytes_with_nul(b"VK_EXT_debug_utils\0").expect("Wrong extension string")
+    }
+
+    pub unsafe fn set_debug_utils_object_name_ext(
+        &self,
+        device: vk::Device,
+        name_info: &vk::DebugUtilsObjectNameInfoEXT,
+    ) -> VkResult<()> {
+        let err_code = self.debug_utils_fn.set_debug_utils_object_name_ext(device, name_info);
+        match err_code {
+            vk::Result::Success => Ok(()),
+            _ => Err(err_code),
+        }
+    }
+
+    /// Names `object` so that validation messages and capture tools can refer to it by `name`.
+    ///
+    /// ```rust,ignore
+    /// let name = CString::new("shadow-map depth").unwrap();
+    /// debug_utils.set_object_name(device.handle(), depth_image, &name)?;
+    /// ```
+    pub unsafe fn set_object_name<H: vk::Handle>(
+        &self,
+        device: vk::Device,
+        object: H,
+        name: &CStr,
+    ) -> VkResult<()> {
+        let name_info = vk::DebugUtilsObjectNameInfoEXT {
+            s_type: vk::StructureType::DebugUtilsObjectNameInfoExt,
+            p_next: ptr::null(),
+            object_type: H::TYPE,
+            object_handle: object.as_raw(),
+            p_object_name: name.as_ptr(),
+        };
+        self.set_debug_utils_object_name_ext(device, &name_info)
+    }
+
+    pub unsafe fn set_debug_utils_object_tag_ext(
+        &self,
+        device: vk::Device,
+        tag_info: &vk::DebugUtilsObjectTagInfoEXT,
+    ) -> VkResult<()> {
+        let err_code = self.debug_utils_fn.set_debug_utils_object_tag_ext(device, tag_info);
+        match err_code {
+            vk::Result::Success => Ok(()),
+            _ => Err(err_code),
+        }
+    }
+
+    pub unsafe fn queue_begin_debug_utils_label_ext(
+        &self,
+        queue: vk::Queue,
+        label: &vk::DebugUtilsLabelEXT,
+    ) {
+        self.debug_utils_fn.queue_begin_debug_utils_label_ext(queue, label);
+    }
+
+    pub unsafe fn queue_end_debug_utils_label_ext(&self, queue: vk::Queue) {
+        self.debug_utils_fn.queue_end_debug_utils_label_ext(queue);
+    }
+
+    pub unsafe fn queue_insert_debug_utils_label_ext(
+        &self,
+        queue: vk::Queue,
+        label: &vk::DebugUtilsLabelEXT,
+    ) {
+        self.debug_utils_fn.queue_insert_debug_utils_label_ext(queue, label);
+    }
+
+    pub unsafe fn cmd_begin_debug_utils_label_ext(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        label: &vk::DebugUtilsLabelEXT,
+    ) {
+        self.debug_utils_fn.cmd_begin_debug_utils_label_ext(command_buffer, label);
+    }
+
+    pub unsafe fn cmd_end_debug_utils_label_ext(&self, command_buffer: vk::CommandBuffer) {
+        self.debug_utils_fn.cmd_end_debug_utils_label_ext(command_buffer);
+    }
+
+    pub unsafe fn cmd_insert_debug_utils_label_ext(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        label: &vk::DebugUtilsLabelEXT,
+    ) {
+        self.debug_utils_fn.cmd_insert_debug_utils_label_ext(command_buffer, label);
+    }
+
+    pub unsafe fn create_debug_utils_messenger_ext(
+        &self,
+        create_info: &vk::DebugUtilsMessengerCreateInfoEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::DebugUtilsMessengerEXT> {
+        let mut messenger = mem::uninitialized();
+        let err_code = self.debug_utils_fn.create_debug_utils_messenger_ext(
+            self.handle,
+            create_info,
+            allocation_callbacks.as_raw_ptr(),
+            &mut messenger,
+        );
+        match err_code {
+            vk::Result::Success => Ok(tracker::created(self.handle, messenger)),
+            _ => Err(err_code),
+        }
+    }
+
+    pub unsafe fn destroy_debug_utils_messenger_ext(
+        &self,
+        messenger: vk::DebugUtilsMessengerEXT,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.debug_utils_fn.destroy_debug_utils_messenger_ext(
+            self.handle,
+            tracker::destroyed(self.handle, messenger),
+            allocation_callbacks.as_raw_ptr(),
+        );
+    }
+
+    /// Registers `callback` for the messages selected by `message_severity` and `message_type`.
+    pub unsafe fn create_debug_utils_messenger<F>(
+        &self,
+        message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+        message_type: vk::DebugUtilsMessageTypeFlagsEXT,
+        callback: F,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<DebugUtilsMessenger>
+    where
+        F: Fn(&DebugUtilsMessage) + Send + Sync + 'static,
+    {
+        let closure: DebugUtilsClosure = Box::new(callback);
+        let closure = Box::into_raw(Box::new(closure));
+        let create_info = vk::DebugUtilsMessengerCreateInfoEXT {
+            s_type: vk::StructureType::DebugUtilsMessengerCreateInfoExt,
+            p_next: ptr::null(),
+            flags: Default::default(),
+            message_severity: message_severity,
+            message_type: message_type,
+            pfn_user_callback: debug_utils_closure_callback,
+            p_user_data: closure as *mut vk::c_void,
+        };
+        match self.create_debug_utils_messenger_ext(&create_info, allocation_callbacks) {
+            Ok(handle) => Ok(DebugUtilsMessenger {
+                handle: handle,
+                closure: closure,
+            }),
+            Err(err_code) => {
+                drop(Box::from_raw(closure));
+                Err(err_code)
+            }
+        }
+    }
+
+    /// Destroys `messenger` and frees its closure.
+    pub unsafe fn destroy_debug_utils_messenger(
+        &self,
+        messenger: DebugUtilsMessenger,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) {
+        self.destroy_debug_utils_messenger_ext(messenger.handle, allocation_callbacks);
+        drop(Box::from_raw(messenger.closure));
+    }
+
+    pub unsafe fn submit_debug_utils_message_ext(
+        &self,
+        message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+        message_types: vk::DebugUtilsMessageTypeFlagsEXT,
+        callback_data: &vk::DebugUtilsMessengerCallbackDataEXT,
+    ) {
+        self.debug_utils_fn.submit_debug_utils_message_ext(
+            self.handle,
+            message_severity,
+            message_types,
+            callback_data,
+        );
+    }
+}
+
+impl Extension for DebugUtils {
+    const NAME: &'static str = vk::VK_EXT_DEBUG_UTILS_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_EXT_DEBUG_UTILS_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[];
+}
+
+impl InstanceExtension for DebugUtils {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<DebugUtils, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let debug_utils_fn = vk::DebugUtilsFn::load(f)?;
+        Ok(DebugUtils {
+            handle: instance,
+            debug_utils_fn: debug_utils_fn,
+        })
+    }
+}
@@ -0,0 +1,286 @@
+#![allow(dead_code)]
+use prelude::*;
+use std::ptr;
+use std::mem;
+use vk;
+use tracker;
+use std::ffi::CStr;
+use RawPtr;
+use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
+
+#[derive(Clone)]
+pub struct Display {
+    handle: vk::Instance,
+    display_fn: vk::DisplayFn,
+}
+
+/// A display mode and a plane that can show it, as picked by `pick_mode_and_plane`.
+#[derive(Debug, Clone)]
+pub struct DisplayModeSelection {
+    pub mode: vk::DisplayModePropertiesKHR,
+    pub plane_index: vk::uint32_t,
+    pub plane_stack_index: vk::uint32_t,
+    pub plane_capabilities: vk::DisplayPlaneCapabilitiesKHR,
+}
+
+impl Display {
+    pub fn new<E: EntryV1_0, I: InstanceV1_0>(
+        entry: &E,
+        instance: &I,
+    ) -> Result<Display, Vec<&'static str>> {
+        Display::load_with(instance.handle(), |name| unsafe {
+            mem::transmute(entry.get_instance_proc_addr(
+                instance.handle(),
+                name.as_ptr(),
+            ))
+        })
+    }
+
+    pub fn name() -> &'static CStr {
+        CStr::from_bytes_with_nul(b"VK_KHR_display\0").expect("Wrong extension string")
+    }
+
+    pub fn get_physical_device_display_properties_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<Vec<vk::DisplayPropertiesKHR>> {
+        unsafe {
+            let mut count = 0;
+            self.display_fn.get_physical_device_display_properties_khr(
+                physical_device,
+                &mut count,
+                ptr::null_mut(),
+            );
+            let mut v = Vec::with_capacity(count as usize);
+            let err_code = self.display_fn.get_physical_device_display_properties_khr(
+                physical_device,
+                &mut count,
+                v.as_mut_ptr(),
+            );
+            v.set_len(count as usize);
+            match err_code {
+                vk::Result::Success => Ok(v),
+                _ => Err(err_code),
+            }
+        }
+    }
+
+    pub fn get_physical_device_display_plane_properties_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<Vec<vk::DisplayPlanePropertiesKHR>> {
+        unsafe {
+            let mut count = 0;
+            self.display_fn
+                .get_physical_device_display_plane_properties_khr(
+                    physical_device,
+                    &mut count,
+                    ptr::null_mut(),
+                );
+            let mut v = Vec::with_capacity(count as usize);
+            let err_code = self.display_fn
+                .get_physical_device_display_plane_properties_khr(
+                    physical_device,
+                    &mut count,
+                    v.as_mut_ptr(),
+                );
+            v.set_len(count as usize);
+            match err_code {
+                vk::Result::Success => Ok(v),
+                _ => Err(err_code),
+            }
+        }
+    }
+
+    pub fn get_display_plane_supported_displays_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        plane_index: vk::uint32_t,
+    ) -> VkResult<Vec<vk::DisplayKHR>> {
+        unsafe {
+            let mut count = 0;
+            self.display_fn.get_display_plane_supported_displays_khr(
+                physical_device,
+                plane_index,
+                &mut count,
+                ptr::null_mut(),
+            );
+            let mut v = Vec::with_capacity(count as usize);
+            let err_code = self.display_fn.get_display_plane_supported_displays_khr(
+                physical_device,
+                plane_index,
+                &mut count,
+                v.as_mut_ptr(),
+            );
+            v.set_len(count as usize);
+            match err_code {
+                vk::Result::Success => Ok(v),
+                _ => Err(err_code),
+            }
+        }
+    }
+
+    pub fn get_display_mode_properties_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+    ) -> VkResult<Vec<vk::DisplayModePropertiesKHR>> {
+        unsafe {
+            let mut count = 0;
+            self.display_fn.get_display_mode_properties_khr(
+                physical_device,
+                display,
+                &mut count,
+                ptr::null_mut(),
+            );
+            let mut v = Vec::with_capacity(count as usize);
+            let err_code = self.display_fn.get_display_mode_properties_khr(
+                physical_device,
+                display,
+                &mut count,
+                v.as_mut_ptr(),
+            );
+            v.set_len(count as usize);
+            match err_code {
+                vk::Result::Success => Ok(v),
+                _ => Err(err_code),
+            }
+        }
+    }
+
+    pub unsafe fn create_display_mode_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+        create_info: &vk::DisplayModeCreateInfoKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::DisplayModeKHR> {
+        let mut display_mode = mem::uninitialized();
+        let err_code = self.display_fn.create_display_mode_khr(
+            physical_device,
+            display,
+            create_info,
+            allocation_callbacks.as_raw_ptr(),
+            &mut display_mode,
+        );
+        match err_code {
+            vk::Result::Success => Ok(display_mode),
+            _ => Err(err_code),
+        }
+    }
+
+    pub fn get_display_plane_capabilities_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        mode: vk::DisplayModeKHR,
+        plane_index: vk::uint32_t,
+    ) -> VkResult<vk::DisplayPlaneCapabilitiesKHR> {
+        unsafe {
+            let mut capabilities = mem::uninitialized();
+            let err_code = self.display_fn.get_display_plane_capabilities_khr(
+                physical_device,
+                mode,
+                plane_index,
+                &mut capabilities,
+            );
+            match err_code {
+                vk::Result::Success => Ok(capabilities),
+                _ => Err(err_code),
+            }
+        }
+    }
+
+    pub unsafe fn create_display_plane_surface_khr(
+        &self,
+        create_info: &vk::DisplaySurfaceCreateInfoKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<vk::SurfaceKHR> {
+        let mut surface = mem::uninitialized();
+        let err_code = self.display_fn.create_display_plane_surface_khr(
+            self.handle,
+            create_info,
+            allocation_callbacks.as_raw_ptr(),
+            &mut surface,
+        );
+        match err_code {
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
+            _ => Err(err_code),
+        }
+    }
+
+    /// Picks the mode of `display` with the largest visible region, preferring the higher refresh
+    /// rate between modes of the same size, and the first plane that can show it.
+    ///
+    /// Planes that are already in use by another display are skipped, as are planes that can't
+    /// scale their destination up to the full visible region of the mode. Returns `None` if the
+    /// display has no modes or no plane is left.
+    pub fn pick_mode_and_plane(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        display: vk::DisplayKHR,
+    ) -> VkResult<Option<DisplayModeSelection>> {
+        let mode = match self.get_display_mode_properties_khr(physical_device, display)?
+            .into_iter()
+            .max_by_key(|mode| {
+                let region = mode.parameters.visible_region;
+                (
+                    region.width as u64 * region.height as u64,
+                    mode.parameters.refresh_rate,
+                )
+            }) {
+            Some(mode) => mode,
+            None => return Ok(None),
+        };
+        let planes = self.get_physical_device_display_plane_properties_khr(physical_device)?;
+        for (plane_index, plane) in planes.iter().enumerate() {
+            let plane_index = plane_index as vk::uint32_t;
+            if plane.current_display != vk::DisplayKHR::null() && plane.current_display != display {
+                continue;
+            }
+            let supported_displays =
+                self.get_display_plane_supported_displays_khr(physical_device, plane_index)?;
+            if !supported_displays.contains(&display) {
+                continue;
+            }
+            let capabilities = self.get_display_plane_capabilities_khr(
+                physical_device,
+                mode.display_mode,
+                plane_index,
+            )?;
+            let region = mode.parameters.visible_region;
+            if capabilities.max_dst_extent.width < region.width
+                || capabilities.max_dst_extent.height < region.height
+            {
+                continue;
+            }
+            return Ok(Some(DisplayModeSelection {
+                mode: mode,
+                plane_index: plane_index,
+                plane_stack_index: plane.current_stack_index,
+                plane_capabilities: capabilities,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+impl Extension for Display {
+    const NAME: &'static str = vk::VK_KHR_DISPLAY_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_DISPLAY_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for Display {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<Display, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let display_fn = vk::DisplayFn::load(f)?;
+        Ok(Display {
+            handle: instance,
+            display_fn: display_fn,
+        })
+    }
+}
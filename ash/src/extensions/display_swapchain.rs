@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{InstanceV1_0, DeviceV1_0};
+use extensions::{Extension, ExtensionLevel, DeviceExtension};
 
 #[derive(Clone)]
 pub struct DisplaySwapchain {
@@ -17,15 +19,11 @@ impl DisplaySwapchain {
         instance: &I,
         device: &D,
     ) -> Result<DisplaySwapchain, Vec<&'static str>> {
-        let swapchain_fn = vk::DisplaySwapchainFn::load(|name| unsafe {
+        DisplaySwapchain::load_with(device.handle(), |name| unsafe {
             mem::transmute(instance.get_device_proc_addr(
                 device.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(DisplaySwapchain {
-            handle: device.handle(),
-            swapchain_fn: swapchain_fn,
         })
     }
 
@@ -48,8 +46,31 @@ impl DisplaySwapchain {
         );
         swapchains.set_len(create_infos.len());
         match err_code {
-            vk::Result::Success => Ok(swapchains),
+            vk::Result::Success => Ok(tracker::created_all(self.handle, swapchains)),
             _ => Err(err_code),
         }
     }
 }
+
+impl Extension for DisplaySwapchain {
+    const NAME: &'static str = vk::VK_KHR_DISPLAY_SWAPCHAIN_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_DISPLAY_SWAPCHAIN_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Device;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[
+        vk::VK_KHR_SWAPCHAIN_EXTENSION_NAME,
+        vk::VK_KHR_DISPLAY_EXTENSION_NAME,
+    ];
+}
+
+impl DeviceExtension for DisplaySwapchain {
+    fn load_with<F>(device: vk::Device, f: F) -> Result<DisplaySwapchain, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let swapchain_fn = vk::DisplaySwapchainFn::load(f)?;
+        Ok(DisplaySwapchain {
+            handle: device,
+            swapchain_fn: swapchain_fn,
+        })
+    }
+}
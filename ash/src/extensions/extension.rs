@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::slice;
+use vk;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExtensionLevel {
+    Instance,
+    Device,
+}
+
+/// Describes a Vulkan extension that has a loader in `ash::extensions`.
+pub trait Extension: Sized {
+    /// The name that has to be passed in `pp_enabled_extension_names`.
+    const NAME: &'static str;
+    /// The revision of the extension that the loader was written against.
+    const SPEC_VERSION: u32;
+    const LEVEL: ExtensionLevel;
+    /// Extensions that have to be enabled as well. Device extensions can depend on instance
+    /// extensions, for example `VK_KHR_swapchain` on `VK_KHR_surface`.
+    const REQUIRED_EXTENSIONS: &'static [&'static str];
+}
+
+pub trait InstanceExtension: Extension {
+    /// Loads the extension with `f`, which resolves names through `vkGetInstanceProcAddr`.
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<Self, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void;
+}
+
+pub trait DeviceExtension: Extension {
+    /// Loads the extension with `f`, which resolves names through `vkGetDeviceProcAddr`.
+    fn load_with<F>(device: vk::Device, f: F) -> Result<Self, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void;
+}
+
+#[derive(Debug)]
+pub enum ExtensionError {
+    /// The extension, or some of the extensions it requires, were not enabled.
+    NotEnabled(Vec<&'static str>),
+    LoadError(Vec<&'static str>),
+}
+
+impl fmt::Display for ExtensionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ExtensionError::{:?}", self)
+    }
+}
+
+impl Error for ExtensionError {
+    fn description(&self) -> &str {
+        "ExtensionError"
+    }
+}
+
+/// Returns `E::NAME` and the names in `E::REQUIRED_EXTENSIONS` that are not in `enabled`.
+pub fn missing_extensions<E: Extension>(enabled: &[CString]) -> Vec<&'static str> {
+    Some(E::NAME)
+        .into_iter()
+        .chain(E::REQUIRED_EXTENSIONS.iter().cloned())
+        .filter(|name| {
+            !enabled
+                .iter()
+                .any(|enabled_name| enabled_name.as_bytes() == name.as_bytes())
+        })
+        .collect()
+}
+
+/// Copies the names out of `pp_enabled_extension_names` in a create info.
+pub unsafe fn extension_names_from_raw(
+    names: *const *const vk::c_char,
+    count: vk::uint32_t,
+) -> Vec<CString> {
+    if names.is_null() || count == 0 {
+        return Vec::new();
+    }
+    slice::from_raw_parts(names, count as usize)
+        .iter()
+        .map(|&name| CStr::from_ptr(name).to_owned())
+        .collect()
+}
@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct IOSSurface {
@@ -17,20 +19,16 @@ impl IOSSurface {
         entry: &E,
         instance: &I,
     ) -> Result<IOSSurface, Vec<&'static str>> {
-        let surface_fn = vk::IOSSurfaceFn::load(|name| unsafe {
+        IOSSurface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(IOSSurface {
-            handle: instance.handle(),
-            ios_surface_fn: surface_fn,
         })
     }
 
     pub fn name() -> &'static CStr {
-        CStr::from_bytes_with_nul(b"VK_MVK_IOS_surface\0").expect("Wrong extension string")
+        CStr::from_bytes_with_nul(b"VK_MVK_ios_surface\0").expect("Wrong extension string")
     }
 
     pub unsafe fn create_ios_surface_mvk(
@@ -46,8 +44,28 @@ impl IOSSurface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
 }
+
+impl Extension for IOSSurface {
+    const NAME: &'static str = vk::VK_MVK_IOS_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_MVK_IOS_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for IOSSurface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<IOSSurface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::IOSSurfaceFn::load(f)?;
+        Ok(IOSSurface {
+            handle: instance,
+            ios_surface_fn: surface_fn,
+        })
+    }
+}
@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct MacOSSurface {
@@ -17,15 +19,11 @@ impl MacOSSurface {
         entry: &E,
         instance: &I,
     ) -> Result<MacOSSurface, Vec<&'static str>> {
-        let surface_fn = vk::MacOSSurfaceFn::load(|name| unsafe {
+        MacOSSurface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(MacOSSurface {
-            handle: instance.handle(),
-            macos_surface_fn: surface_fn,
         })
     }
 
@@ -46,8 +44,28 @@ impl MacOSSurface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
 }
+
+impl Extension for MacOSSurface {
+    const NAME: &'static str = vk::VK_MVK_MACOS_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_MVK_MACOS_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for MacOSSurface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<MacOSSurface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::MacOSSurfaceFn::load(f)?;
+        Ok(MacOSSurface {
+            handle: instance,
+            macos_surface_fn: surface_fn,
+        })
+    }
+}
@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct MirSurface {
@@ -17,15 +19,11 @@ impl MirSurface {
         entry: &E,
         instance: &I,
     ) -> Result<MirSurface, Vec<&'static str>> {
-        let surface_fn = vk::MirSurfaceFn::load(|name| unsafe {
+        MirSurface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(MirSurface {
-            handle: instance.handle(),
-            mir_surface_fn: surface_fn,
         })
     }
 
@@ -46,8 +44,42 @@ impl MirSurface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
+
+    pub unsafe fn get_physical_device_mir_presentation_support_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        queue_family_index: vk::uint32_t,
+        connection: *mut vk::MirConnection,
+    ) -> bool {
+        self.mir_surface_fn
+            .get_physical_device_mir_presentation_support_khr(
+                physical_device,
+                queue_family_index,
+                connection,
+            ) > 0
+    }
+}
+
+impl Extension for MirSurface {
+    const NAME: &'static str = vk::VK_KHR_MIR_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_MIR_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for MirSurface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<MirSurface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::MirSurfaceFn::load(f)?;
+        Ok(MirSurface {
+            handle: instance,
+            mir_surface_fn: surface_fn,
+        })
+    }
 }
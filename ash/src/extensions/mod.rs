@@ -1,8 +1,18 @@
+pub use self::extension::{missing_extensions, extension_names_from_raw, DeviceExtension, Extension,
+                          ExtensionError, ExtensionLevel, InstanceExtension};
 pub use self::swapchain::Swapchain;
+pub use self::display::{Display, DisplayModeSelection};
 pub use self::display_swapchain::DisplaySwapchain;
 pub use self::surface::Surface;
 pub use self::xlib_surface::XlibSurface;
-pub use self::debug_report::DebugReport;
+pub use self::debug_report::{DebugReport, DebugReportCallback, DebugReportClosure,
+                              DebugReportMessage};
+#[cfg(feature = "log")]
+pub use self::debug_report::log_debug_report_message;
+pub use self::debug_utils::{DebugUtils, DebugUtilsMessage, DebugUtilsMessageObject,
+                            DebugUtilsMessenger};
+#[cfg(feature = "log")]
+pub use self::debug_utils::log_debug_utils_message;
 pub use self::win32_surface::Win32Surface;
 pub use self::mir_surface::MirSurface;
 pub use self::xcb_surface::XcbSurface;
@@ -11,12 +21,15 @@ pub use self::android_surface::AndroidSurface;
 pub use self::macos_surface::MacOSSurface;
 pub use self::ios_surface::IOSSurface;
 
+mod extension;
 mod swapchain;
+mod display;
 mod display_swapchain;
 mod surface;
 mod xlib_surface;
 mod win32_surface;
 mod debug_report;
+mod debug_utils;
 mod mir_surface;
 mod android_surface;
 mod wayland_surface;
@@ -3,9 +3,11 @@ use prelude::*;
 use std::ptr;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct Surface {
@@ -18,15 +20,11 @@ impl Surface {
         entry: &E,
         instance: &I,
     ) -> Result<Surface, Vec<&'static str>> {
-        let surface_fn = vk::SurfaceFn::load(|name| unsafe {
+        Surface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(Surface {
-            handle: instance.handle(),
-            surface_fn: surface_fn,
         })
     }
 
@@ -136,8 +134,28 @@ impl Surface {
     ) {
         self.surface_fn.destroy_surface_khr(
             self.handle,
-            surface,
+            tracker::destroyed(self.handle, surface),
             allocation_callbacks.as_raw_ptr(),
         );
     }
 }
+
+impl Extension for Surface {
+    const NAME: &'static str = vk::VK_KHR_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[];
+}
+
+impl InstanceExtension for Surface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<Surface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::SurfaceFn::load(f)?;
+        Ok(Surface {
+            handle: instance,
+            surface_fn: surface_fn,
+        })
+    }
+}
@@ -3,9 +3,11 @@ use prelude::*;
 use std::ptr;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{InstanceV1_0, DeviceV1_0};
+use extensions::{Extension, ExtensionLevel, DeviceExtension};
 
 #[derive(Clone)]
 pub struct Swapchain {
@@ -18,15 +20,11 @@ impl Swapchain {
         instance: &I,
         device: &D,
     ) -> Result<Swapchain, Vec<&'static str>> {
-        let swapchain_fn = vk::SwapchainFn::load(|name| unsafe {
+        Swapchain::load_with(device.handle(), |name| unsafe {
             mem::transmute(instance.get_device_proc_addr(
                 device.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(Swapchain {
-            handle: device.handle(),
-            swapchain_fn: swapchain_fn,
         })
     }
 
@@ -41,7 +39,7 @@ impl Swapchain {
     ) {
         self.swapchain_fn.destroy_swapchain_khr(
             self.handle,
-            swapchain,
+            tracker::destroyed(self.handle, swapchain),
             allocation_callbacks.as_raw_ptr(),
         );
     }
@@ -81,7 +79,7 @@ impl Swapchain {
             &mut swapchain,
         );
         match err_code {
-            vk::Result::Success => Ok(swapchain),
+            vk::Result::Success => Ok(tracker::created(self.handle, swapchain)),
             _ => Err(err_code),
         }
     }
@@ -126,3 +124,23 @@ impl Swapchain {
         }
     }
 }
+
+impl Extension for Swapchain {
+    const NAME: &'static str = vk::VK_KHR_SWAPCHAIN_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_SWAPCHAIN_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Device;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl DeviceExtension for Swapchain {
+    fn load_with<F>(device: vk::Device, f: F) -> Result<Swapchain, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let swapchain_fn = vk::SwapchainFn::load(f)?;
+        Ok(Swapchain {
+            handle: device,
+            swapchain_fn: swapchain_fn,
+        })
+    }
+}
@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct WaylandSurface {
@@ -17,15 +19,11 @@ impl WaylandSurface {
         entry: &E,
         instance: &I,
     ) -> Result<WaylandSurface, Vec<&'static str>> {
-        let surface_fn = vk::WaylandSurfaceFn::load(|name| unsafe {
+        WaylandSurface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(WaylandSurface {
-            handle: instance.handle(),
-            wayland_surface_fn: surface_fn,
         })
     }
 
@@ -46,8 +44,42 @@ impl WaylandSurface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
+
+    pub unsafe fn get_physical_device_wayland_presentation_support_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        queue_family_index: vk::uint32_t,
+        display: *mut vk::wl_display,
+    ) -> bool {
+        self.wayland_surface_fn
+            .get_physical_device_wayland_presentation_support_khr(
+                physical_device,
+                queue_family_index,
+                display,
+            ) > 0
+    }
+}
+
+impl Extension for WaylandSurface {
+    const NAME: &'static str = vk::VK_KHR_WAYLAND_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_WAYLAND_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for WaylandSurface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<WaylandSurface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::WaylandSurfaceFn::load(f)?;
+        Ok(WaylandSurface {
+            handle: instance,
+            wayland_surface_fn: surface_fn,
+        })
+    }
 }
@@ -2,9 +2,11 @@
 use prelude::*;
 use std::mem;
 use vk;
+use tracker;
 use std::ffi::CStr;
 use RawPtr;
 use version::{EntryV1_0, InstanceV1_0};
+use extensions::{Extension, ExtensionLevel, InstanceExtension};
 
 #[derive(Clone)]
 pub struct Win32Surface {
@@ -17,15 +19,11 @@ impl Win32Surface {
         entry: &E,
         instance: &I,
     ) -> Result<Win32Surface, Vec<&'static str>> {
-        let surface_fn = vk::Win32SurfaceFn::load(|name| unsafe {
+        Win32Surface::load_with(instance.handle(), |name| unsafe {
             mem::transmute(entry.get_instance_proc_addr(
                 instance.handle(),
                 name.as_ptr(),
             ))
-        })?;
-        Ok(Win32Surface {
-            handle: instance.handle(),
-            win32_surface_fn: surface_fn,
         })
     }
 
@@ -46,8 +44,42 @@ impl Win32Surface {
             &mut surface,
         );
         match err_code {
-            vk::Result::Success => Ok(surface),
+            vk::Result::Success => Ok(tracker::created(self.handle, surface)),
             _ => Err(err_code),
         }
     }
+
+    pub fn get_physical_device_win32_presentation_support_khr(
+        &self,
+        physical_device: vk::PhysicalDevice,
+        queue_family_index: vk::uint32_t,
+    ) -> bool {
+        unsafe {
+            self.win32_surface_fn
+                .get_physical_device_win32_presentation_support_khr(
+                    physical_device,
+                    queue_family_index,
+                ) > 0
+        }
+    }
+}
+
+impl Extension for Win32Surface {
+    const NAME: &'static str = vk::VK_KHR_WIN32_SURFACE_EXTENSION_NAME;
+    const SPEC_VERSION: u32 = vk::VK_KHR_WIN32_SURFACE_SPEC_VERSION;
+    const LEVEL: ExtensionLevel = ExtensionLevel::Instance;
+    const REQUIRED_EXTENSIONS: &'static [&'static str] = &[vk::VK_KHR_SURFACE_EXTENSION_NAME];
+}
+
+impl InstanceExtension for Win32Surface {
+    fn load_with<F>(instance: vk::Instance, f: F) -> Result<Win32Surface, Vec<&'static str>>
+    where
+        F: FnMut(&CStr) -> *const vk::c_void,
+    {
+        let surface_fn = vk::Win32SurfaceFn::load(f)?;
+        Ok(Win32Surface {
+            handle: instance,
+            win32_surface_fn: surface_fn,
+        })
+    }
 }
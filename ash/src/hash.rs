@@ -0,0 +1,1187 @@
+//! Content hashing for Vulkan structs that reference other data through raw pointers.
+//!
+//! Pointer free structs in `vk` implement `PartialEq`, `Eq` and `Hash` directly. Create infos
+//! and descriptions that point to arrays can't do that without dereferencing those pointers, so
+//! they implement `ContentHash` instead, which follows every array and string that the struct
+//! points to. `p_next` chains, window system handles and user data pointers can't be followed
+//! generically and are hashed by address. Callback tables like `AllocationCallbacks` have no
+//! content to follow and don't implement it.
+//!
+//! ```rust,ignore
+//! let mut render_passes: HashMap<ContentKey, vk::RenderPass> = HashMap::new();
+//! let key = unsafe { ContentKey::new(&renderpass_create_info) };
+//! let renderpass = *render_passes.entry(key).or_insert_with(|| unsafe {
+//!     device.create_render_pass(&renderpass_create_info, None).unwrap()
+//! });
+//! ```
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::CStr;
+use std::hash::{Hash, Hasher};
+use raw::array;
+use vk;
+
+pub trait ContentHash {
+    /// Feeds the struct and everything it points to into `state`.
+    ///
+    /// This is unsafe because every pointer and count inside the struct has to be valid, the
+    /// same as when the struct is passed to Vulkan.
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H);
+}
+
+/// Hashes the content of `value` with the standard library's default hasher.
+pub unsafe fn content_hash<T: ContentHash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash_content(&mut hasher);
+    hasher.finish()
+}
+
+/// An owned snapshot of the content of a `ContentHash` struct.
+///
+/// Two keys are equal exactly when the content they were created from is equal, so they can be
+/// stored as keys in sampler, render pass or pipeline caches after the create info and its
+/// arrays are gone.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContentKey(Vec<u8>);
+
+impl ContentKey {
+    pub unsafe fn new<T: ContentHash>(value: &T) -> ContentKey {
+        let mut recorder = ContentRecorder { bytes: Vec::new() };
+        value.hash_content(&mut recorder);
+        ContentKey(recorder.bytes)
+    }
+}
+
+/// A `Hasher` that keeps every byte it is fed instead of mixing them.
+struct ContentRecorder {
+    bytes: Vec<u8>,
+}
+
+impl Hasher for ContentRecorder {
+    fn write(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&self.bytes);
+        hasher.finish()
+    }
+}
+
+unsafe fn hash_slice<T: Hash, H: Hasher>(ptr: *const T, count: usize, state: &mut H) {
+    array(ptr, count).hash(state);
+}
+
+unsafe fn hash_content_slice<T: ContentHash, H: Hasher>(
+    ptr: *const T,
+    count: usize,
+    state: &mut H,
+) {
+    let items = array(ptr, count);
+    items.len().hash(state);
+    for item in items {
+        item.hash_content(state);
+    }
+}
+
+unsafe fn hash_option<T: Hash, H: Hasher>(ptr: *const T, state: &mut H) {
+    ptr.as_ref().hash(state);
+}
+
+unsafe fn hash_content_option<T: ContentHash, H: Hasher>(ptr: *const T, state: &mut H) {
+    match ptr.as_ref() {
+        Some(value) => {
+            true.hash(state);
+            value.hash_content(state);
+        }
+        None => false.hash(state),
+    }
+}
+
+unsafe fn hash_c_str<H: Hasher>(ptr: *const vk::c_char, state: &mut H) {
+    if ptr.is_null() {
+        None::<&[u8]>.hash(state);
+    } else {
+        Some(CStr::from_ptr(ptr).to_bytes()).hash(state);
+    }
+}
+
+unsafe fn hash_c_str_array<H: Hasher>(
+    ptr: *const *const vk::c_char,
+    count: usize,
+    state: &mut H,
+) {
+    let names = array(ptr, count);
+    names.len().hash(state);
+    for &name in names {
+        hash_c_str(name, state);
+    }
+}
+
+unsafe fn hash_bytes<H: Hasher>(ptr: *const vk::c_void, size: usize, state: &mut H) {
+    hash_slice(ptr as *const u8, size, state);
+}
+
+fn hash_address<T, H: Hasher>(ptr: *const T, state: &mut H) {
+    (ptr as usize).hash(state);
+}
+
+fn hash_float<H: Hasher>(value: vk::c_float, state: &mut H) {
+    value.to_bits().hash(state);
+}
+
+unsafe fn hash_float_slice<H: Hasher>(ptr: *const vk::c_float, count: usize, state: &mut H) {
+    let values = array(ptr, count);
+    values.len().hash(state);
+    for &value in values {
+        hash_float(value, state);
+    }
+}
+
+impl ContentHash for vk::ApplicationInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        hash_c_str(self.p_application_name, state);
+        self.application_version.hash(state);
+        hash_c_str(self.p_engine_name, state);
+        self.engine_version.hash(state);
+        self.api_version.hash(state);
+    }
+}
+
+impl ContentHash for vk::InstanceCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_content_option(self.p_application_info, state);
+        hash_c_str_array(
+            self.pp_enabled_layer_names,
+            self.enabled_layer_count as usize,
+            state,
+        );
+        hash_c_str_array(
+            self.pp_enabled_extension_names,
+            self.enabled_extension_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::DeviceQueueCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.queue_family_index.hash(state);
+        hash_float_slice(self.p_queue_priorities, self.queue_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::DeviceCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_content_slice(
+            self.p_queue_create_infos,
+            self.queue_create_info_count as usize,
+            state,
+        );
+        hash_c_str_array(
+            self.pp_enabled_layer_names,
+            self.enabled_layer_count as usize,
+            state,
+        );
+        hash_c_str_array(
+            self.pp_enabled_extension_names,
+            self.enabled_extension_count as usize,
+            state,
+        );
+        hash_option(self.p_enabled_features, state);
+    }
+}
+
+impl ContentHash for vk::SubmitInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        hash_slice(
+            self.p_wait_semaphores,
+            self.wait_semaphore_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_wait_dst_stage_mask,
+            self.wait_semaphore_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_command_buffers,
+            self.command_buffer_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_signal_semaphores,
+            self.signal_semaphore_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::MemoryAllocateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.allocation_size.hash(state);
+        self.memory_type_index.hash(state);
+    }
+}
+
+impl ContentHash for vk::MappedMemoryRange {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.memory.hash(state);
+        self.offset.hash(state);
+        self.size.hash(state);
+    }
+}
+
+impl ContentHash for vk::SparseBufferMemoryBindInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.buffer.hash(state);
+        hash_slice(self.p_binds, self.bind_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::SparseImageOpaqueMemoryBindInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.image.hash(state);
+        hash_slice(self.p_binds, self.bind_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::SparseImageMemoryBindInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.image.hash(state);
+        hash_slice(self.p_binds, self.bind_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::BindSparseInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        hash_slice(
+            self.p_wait_semaphores,
+            self.wait_semaphore_count as usize,
+            state,
+        );
+        hash_content_slice(self.p_buffer_binds, self.buffer_bind_count as usize, state);
+        hash_content_slice(
+            self.p_image_opaque_binds,
+            self.image_opaque_bind_count as usize,
+            state,
+        );
+        hash_content_slice(self.p_image_binds, self.image_bind_count as usize, state);
+        hash_slice(
+            self.p_signal_semaphores,
+            self.signal_semaphore_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::FenceCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+    }
+}
+
+impl ContentHash for vk::SemaphoreCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+    }
+}
+
+impl ContentHash for vk::EventCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+    }
+}
+
+impl ContentHash for vk::QueryPoolCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.query_type.hash(state);
+        self.query_count.hash(state);
+        self.pipeline_statistics.hash(state);
+    }
+}
+
+impl ContentHash for vk::BufferCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.size.hash(state);
+        self.usage.hash(state);
+        self.sharing_mode.hash(state);
+        hash_slice(
+            self.p_queue_family_indices,
+            self.queue_family_index_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::BufferViewCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.buffer.hash(state);
+        self.format.hash(state);
+        self.offset.hash(state);
+        self.range.hash(state);
+    }
+}
+
+impl ContentHash for vk::ImageCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.image_type.hash(state);
+        self.format.hash(state);
+        self.extent.hash(state);
+        self.mip_levels.hash(state);
+        self.array_layers.hash(state);
+        self.samples.hash(state);
+        self.tiling.hash(state);
+        self.usage.hash(state);
+        self.sharing_mode.hash(state);
+        hash_slice(
+            self.p_queue_family_indices,
+            self.queue_family_index_count as usize,
+            state,
+        );
+        self.initial_layout.hash(state);
+    }
+}
+
+impl ContentHash for vk::ImageViewCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.image.hash(state);
+        self.view_type.hash(state);
+        self.format.hash(state);
+        self.components.hash(state);
+        self.subresource_range.hash(state);
+    }
+}
+
+impl ContentHash for vk::ShaderModuleCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_bytes(self.p_code as *const vk::c_void, self.code_size, state);
+    }
+}
+
+impl ContentHash for vk::PipelineCacheCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_bytes(self.p_initial_data, self.initial_data_size, state);
+    }
+}
+
+impl ContentHash for vk::SpecializationInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        hash_slice(self.p_map_entries, self.map_entry_count as usize, state);
+        hash_bytes(self.p_data, self.data_size, state);
+    }
+}
+
+impl ContentHash for vk::PipelineShaderStageCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.stage.hash(state);
+        self.module.hash(state);
+        hash_c_str(self.p_name, state);
+        hash_content_option(self.p_specialization_info, state);
+    }
+}
+
+impl ContentHash for vk::PipelineVertexInputStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_slice(
+            self.p_vertex_binding_descriptions,
+            self.vertex_binding_description_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_vertex_attribute_descriptions,
+            self.vertex_attribute_description_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::PipelineInputAssemblyStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.topology.hash(state);
+        self.primitive_restart_enable.hash(state);
+    }
+}
+
+impl ContentHash for vk::PipelineTessellationStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.patch_control_points.hash(state);
+    }
+}
+
+impl ContentHash for vk::PipelineViewportStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.viewport_count.hash(state);
+        hash_slice(self.p_viewports, self.viewport_count as usize, state);
+        self.scissor_count.hash(state);
+        hash_slice(self.p_scissors, self.scissor_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::PipelineRasterizationStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.depth_clamp_enable.hash(state);
+        self.rasterizer_discard_enable.hash(state);
+        self.polygon_mode.hash(state);
+        self.cull_mode.hash(state);
+        self.front_face.hash(state);
+        self.depth_bias_enable.hash(state);
+        hash_float(self.depth_bias_constant_factor, state);
+        hash_float(self.depth_bias_clamp, state);
+        hash_float(self.depth_bias_slope_factor, state);
+        hash_float(self.line_width, state);
+    }
+}
+
+impl ContentHash for vk::PipelineMultisampleStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        // The sample mask holds one bit per sample, packed into 32 bit words.
+        let sample_mask_words = (self.rasterization_samples.flags() as usize + 31) / 32;
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.rasterization_samples.hash(state);
+        self.sample_shading_enable.hash(state);
+        hash_float(self.min_sample_shading, state);
+        hash_slice(self.p_sample_mask, sample_mask_words, state);
+        self.alpha_to_coverage_enable.hash(state);
+        self.alpha_to_one_enable.hash(state);
+    }
+}
+
+impl ContentHash for vk::PipelineDepthStencilStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.depth_test_enable.hash(state);
+        self.depth_write_enable.hash(state);
+        self.depth_compare_op.hash(state);
+        self.depth_bounds_test_enable.hash(state);
+        self.stencil_test_enable.hash(state);
+        self.front.hash(state);
+        self.back.hash(state);
+        hash_float(self.min_depth_bounds, state);
+        hash_float(self.max_depth_bounds, state);
+    }
+}
+
+impl ContentHash for vk::PipelineColorBlendStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.logic_op_enable.hash(state);
+        self.logic_op.hash(state);
+        hash_slice(self.p_attachments, self.attachment_count as usize, state);
+        for &blend_constant in &self.blend_constants {
+            hash_float(blend_constant, state);
+        }
+    }
+}
+
+impl ContentHash for vk::PipelineDynamicStateCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_slice(
+            self.p_dynamic_states,
+            self.dynamic_state_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::GraphicsPipelineCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_content_slice(self.p_stages, self.stage_count as usize, state);
+        hash_content_option(self.p_vertex_input_state, state);
+        hash_content_option(self.p_input_assembly_state, state);
+        hash_content_option(self.p_tessellation_state, state);
+        hash_content_option(self.p_viewport_state, state);
+        hash_content_option(self.p_rasterization_state, state);
+        hash_content_option(self.p_multisample_state, state);
+        hash_content_option(self.p_depth_stencil_state, state);
+        hash_content_option(self.p_color_blend_state, state);
+        hash_content_option(self.p_dynamic_state, state);
+        self.layout.hash(state);
+        self.render_pass.hash(state);
+        self.subpass.hash(state);
+        self.base_pipeline_handle.hash(state);
+        self.base_pipeline_index.hash(state);
+    }
+}
+
+impl ContentHash for vk::ComputePipelineCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.stage.hash_content(state);
+        self.layout.hash(state);
+        self.base_pipeline_handle.hash(state);
+        self.base_pipeline_index.hash(state);
+    }
+}
+
+impl ContentHash for vk::PipelineLayoutCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_slice(self.p_set_layouts, self.set_layout_count as usize, state);
+        hash_slice(
+            self.p_push_constant_ranges,
+            self.push_constant_range_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::SamplerCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.mag_filter.hash(state);
+        self.min_filter.hash(state);
+        self.mipmap_mode.hash(state);
+        self.address_mode_u.hash(state);
+        self.address_mode_v.hash(state);
+        self.address_mode_w.hash(state);
+        hash_float(self.mip_lod_bias, state);
+        self.anisotropy_enable.hash(state);
+        hash_float(self.max_anisotropy, state);
+        self.compare_enable.hash(state);
+        self.compare_op.hash(state);
+        hash_float(self.min_lod, state);
+        hash_float(self.max_lod, state);
+        self.border_color.hash(state);
+        self.unnormalized_coordinates.hash(state);
+    }
+}
+
+impl ContentHash for vk::DescriptorSetLayoutBinding {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.binding.hash(state);
+        self.descriptor_type.hash(state);
+        self.descriptor_count.hash(state);
+        self.stage_flags.hash(state);
+        // Immutable samplers are ignored for every other descriptor type.
+        match self.descriptor_type {
+            vk::DescriptorType::Sampler |
+            vk::DescriptorType::CombinedImageSampler => {
+                hash_slice(
+                    self.p_immutable_samplers,
+                    self.descriptor_count as usize,
+                    state,
+                )
+            }
+            _ => (),
+        }
+    }
+}
+
+impl ContentHash for vk::DescriptorSetLayoutCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_content_slice(self.p_bindings, self.binding_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::DescriptorPoolCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.max_sets.hash(state);
+        hash_slice(self.p_pool_sizes, self.pool_size_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::DescriptorSetAllocateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.descriptor_pool.hash(state);
+        hash_slice(
+            self.p_set_layouts,
+            self.descriptor_set_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::WriteDescriptorSet {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        let count = self.descriptor_count as usize;
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.dst_set.hash(state);
+        self.dst_binding.hash(state);
+        self.dst_array_element.hash(state);
+        self.descriptor_type.hash(state);
+        // Only one of the three arrays is read, depending on the descriptor type.
+        match self.descriptor_type {
+            vk::DescriptorType::Sampler |
+            vk::DescriptorType::CombinedImageSampler |
+            vk::DescriptorType::SampledImage |
+            vk::DescriptorType::StorageImage |
+            vk::DescriptorType::InputAttachment => hash_slice(self.p_image_info, count, state),
+            vk::DescriptorType::UniformTexelBuffer |
+            vk::DescriptorType::StorageTexelBuffer => {
+                hash_slice(self.p_texel_buffer_view, count, state)
+            }
+            vk::DescriptorType::UniformBuffer |
+            vk::DescriptorType::StorageBuffer |
+            vk::DescriptorType::UniformBufferDynamic |
+            vk::DescriptorType::StorageBufferDynamic => {
+                hash_slice(self.p_buffer_info, count, state)
+            }
+        }
+    }
+}
+
+impl ContentHash for vk::CopyDescriptorSet {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.src_set.hash(state);
+        self.src_binding.hash(state);
+        self.src_array_element.hash(state);
+        self.dst_set.hash(state);
+        self.dst_binding.hash(state);
+        self.dst_array_element.hash(state);
+        self.descriptor_count.hash(state);
+    }
+}
+
+impl ContentHash for vk::FramebufferCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.render_pass.hash(state);
+        hash_slice(self.p_attachments, self.attachment_count as usize, state);
+        self.width.hash(state);
+        self.height.hash(state);
+        self.layers.hash(state);
+    }
+}
+
+impl ContentHash for vk::SubpassDescription {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.flags.hash(state);
+        self.pipeline_bind_point.hash(state);
+        hash_slice(
+            self.p_input_attachments,
+            self.input_attachment_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_color_attachments,
+            self.color_attachment_count as usize,
+            state,
+        );
+        hash_slice(
+            self.p_resolve_attachments,
+            self.color_attachment_count as usize,
+            state,
+        );
+        hash_option(self.p_depth_stencil_attachment, state);
+        hash_slice(
+            self.p_preserve_attachments,
+            self.preserve_attachment_count as usize,
+            state,
+        );
+    }
+}
+
+impl ContentHash for vk::RenderPassCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_slice(self.p_attachments, self.attachment_count as usize, state);
+        hash_content_slice(self.p_subpasses, self.subpass_count as usize, state);
+        hash_slice(self.p_dependencies, self.dependency_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::CommandPoolCreateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.queue_family_index.hash(state);
+    }
+}
+
+impl ContentHash for vk::CommandBufferAllocateInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.command_pool.hash(state);
+        self.level.hash(state);
+        self.command_buffer_count.hash(state);
+    }
+}
+
+impl ContentHash for vk::CommandBufferInheritanceInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.render_pass.hash(state);
+        self.subpass.hash(state);
+        self.framebuffer.hash(state);
+        self.occlusion_query_enable.hash(state);
+        self.query_flags.hash(state);
+        self.pipeline_statistics.hash(state);
+    }
+}
+
+impl ContentHash for vk::CommandBufferBeginInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_content_option(self.p_inheritance_info, state);
+    }
+}
+
+impl ContentHash for vk::MemoryBarrier {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.src_access_mask.hash(state);
+        self.dst_access_mask.hash(state);
+    }
+}
+
+impl ContentHash for vk::BufferMemoryBarrier {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.src_access_mask.hash(state);
+        self.dst_access_mask.hash(state);
+        self.src_queue_family_index.hash(state);
+        self.dst_queue_family_index.hash(state);
+        self.buffer.hash(state);
+        self.offset.hash(state);
+        self.size.hash(state);
+    }
+}
+
+impl ContentHash for vk::ImageMemoryBarrier {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.src_access_mask.hash(state);
+        self.dst_access_mask.hash(state);
+        self.old_layout.hash(state);
+        self.new_layout.hash(state);
+        self.src_queue_family_index.hash(state);
+        self.dst_queue_family_index.hash(state);
+        self.image.hash(state);
+        self.subresource_range.hash(state);
+    }
+}
+
+impl ContentHash for vk::RenderPassBeginInfo {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.render_pass.hash(state);
+        self.framebuffer.hash(state);
+        self.render_area.hash(state);
+        hash_slice(self.p_clear_values, self.clear_value_count as usize, state);
+    }
+}
+
+impl ContentHash for vk::XlibSurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.dpy, state);
+        self.window.hash(state);
+    }
+}
+
+impl ContentHash for vk::XcbSurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.connection, state);
+        self.window.hash(state);
+    }
+}
+
+impl ContentHash for vk::MirSurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.connection, state);
+        hash_address(self.mir_surface, state);
+    }
+}
+
+impl ContentHash for vk::Win32SurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.hinstance, state);
+        hash_address(self.hwnd, state);
+    }
+}
+
+impl ContentHash for vk::AndroidSurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.window, state);
+    }
+}
+
+impl ContentHash for vk::WaylandSurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.display, state);
+        hash_address(self.surface, state);
+    }
+}
+
+impl ContentHash for vk::IOSSurfaceCreateInfoMVK {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.p_view, state);
+    }
+}
+
+impl ContentHash for vk::MacOSSurfaceCreateInfoMVK {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_address(self.p_view, state);
+    }
+}
+
+impl ContentHash for vk::SwapchainCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.surface.hash(state);
+        self.min_image_count.hash(state);
+        self.image_format.hash(state);
+        self.image_color_space.hash(state);
+        self.image_extent.hash(state);
+        self.image_array_layers.hash(state);
+        self.image_usage.hash(state);
+        self.image_sharing_mode.hash(state);
+        hash_slice(
+            self.p_queue_family_indices,
+            self.queue_family_index_count as usize,
+            state,
+        );
+        self.pre_transform.hash(state);
+        self.composite_alpha.hash(state);
+        self.present_mode.hash(state);
+        self.clipped.hash(state);
+        self.old_swapchain.hash(state);
+    }
+}
+
+impl ContentHash for vk::PresentInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        hash_slice(
+            self.p_wait_semaphores,
+            self.wait_semaphore_count as usize,
+            state,
+        );
+        hash_slice(self.p_swapchains, self.swapchain_count as usize, state);
+        hash_slice(self.p_image_indices, self.swapchain_count as usize, state);
+        // `p_results` is written by the driver and has no content of its own.
+        hash_address(self.p_results, state);
+    }
+}
+
+impl ContentHash for vk::DisplayPresentInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.src_rect.hash(state);
+        self.dst_rect.hash(state);
+        self.persistent.hash(state);
+    }
+}
+
+impl ContentHash for vk::DisplayPropertiesKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.display.hash(state);
+        hash_c_str(self.display_name, state);
+        self.physical_dimensions.hash(state);
+        self.physical_resolution.hash(state);
+        self.supported_transforms.hash(state);
+        self.plane_reorder_possible.hash(state);
+        self.persistent_content.hash(state);
+    }
+}
+
+impl ContentHash for vk::DisplayModeCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.parameters.hash(state);
+    }
+}
+
+impl ContentHash for vk::DisplaySurfaceCreateInfoKHR {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        self.display_mode.hash(state);
+        self.plane_index.hash(state);
+        self.plane_stack_index.hash(state);
+        self.transform.hash(state);
+        hash_float(self.global_alpha, state);
+        self.alpha_mode.hash(state);
+        self.image_extent.hash(state);
+    }
+}
+
+impl ContentHash for vk::DebugUtilsObjectNameInfoEXT {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.object_type.hash(state);
+        self.object_handle.hash(state);
+        hash_c_str(self.p_object_name, state);
+    }
+}
+
+impl ContentHash for vk::DebugUtilsObjectTagInfoEXT {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.object_type.hash(state);
+        self.object_handle.hash(state);
+        self.tag_name.hash(state);
+        hash_bytes(self.p_tag, self.tag_size, state);
+    }
+}
+
+impl ContentHash for vk::DebugUtilsLabelEXT {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        hash_c_str(self.p_label_name, state);
+        for &component in &self.color {
+            hash_float(component, state);
+        }
+    }
+}
+
+impl ContentHash for vk::DebugUtilsMessengerCallbackDataEXT {
+    unsafe fn hash_content<H: Hasher>(&self, state: &mut H) {
+        self.s_type.hash(state);
+        hash_address(self.p_next, state);
+        self.flags.hash(state);
+        hash_c_str(self.p_message_id_name, state);
+        self.message_id_number.hash(state);
+        hash_c_str(self.p_message, state);
+        hash_content_slice(
+            self.p_queue_labels,
+            self.queue_label_count as usize,
+            state,
+        );
+        hash_content_slice(
+            self.p_cmd_buf_labels,
+            self.cmd_buf_label_count as usize,
+            state,
+        );
+        hash_content_slice(self.p_objects, self.object_count as usize, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::ptr;
+
+    fn queue_info(priorities: &[f32]) -> vk::DeviceQueueCreateInfo {
+        vk::DeviceQueueCreateInfo {
+            s_type: vk::StructureType::DeviceQueueCreateInfo,
+            p_next: ptr::null(),
+            flags: vk::DeviceQueueCreateFlags::empty(),
+            queue_family_index: 0,
+            queue_count: priorities.len() as vk::uint32_t,
+            p_queue_priorities: priorities.as_ptr(),
+        }
+    }
+
+    fn instance_info(names: &[*const vk::c_char]) -> vk::InstanceCreateInfo {
+        vk::InstanceCreateInfo {
+            s_type: vk::StructureType::InstanceCreateInfo,
+            p_next: ptr::null(),
+            flags: vk::InstanceCreateFlags::empty(),
+            p_application_info: ptr::null(),
+            enabled_layer_count: 0,
+            pp_enabled_layer_names: ptr::null(),
+            enabled_extension_count: names.len() as vk::uint32_t,
+            pp_enabled_extension_names: names.as_ptr(),
+        }
+    }
+
+    #[test]
+    fn equal_content_behind_different_pointers() {
+        let (first, second) = (vec![1.0, 0.5], vec![1.0, 0.5]);
+        assert_ne!(first.as_ptr(), second.as_ptr());
+        unsafe {
+            let (first, second) = (queue_info(&first), queue_info(&second));
+            assert_eq!(ContentKey::new(&first), ContentKey::new(&second));
+            assert_eq!(content_hash(&first), content_hash(&second));
+        }
+
+        let first = CString::new("VK_KHR_surface").unwrap();
+        let second = first.clone();
+        unsafe {
+            let first = instance_info(&[first.as_ptr()]);
+            let second = instance_info(&[second.as_ptr()]);
+            assert_eq!(ContentKey::new(&first), ContentKey::new(&second));
+        }
+    }
+
+    #[test]
+    fn different_content() {
+        unsafe {
+            let key = ContentKey::new(&queue_info(&[1.0, 0.5]));
+            assert_ne!(key, ContentKey::new(&queue_info(&[1.0, 0.25])));
+            assert_ne!(key, ContentKey::new(&queue_info(&[1.0])));
+            assert_ne!(key, ContentKey::new(&queue_info(&[1.0, 0.5, 0.5])));
+        }
+
+        let surface = CString::new("VK_KHR_surface").unwrap();
+        let swapchain = CString::new("VK_KHR_swapchain").unwrap();
+        unsafe {
+            let key = ContentKey::new(&instance_info(&[surface.as_ptr()]));
+            assert_ne!(key, ContentKey::new(&instance_info(&[swapchain.as_ptr()])));
+            // The names are hashed one by one, and not as one concatenated string.
+            let prefix = CString::new("VK_KHR_").unwrap();
+            let suffix = CString::new("surface").unwrap();
+            let split = instance_info(&[prefix.as_ptr(), suffix.as_ptr()]);
+            assert_ne!(key, ContentKey::new(&split));
+        }
+    }
+
+    #[test]
+    fn different_p_next() {
+        let priorities = [1.0];
+        let (unchained, next) = (queue_info(&priorities), queue_info(&priorities));
+        let mut chained = queue_info(&priorities);
+        chained.p_next = &next as *const _ as *const vk::c_void;
+        unsafe {
+            assert_ne!(ContentKey::new(&unchained), ContentKey::new(&chained));
+        }
+    }
+
+    #[test]
+    fn null_is_empty() {
+        let mut null = queue_info(&[]);
+        null.p_queue_priorities = ptr::null();
+        unsafe {
+            assert_eq!(ContentKey::new(&null), ContentKey::new(&queue_info(&[])));
+            assert_eq!(
+                ContentKey::new(&instance_info(&[])),
+                ContentKey::new(&vk::InstanceCreateInfo {
+                    pp_enabled_extension_names: ptr::null(),
+                    ..instance_info(&[])
+                })
+            );
+        }
+    }
+}
@@ -3,12 +3,15 @@ use prelude::*;
 use std::ptr;
 use std::mem;
 use vk;
+use tracker;
 use device::Device;
 use std::error::Error;
 use std::fmt;
 use RawPtr;
 use version::{FunctionPointers, V1_0};
-use version::DeviceLoader;
+use version::{DeviceLoader, InstanceLoader};
+use extensions::{extension_names_from_raw, missing_extensions, ExtensionError, InstanceExtension};
+use std::ffi::CString;
 
 #[derive(Debug)]
 pub enum DeviceError {
@@ -39,6 +42,7 @@ impl Error for DeviceError {
 pub struct Instance<V: FunctionPointers> {
     handle: vk::Instance,
     instance_fp: V::InstanceFp,
+    enabled_extensions: Option<Vec<CString>>,
 }
 
 impl InstanceV1_0 for Instance<V1_0> {
@@ -50,6 +54,10 @@ impl InstanceV1_0 for Instance<V1_0> {
     fn fp_v1_0(&self) -> &vk::InstanceFnV1_0 {
         &self.instance_fp.instance_fn
     }
+
+    fn enabled_extensions(&self) -> Option<&[CString]> {
+        self.enabled_extensions.as_ref().map(|names| names.as_slice())
+    }
 }
 impl<V: FunctionPointers> Instance<V> {
     pub fn handle(&self) -> vk::Instance {
@@ -60,8 +68,36 @@ impl<V: FunctionPointers> Instance<V> {
         Instance {
             handle: handle,
             instance_fp: version,
+            enabled_extensions: None,
         }
     }
+
+    /// Records the extensions that were enabled when the instance was created. Without them
+    /// `load` can't check if an extension was enabled and loads it unconditionally.
+    pub fn with_enabled_extensions(mut self, enabled_extensions: Vec<CString>) -> Self {
+        self.enabled_extensions = Some(enabled_extensions);
+        self
+    }
+
+    /// Loads an instance extension after checking that it and the extensions it requires were
+    /// enabled.
+    ///
+    /// ```rust,ignore
+    /// let surface_loader = instance.load::<Surface>()?;
+    /// ```
+    pub fn load<E: InstanceExtension>(&self) -> Result<E, ExtensionError> {
+        if let Some(ref enabled_extensions) = self.enabled_extensions {
+            let missing = missing_extensions::<E>(enabled_extensions);
+            if !missing.is_empty() {
+                return Err(ExtensionError::NotEnabled(missing));
+            }
+        }
+        let handle = self.handle;
+        let static_fn = self.instance_fp.static_fn();
+        E::load_with(handle, |name| unsafe {
+            mem::transmute(static_fn.get_instance_proc_addr(handle, name.as_ptr()))
+        }).map_err(|err| ExtensionError::LoadError(err))
+    }
 }
 
 #[allow(non_camel_case_types)]
@@ -69,6 +105,12 @@ pub trait InstanceV1_0 {
     type Fp: FunctionPointers;
     fn handle(&self) -> vk::Instance;
     fn fp_v1_0(&self) -> &vk::InstanceFnV1_0;
+
+    /// The extensions that were enabled when the instance was created, if they are known.
+    fn enabled_extensions(&self) -> Option<&[CString]> {
+        None
+    }
+
     unsafe fn create_device(
         &self,
         physical_device: vk::PhysicalDevice,
@@ -85,12 +127,24 @@ pub trait InstanceV1_0 {
         if err_code != vk::Result::Success {
             return Err(DeviceError::VkError(err_code));
         }
+        let device = tracker::created(self.handle(), device);
         let device_fn =
             <<Self as InstanceV1_0>::Fp as FunctionPointers>::DeviceFp::load(
                 self.fp_v1_0(),
                 device,
             ).map_err(|err| DeviceError::LoadError(err))?;
-        Ok(Device::from_raw(device, device_fn))
+        let device = Device::from_raw(device, device_fn);
+        match self.enabled_extensions() {
+            Some(instance_extensions) => {
+                let mut enabled_extensions = extension_names_from_raw(
+                    create_info.pp_enabled_extension_names,
+                    create_info.enabled_extension_count,
+                );
+                enabled_extensions.extend(instance_extensions.iter().cloned());
+                Ok(device.with_enabled_extensions(enabled_extensions))
+            }
+            None => Ok(device),
+        }
     }
 
     fn get_device_proc_addr(
@@ -102,6 +156,7 @@ pub trait InstanceV1_0 {
     }
 
     unsafe fn destroy_instance(&self, allocation_callbacks: Option<&vk::AllocationCallbacks>) {
+        tracker::parent_destroyed(self.handle());
         self.fp_v1_0().destroy_instance(
             self.handle(),
             allocation_callbacks.as_raw_ptr(),
@@ -236,6 +291,31 @@ pub trait InstanceV1_0 {
             }
         }
     }
+
+    fn enumerate_device_layer_properties(
+        &self,
+        device: vk::PhysicalDevice,
+    ) -> Result<Vec<vk::LayerProperties>, vk::Result> {
+        unsafe {
+            let mut num = 0;
+            self.fp_v1_0().enumerate_device_layer_properties(
+                device,
+                &mut num,
+                ptr::null_mut(),
+            );
+            let mut data = Vec::with_capacity(num as usize);
+            let err_code = self.fp_v1_0().enumerate_device_layer_properties(
+                device,
+                &mut num,
+                data.as_mut_ptr(),
+            );
+            data.set_len(num as usize);
+            match err_code {
+                vk::Result::Success => Ok(data),
+                _ => Err(err_code),
+            }
+        }
+    }
 }
 
 // pub trait InstanceMajor1Minor1: InstanceMajor1Minor0 {}
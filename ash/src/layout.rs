@@ -0,0 +1,152 @@
+//! The std140 and std430 layout rules of GLSL uniform and storage blocks.
+//!
+//! `#[derive(Std140)]` and `#[derive(Std430)]` from the `ash-derive` crate implement these traits
+//! for a `#[repr(C)]` struct and fail to compile if a field isn't at the offset the shader
+//! expects, or if the size of a field or of the struct differs from the size in the shader.
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate ash_derive;
+//!
+//! #[repr(C)]
+//! #[derive(Clone, Copy, Std140)]
+//! struct Light {
+//!     position: [f32; 3],
+//!     intensity: f32,
+//!     color: [f32; 4],
+//! }
+//! ```
+//!
+//! `[f32; 2]`, `[f32; 3]` and `[f32; 4]` (and the same for `i32`, `u32` and `f64`) are vectors,
+//! and arrays of vectors, matrices or structs are arrays, so `[[f32; 4]; 4]` is a `mat4`. The
+//! elements of an array of `vec3`, and the columns of a `mat3`, are 16 bytes apart, which no Rust
+//! array matches, so use `[[f32; 4]; N]` for them and ignore the last component.
+//!
+//! Arrays of scalars depend on the layout. Under std430, `float values[8]` is a `[f32; 8]` field
+//! marked with `#[layout(array)]`. Under std140 every array element is 16 bytes apart, which a
+//! `[f32; N]` can never match, so `#[layout(array)]` on scalars only compiles for std430. Use
+//! `[[f32; 4]; N]` for `float values[N]` under std140 and ignore all but the first component.
+//!
+//! Pad with scalar fields such as `_pad: f32`, because `[u32; 3]` is a vector with its own
+//! alignment.
+
+/// The base alignment and size of a type under std140.
+pub trait Std140 {
+    const ALIGN: usize;
+    const SIZE: usize;
+}
+
+/// The base alignment and size of a type under std430.
+pub trait Std430 {
+    const ALIGN: usize;
+    const SIZE: usize;
+}
+
+/// Types that an array `[T; N]` can be made of under std140 without `#[layout(array)]`. Scalars
+/// aren't, so that `[f32; 3]` stays a vector.
+pub trait Std140ArrayElement: Std140 {}
+
+/// Types that an array `[T; N]` can be made of under std430 without `#[layout(array)]`.
+pub trait Std430ArrayElement: Std430 {}
+
+pub const fn align_up(offset: usize, alignment: usize) -> usize {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+pub const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The alignment of an array, which is also the stride together with `array_stride`.
+pub const fn std140_array_align(element_align: usize) -> usize {
+    align_up(element_align, 16)
+}
+
+pub const fn std430_array_align(element_align: usize) -> usize {
+    element_align
+}
+
+pub const fn array_stride(element_size: usize, array_align: usize) -> usize {
+    align_up(element_size, array_align)
+}
+
+/// The alignment of a struct whose largest member alignment is `member_align`.
+pub const fn std140_struct_align(member_align: usize) -> usize {
+    align_up(member_align, 16)
+}
+
+pub const fn std430_struct_align(member_align: usize) -> usize {
+    member_align
+}
+
+macro_rules! scalar {
+    ($($ty: ty),*) => {
+        $(
+            impl Std140 for $ty {
+                const ALIGN: usize = ::std::mem::size_of::<$ty>();
+                const SIZE: usize = ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std430 for $ty {
+                const ALIGN: usize = ::std::mem::size_of::<$ty>();
+                const SIZE: usize = ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std140 for [$ty; 2] {
+                const ALIGN: usize = 2 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 2 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std430 for [$ty; 2] {
+                const ALIGN: usize = 2 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 2 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std140 for [$ty; 3] {
+                const ALIGN: usize = 4 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 3 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std430 for [$ty; 3] {
+                const ALIGN: usize = 4 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 3 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std140 for [$ty; 4] {
+                const ALIGN: usize = 4 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 4 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std430 for [$ty; 4] {
+                const ALIGN: usize = 4 * ::std::mem::size_of::<$ty>();
+                const SIZE: usize = 4 * ::std::mem::size_of::<$ty>();
+            }
+
+            impl Std140ArrayElement for [$ty; 2] {}
+            impl Std140ArrayElement for [$ty; 3] {}
+            impl Std140ArrayElement for [$ty; 4] {}
+            impl Std430ArrayElement for [$ty; 2] {}
+            impl Std430ArrayElement for [$ty; 3] {}
+            impl Std430ArrayElement for [$ty; 4] {}
+        )*
+    }
+}
+
+scalar!(f32, f64, i32, u32);
+
+impl<T: Std140ArrayElement, const N: usize> Std140 for [T; N] {
+    const ALIGN: usize = std140_array_align(T::ALIGN);
+    const SIZE: usize = N * array_stride(T::SIZE, std140_array_align(T::ALIGN));
+}
+
+impl<T: Std430ArrayElement, const N: usize> Std430 for [T; N] {
+    const ALIGN: usize = std430_array_align(T::ALIGN);
+    const SIZE: usize = N * array_stride(T::SIZE, std430_array_align(T::ALIGN));
+}
+
+impl<T: Std140ArrayElement, const N: usize> Std140ArrayElement for [T; N] {}
+impl<T: Std430ArrayElement, const N: usize> Std430ArrayElement for [T; N] {}
@@ -2,6 +2,9 @@ extern crate libc;
 extern crate shared_library;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "log")]
+#[macro_use]
+extern crate log;
 pub use instance::{Instance, DeviceError};
 pub use device::Device;
 pub use entry::{Entry, InstanceError, LoadingError};
@@ -11,10 +14,24 @@ mod device;
 mod entry;
 pub mod prelude;
 pub mod vk;
-//mod allocator;
+pub mod allocator;
 pub mod extensions;
 pub mod version;
 pub mod util;
+pub mod builder;
+pub mod selector;
+pub mod report;
+pub mod limits;
+pub mod layout;
+pub mod vertex;
+pub mod memory;
+pub mod owned;
+pub mod bound;
+pub mod hash;
+mod raw;
+mod tracker;
+pub mod window;
+pub mod validation;
 
 pub trait RawPtr<T> {
     fn as_raw_ptr(&self) -> *const T;
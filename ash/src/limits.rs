@@ -0,0 +1,537 @@
+//! Declarative requirements on `vk::PhysicalDeviceLimits` and
+//! `vk::PhysicalDeviceSparseProperties`.
+//!
+//! ```rust,ignore
+//! let requirements = LimitRequirements {
+//!     max_push_constants_size: Some(128),
+//!     max_bound_descriptor_sets: Some(4),
+//!     min_uniform_buffer_offset_alignment: Some(256),
+//!     ..Default::default()
+//! };
+//! for violation in requirements.check_properties(&properties) {
+//!     println!("{}", violation);
+//! }
+//! ```
+use std::fmt;
+use vk;
+
+/// The value of a limit, in the type of its field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitValue {
+    Bool(bool),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    Usize(usize),
+    F32(f32),
+    U32x2([u32; 2]),
+    U32x3([u32; 3]),
+    F32x2([f32; 2]),
+    SampleCounts(vk::SampleCountFlags),
+}
+
+impl fmt::Display for LimitValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LimitValue::Bool(value) => write!(f, "{}", value),
+            LimitValue::U32(value) => write!(f, "{}", value),
+            LimitValue::I32(value) => write!(f, "{}", value),
+            LimitValue::U64(value) => write!(f, "{}", value),
+            LimitValue::Usize(value) => write!(f, "{}", value),
+            LimitValue::F32(value) => write!(f, "{}", value),
+            LimitValue::U32x2(value) => write!(f, "{:?}", value),
+            LimitValue::U32x3(value) => write!(f, "{:?}", value),
+            LimitValue::F32x2(value) => write!(f, "{:?}", value),
+            LimitValue::SampleCounts(value) => {
+                let counts: Vec<String> = (0..7)
+                    .map(|bit| 1u32 << bit)
+                    .filter(|&count| value.flags() & count != 0)
+                    .map(|count| count.to_string())
+                    .collect();
+                write!(f, "{{{}}}", counts.join(", "))
+            }
+        }
+    }
+}
+
+macro_rules! limit_value_from {
+    ($($ty: ty => $variant: ident),*) => {
+        $(
+            impl From<$ty> for LimitValue {
+                fn from(value: $ty) -> LimitValue {
+                    LimitValue::$variant(value)
+                }
+            }
+        )*
+    }
+}
+
+limit_value_from!(
+    bool => Bool,
+    u32 => U32,
+    i32 => I32,
+    u64 => U64,
+    usize => Usize,
+    f32 => F32,
+    [u32; 2] => U32x2,
+    [u32; 3] => U32x3,
+    [f32; 2] => F32x2,
+    vk::SampleCountFlags => SampleCounts
+);
+
+/// A limit that the device doesn't meet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitViolation {
+    /// The name of the field in `vk::PhysicalDeviceLimits` or
+    /// `vk::PhysicalDeviceSparseProperties`.
+    pub name: &'static str,
+    pub required: LimitValue,
+    pub actual: LimitValue,
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is {}, but {} is required",
+            self.name,
+            self.actual,
+            self.required
+        )
+    }
+}
+
+fn at_least<T: PartialOrd + Into<LimitValue>>(required: T, actual: T) -> Option<(T, T)> {
+    if actual >= required {
+        None
+    } else {
+        Some((required, actual))
+    }
+}
+
+fn at_most<T: PartialOrd + Into<LimitValue>>(required: T, actual: T) -> Option<(T, T)> {
+    if actual <= required {
+        None
+    } else {
+        Some((required, actual))
+    }
+}
+
+fn each_at_least<A: AsRef<[u32]> + Copy>(required: A, actual: A) -> Option<(A, A)> {
+    let ok = required
+        .as_ref()
+        .iter()
+        .zip(actual.as_ref())
+        .all(|(required, actual)| actual >= required);
+    if ok {
+        None
+    } else {
+        Some((required, actual))
+    }
+}
+
+fn covers(required: [f32; 2], actual: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+    if actual[0] <= required[0] && actual[1] >= required[1] {
+        None
+    } else {
+        Some((required, actual))
+    }
+}
+
+fn contains(
+    required: vk::SampleCountFlags,
+    actual: vk::SampleCountFlags,
+) -> Option<(vk::SampleCountFlags, vk::SampleCountFlags)> {
+    if actual.subset(required) {
+        None
+    } else {
+        Some((required, actual))
+    }
+}
+
+fn enabled(required: bool, actual: vk::Bool32) -> Option<(bool, bool)> {
+    if !required || actual != 0 {
+        None
+    } else {
+        Some((required, false))
+    }
+}
+
+macro_rules! limit_requirements {
+    (
+        limits { $($field: ident: $ty: ty => $check: ident,)* }
+        sparse_properties { $($sparse_field: ident,)* }
+    ) => {
+        /// Requirements on the limits and sparse properties of a device. Every field that is
+        /// `None` is ignored.
+        ///
+        /// How a field is compared depends on its meaning:
+        ///
+        /// * `max_*` limits, counts and precision bits have to be at least the requirement.
+        /// * `min_*` limits, alignments, granularities and `timestamp_period` have to be at most
+        ///   the requirement. Alignments are powers of two, so an alignment that the renderer
+        ///   assumes is also a multiple of the device alignment.
+        /// * `min_memory_map_alignment` is a guaranteed alignment of mapped pointers instead, so
+        ///   it has to be at least the requirement.
+        /// * `max_compute_work_group_count`, `max_compute_work_group_size` and
+        ///   `max_viewport_dimensions` are compared element by element.
+        /// * `viewport_bounds_range`, `point_size_range` and `line_width_range` have to contain
+        ///   the required range.
+        /// * The `*_sample_counts` flags have to contain every required sample count.
+        /// * Boolean limits and sparse properties only fail if they are required to be `true`.
+        #[derive(Debug, Clone, Copy, Default, PartialEq)]
+        pub struct LimitRequirements {
+            $(pub $field: Option<$ty>,)*
+            $(pub $sparse_field: Option<bool>,)*
+        }
+
+        impl LimitRequirements {
+            pub fn check(
+                &self,
+                limits: &vk::PhysicalDeviceLimits,
+                sparse_properties: &vk::PhysicalDeviceSparseProperties,
+            ) -> Vec<LimitViolation> {
+                let mut violations = Vec::new();
+                $(
+                    if let Some(required) = self.$field {
+                        if let Some((required, actual)) = $check(required, limits.$field) {
+                            violations.push(LimitViolation {
+                                name: stringify!($field),
+                                required: required.into(),
+                                actual: actual.into(),
+                            });
+                        }
+                    }
+                )*
+                $(
+                    if let Some(required) = self.$sparse_field {
+                        if let Some((required, actual)) =
+                            enabled(required, sparse_properties.$sparse_field)
+                        {
+                            violations.push(LimitViolation {
+                                name: stringify!($sparse_field),
+                                required: required.into(),
+                                actual: actual.into(),
+                            });
+                        }
+                    }
+                )*
+                violations
+            }
+        }
+    }
+}
+
+limit_requirements! {
+    limits {
+        max_image_dimension1d: u32 => at_least,
+        max_image_dimension2d: u32 => at_least,
+        max_image_dimension3d: u32 => at_least,
+        max_image_dimension_cube: u32 => at_least,
+        max_image_array_layers: u32 => at_least,
+        max_texel_buffer_elements: u32 => at_least,
+        max_uniform_buffer_range: u32 => at_least,
+        max_storage_buffer_range: u32 => at_least,
+        max_push_constants_size: u32 => at_least,
+        max_memory_allocation_count: u32 => at_least,
+        max_sampler_allocation_count: u32 => at_least,
+        buffer_image_granularity: u64 => at_most,
+        sparse_address_space_size: u64 => at_least,
+        max_bound_descriptor_sets: u32 => at_least,
+        max_per_stage_descriptor_samplers: u32 => at_least,
+        max_per_stage_descriptor_uniform_buffers: u32 => at_least,
+        max_per_stage_descriptor_storage_buffers: u32 => at_least,
+        max_per_stage_descriptor_sampled_images: u32 => at_least,
+        max_per_stage_descriptor_storage_images: u32 => at_least,
+        max_per_stage_descriptor_input_attachments: u32 => at_least,
+        max_per_stage_resources: u32 => at_least,
+        max_descriptor_set_samplers: u32 => at_least,
+        max_descriptor_set_uniform_buffers: u32 => at_least,
+        max_descriptor_set_uniform_buffers_dynamic: u32 => at_least,
+        max_descriptor_set_storage_buffers: u32 => at_least,
+        max_descriptor_set_storage_buffers_dynamic: u32 => at_least,
+        max_descriptor_set_sampled_images: u32 => at_least,
+        max_descriptor_set_storage_images: u32 => at_least,
+        max_descriptor_set_input_attachments: u32 => at_least,
+        max_vertex_input_attributes: u32 => at_least,
+        max_vertex_input_bindings: u32 => at_least,
+        max_vertex_input_attribute_offset: u32 => at_least,
+        max_vertex_input_binding_stride: u32 => at_least,
+        max_vertex_output_components: u32 => at_least,
+        max_tessellation_generation_level: u32 => at_least,
+        max_tessellation_patch_size: u32 => at_least,
+        max_tessellation_control_per_vertex_input_components: u32 => at_least,
+        max_tessellation_control_per_vertex_output_components: u32 => at_least,
+        max_tessellation_control_per_patch_output_components: u32 => at_least,
+        max_tessellation_control_total_output_components: u32 => at_least,
+        max_tessellation_evaluation_input_components: u32 => at_least,
+        max_tessellation_evaluation_output_components: u32 => at_least,
+        max_geometry_shader_invocations: u32 => at_least,
+        max_geometry_input_components: u32 => at_least,
+        max_geometry_output_components: u32 => at_least,
+        max_geometry_output_vertices: u32 => at_least,
+        max_geometry_total_output_components: u32 => at_least,
+        max_fragment_input_components: u32 => at_least,
+        max_fragment_output_attachments: u32 => at_least,
+        max_fragment_dual_src_attachments: u32 => at_least,
+        max_fragment_combined_output_resources: u32 => at_least,
+        max_compute_shared_memory_size: u32 => at_least,
+        max_compute_work_group_count: [u32; 3] => each_at_least,
+        max_compute_work_group_invocations: u32 => at_least,
+        max_compute_work_group_size: [u32; 3] => each_at_least,
+        sub_pixel_precision_bits: u32 => at_least,
+        sub_texel_precision_bits: u32 => at_least,
+        mipmap_precision_bits: u32 => at_least,
+        max_draw_indexed_index_value: u32 => at_least,
+        max_draw_indirect_count: u32 => at_least,
+        max_sampler_lod_bias: f32 => at_least,
+        max_sampler_anisotropy: f32 => at_least,
+        max_viewports: u32 => at_least,
+        max_viewport_dimensions: [u32; 2] => each_at_least,
+        viewport_bounds_range: [f32; 2] => covers,
+        viewport_sub_pixel_bits: u32 => at_least,
+        min_memory_map_alignment: usize => at_least,
+        min_texel_buffer_offset_alignment: u64 => at_most,
+        min_uniform_buffer_offset_alignment: u64 => at_most,
+        min_storage_buffer_offset_alignment: u64 => at_most,
+        min_texel_offset: i32 => at_most,
+        max_texel_offset: u32 => at_least,
+        min_texel_gather_offset: i32 => at_most,
+        max_texel_gather_offset: u32 => at_least,
+        min_interpolation_offset: f32 => at_most,
+        max_interpolation_offset: f32 => at_least,
+        sub_pixel_interpolation_offset_bits: u32 => at_least,
+        max_framebuffer_width: u32 => at_least,
+        max_framebuffer_height: u32 => at_least,
+        max_framebuffer_layers: u32 => at_least,
+        framebuffer_color_sample_counts: vk::SampleCountFlags => contains,
+        framebuffer_depth_sample_counts: vk::SampleCountFlags => contains,
+        framebuffer_stencil_sample_counts: vk::SampleCountFlags => contains,
+        framebuffer_no_attachments_sample_counts: vk::SampleCountFlags => contains,
+        max_color_attachments: u32 => at_least,
+        sampled_image_color_sample_counts: vk::SampleCountFlags => contains,
+        sampled_image_integer_sample_counts: vk::SampleCountFlags => contains,
+        sampled_image_depth_sample_counts: vk::SampleCountFlags => contains,
+        sampled_image_stencil_sample_counts: vk::SampleCountFlags => contains,
+        storage_image_sample_counts: vk::SampleCountFlags => contains,
+        max_sample_mask_words: u32 => at_least,
+        timestamp_compute_and_graphics: bool => enabled,
+        timestamp_period: f32 => at_most,
+        max_clip_distances: u32 => at_least,
+        max_cull_distances: u32 => at_least,
+        max_combined_clip_and_cull_distances: u32 => at_least,
+        discrete_queue_priorities: u32 => at_least,
+        point_size_range: [f32; 2] => covers,
+        line_width_range: [f32; 2] => covers,
+        point_size_granularity: f32 => at_most,
+        line_width_granularity: f32 => at_most,
+        strict_lines: bool => enabled,
+        standard_sample_locations: bool => enabled,
+        optimal_buffer_copy_offset_alignment: u64 => at_most,
+        optimal_buffer_copy_row_pitch_alignment: u64 => at_most,
+        non_coherent_atom_size: u64 => at_most,
+    }
+    sparse_properties {
+        residency_standard2dblock_shape,
+        residency_standard2dmultisample_block_shape,
+        residency_standard3dblock_shape,
+        residency_aligned_mip_size,
+        residency_non_resident_strict,
+    }
+}
+
+impl LimitRequirements {
+    /// Checks the limits and sparse properties of `properties`.
+    pub fn check_properties(
+        &self,
+        properties: &vk::PhysicalDeviceProperties,
+    ) -> Vec<LimitViolation> {
+        self.check(&properties.limits, &properties.sparse_properties)
+    }
+
+    pub fn is_satisfied_by(&self, properties: &vk::PhysicalDeviceProperties) -> bool {
+        self.check_properties(properties).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem;
+
+    // Every field is a number or flags, so zero is a valid value for all of them.
+    fn properties() -> (vk::PhysicalDeviceLimits, vk::PhysicalDeviceSparseProperties) {
+        unsafe { (mem::zeroed(), mem::zeroed()) }
+    }
+
+    fn violation<T: Into<LimitValue>>(
+        name: &'static str,
+        required: T,
+        actual: T,
+    ) -> LimitViolation {
+        LimitViolation {
+            name: name,
+            required: required.into(),
+            actual: actual.into(),
+        }
+    }
+
+    #[test]
+    fn nothing_required() {
+        let (limits, sparse_properties) = properties();
+        assert!(LimitRequirements::default()
+            .check(&limits, &sparse_properties)
+            .is_empty());
+    }
+
+    #[test]
+    fn at_least() {
+        let (mut limits, sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            max_push_constants_size: Some(256),
+            max_sampler_anisotropy: Some(16.0),
+            ..Default::default()
+        };
+        limits.max_push_constants_size = 256;
+        limits.max_sampler_anisotropy = 16.0;
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        limits.max_push_constants_size = 128;
+        assert_eq!(
+            requirements.check(&limits, &sparse_properties),
+            vec![violation("max_push_constants_size", 256u32, 128)]
+        );
+    }
+
+    #[test]
+    fn at_most() {
+        let (mut limits, sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            min_uniform_buffer_offset_alignment: Some(256),
+            min_texel_offset: Some(-8),
+            ..Default::default()
+        };
+        limits.min_uniform_buffer_offset_alignment = 64;
+        limits.min_texel_offset = -8;
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        limits.min_uniform_buffer_offset_alignment = 1024;
+        limits.min_texel_offset = -4;
+        assert_eq!(
+            requirements.check(&limits, &sparse_properties),
+            vec![
+                violation("min_uniform_buffer_offset_alignment", 256u64, 1024),
+                violation("min_texel_offset", -8i32, -4),
+            ]
+        );
+    }
+
+    #[test]
+    fn min_memory_map_alignment() {
+        let (mut limits, sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            min_memory_map_alignment: Some(64),
+            ..Default::default()
+        };
+        // A larger alignment of mapped pointers is also a multiple of the required one.
+        limits.min_memory_map_alignment = 4096;
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        limits.min_memory_map_alignment = 16;
+        assert_eq!(
+            requirements.check(&limits, &sparse_properties),
+            vec![violation("min_memory_map_alignment", 64usize, 16)]
+        );
+    }
+
+    #[test]
+    fn each_at_least() {
+        let (mut limits, sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            max_compute_work_group_size: Some([256, 256, 64]),
+            ..Default::default()
+        };
+        limits.max_compute_work_group_size = [1024, 1024, 64];
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        limits.max_compute_work_group_size = [1024, 1024, 32];
+        assert_eq!(
+            requirements.check(&limits, &sparse_properties),
+            vec![violation(
+                "max_compute_work_group_size",
+                [256, 256, 64],
+                [1024, 1024, 32]
+            )]
+        );
+    }
+
+    #[test]
+    fn covers() {
+        let (mut limits, sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            point_size_range: Some([1.0, 64.0]),
+            ..Default::default()
+        };
+        limits.point_size_range = [1.0, 2047.0];
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        for &range in &[[2.0, 2047.0], [1.0, 32.0]] {
+            limits.point_size_range = range;
+            assert_eq!(
+                requirements.check(&limits, &sparse_properties),
+                vec![violation("point_size_range", [1.0, 64.0], range)]
+            );
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let (mut limits, sparse_properties) = properties();
+        let required = vk::SAMPLE_COUNT_1_BIT | vk::SAMPLE_COUNT_4_BIT;
+        let requirements = LimitRequirements {
+            framebuffer_color_sample_counts: Some(required),
+            ..Default::default()
+        };
+        limits.framebuffer_color_sample_counts =
+            required | vk::SAMPLE_COUNT_2_BIT | vk::SAMPLE_COUNT_8_BIT;
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        let actual = vk::SAMPLE_COUNT_1_BIT | vk::SAMPLE_COUNT_2_BIT;
+        limits.framebuffer_color_sample_counts = actual;
+        let violations = requirements.check(&limits, &sparse_properties);
+        assert_eq!(
+            violations,
+            vec![violation(
+                "framebuffer_color_sample_counts",
+                required,
+                actual
+            )]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "framebuffer_color_sample_counts is {1, 2}, but {1, 4} is required"
+        );
+    }
+
+    #[test]
+    fn enabled() {
+        let (mut limits, mut sparse_properties) = properties();
+        let requirements = LimitRequirements {
+            strict_lines: Some(true),
+            standard_sample_locations: Some(false),
+            residency_aligned_mip_size: Some(true),
+            ..Default::default()
+        };
+        limits.strict_lines = vk::VK_TRUE;
+        sparse_properties.residency_aligned_mip_size = vk::VK_TRUE;
+        assert!(requirements.check(&limits, &sparse_properties).is_empty());
+        limits.strict_lines = 0;
+        sparse_properties.residency_aligned_mip_size = 0;
+        let violations = requirements.check(&limits, &sparse_properties);
+        assert_eq!(
+            violations,
+            vec![
+                violation("strict_lines", true, false),
+                violation("residency_aligned_mip_size", true, false),
+            ]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "strict_lines is false, but true is required"
+        );
+    }
+}
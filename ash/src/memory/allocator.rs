@@ -0,0 +1,508 @@
+use super::block::{BlockAllocator, ResourceKind};
+use super::memory_type::{memory_type_error, rank_memory_types, MemoryTypeError,
+                         MemoryTypeRequest};
+use prelude::*;
+use std::error::Error;
+use std::fmt;
+use std::ptr;
+use version::{DeviceV1_0, InstanceV1_0};
+use vk;
+
+/// How the memory is going to be accessed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryUsage {
+    /// Only accessed by the device. Requires `DEVICE_LOCAL`.
+    GpuOnly,
+    /// Written by the host and read by the device, like staging and uniform buffers. Requires
+    /// `HOST_VISIBLE | HOST_COHERENT` and prefers `DEVICE_LOCAL`.
+    Upload,
+    /// Written by the device and read back by the host. Requires
+    /// `HOST_VISIBLE | HOST_COHERENT` and prefers `HOST_CACHED`.
+    Readback,
+}
+
+impl MemoryUsage {
+    pub fn memory_type_request(self) -> MemoryTypeRequest {
+        match self {
+            MemoryUsage::GpuOnly => MemoryTypeRequest::new(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT),
+            MemoryUsage::Upload => MemoryTypeRequest::new(
+                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+            ).preferred(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT),
+            MemoryUsage::Readback => MemoryTypeRequest::new(
+                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+            ).preferred(vk::MEMORY_PROPERTY_HOST_CACHED_BIT),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum AllocatorError {
+    /// None of the allowed memory types has the flags that the usage requires.
+    NoMemoryType(MemoryTypeError),
+    VkError(vk::Result),
+}
+
+impl fmt::Display for AllocatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AllocatorError::{:?}", self)
+    }
+}
+
+impl Error for AllocatorError {
+    fn description(&self) -> &str {
+        "AllocatorError"
+    }
+}
+
+/// A range of device memory from a `MemoryAllocator`. It has to be returned with
+/// `MemoryAllocator::free` before the allocator is dropped.
+#[derive(Debug)]
+pub struct Allocation {
+    memory: vk::DeviceMemory,
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    mapped_ptr: *mut vk::c_void,
+    memory_type_index: vk::uint32_t,
+    /// `None` for a dedicated allocation.
+    block_index: Option<usize>,
+}
+
+unsafe impl Send for Allocation {}
+unsafe impl Sync for Allocation {}
+
+impl Allocation {
+    pub fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    pub fn offset(&self) -> vk::DeviceSize {
+        self.offset
+    }
+
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    pub fn memory_type_index(&self) -> vk::uint32_t {
+        self.memory_type_index
+    }
+
+    /// A pointer to the start of the allocation that stays valid until it is freed, if the
+    /// memory is host visible.
+    pub fn mapped_ptr(&self) -> Option<*mut vk::c_void> {
+        if self.mapped_ptr.is_null() {
+            None
+        } else {
+            Some(self.mapped_ptr)
+        }
+    }
+
+    pub fn is_dedicated(&self) -> bool {
+        self.block_index.is_none()
+    }
+}
+
+/// Memory usage of a `MemoryAllocator`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Calls to `allocate_memory` that are still alive, blocks and dedicated allocations.
+    pub device_allocations: usize,
+    pub device_bytes: vk::DeviceSize,
+    pub allocations: usize,
+    pub allocated_bytes: vk::DeviceSize,
+}
+
+struct MemoryBlock {
+    memory: vk::DeviceMemory,
+    mapped_ptr: *mut vk::c_void,
+    allocator: BlockAllocator,
+}
+
+/// Sub-allocates buffers and images from large blocks of device memory, so that only a few
+/// `allocate_memory` calls count against `max_memory_allocation_count`.
+///
+/// Every memory type has its own pool of blocks. Requests larger than half a block get a
+/// dedicated allocation. Host visible blocks are mapped once when they are allocated and stay
+/// mapped. Empty blocks are freed, except the last one of each type.
+///
+/// Dropping the allocator frees all of its memory, so the device has to outlive it, and debug
+/// builds panic if allocations are still alive at that point.
+///
+/// ```rust,ignore
+/// let mut allocator = MemoryAllocator::new(&instance, physical_device, device.clone());
+/// let buffer = device.create_buffer(&buffer_info, None)?;
+/// let allocation = allocator.allocate_for_buffer(buffer, MemoryUsage::Upload)?;
+/// copy_vertices(allocation.mapped_ptr().unwrap());
+/// // ...
+/// device.destroy_buffer(buffer, None);
+/// allocator.free(allocation);
+/// ```
+pub struct MemoryAllocator<D: DeviceV1_0> {
+    device: D,
+    memory_properties: vk::PhysicalDeviceMemoryProperties,
+    buffer_image_granularity: vk::DeviceSize,
+    block_size: vk::DeviceSize,
+    pools: Vec<Vec<Option<MemoryBlock>>>,
+    dedicated: Vec<(vk::DeviceMemory, vk::DeviceSize)>,
+    allocations: usize,
+}
+
+/// The block size for heaps larger than 1 GiB. Smaller heaps use an eighth of their size.
+pub const DEFAULT_BLOCK_SIZE: vk::DeviceSize = 64 * 1024 * 1024;
+
+impl<D: DeviceV1_0> MemoryAllocator<D> {
+    pub fn new<I: InstanceV1_0>(
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+        device: D,
+    ) -> MemoryAllocator<D> {
+        let properties = instance.get_physical_device_properties(physical_device);
+        MemoryAllocator::from_properties(
+            device,
+            instance.get_physical_device_memory_properties(physical_device),
+            properties.limits.buffer_image_granularity,
+        )
+    }
+
+    pub fn from_properties(
+        device: D,
+        memory_properties: vk::PhysicalDeviceMemoryProperties,
+        buffer_image_granularity: vk::DeviceSize,
+    ) -> MemoryAllocator<D> {
+        let memory_type_count = memory_properties.memory_type_count as usize;
+        MemoryAllocator {
+            device: device,
+            memory_properties: memory_properties,
+            buffer_image_granularity: buffer_image_granularity,
+            block_size: DEFAULT_BLOCK_SIZE,
+            pools: (0..memory_type_count).map(|_| Vec::new()).collect(),
+            dedicated: Vec::new(),
+            allocations: 0,
+        }
+    }
+
+    /// Overrides `DEFAULT_BLOCK_SIZE` for blocks that are allocated from now on.
+    pub fn with_block_size(mut self, block_size: vk::DeviceSize) -> MemoryAllocator<D> {
+        self.block_size = block_size;
+        self
+    }
+
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+
+    pub fn memory_properties(&self) -> &vk::PhysicalDeviceMemoryProperties {
+        &self.memory_properties
+    }
+
+    pub fn allocate(
+        &mut self,
+        requirements: &vk::MemoryRequirements,
+        usage: MemoryUsage,
+        kind: ResourceKind,
+    ) -> Result<Allocation, AllocatorError> {
+        let request = usage.memory_type_request();
+        let memory_types =
+            rank_memory_types(requirements.memory_type_bits, &self.memory_properties, request);
+        if memory_types.is_empty() {
+            return Err(AllocatorError::NoMemoryType(memory_type_error(
+                requirements.memory_type_bits,
+                &self.memory_properties,
+                &[request],
+            )));
+        }
+        let mut last_error = vk::Result::ErrorOutOfDeviceMemory;
+        for memory_type_index in memory_types {
+            match self.allocate_from_type(memory_type_index, requirements, kind) {
+                Ok(allocation) => {
+                    self.allocations += 1;
+                    return Ok(allocation);
+                }
+                Err(err) => last_error = err,
+            }
+        }
+        Err(AllocatorError::VkError(last_error))
+    }
+
+    /// Allocates memory for `buffer` and binds it.
+    pub fn allocate_for_buffer(
+        &mut self,
+        buffer: vk::Buffer,
+        usage: MemoryUsage,
+    ) -> Result<Allocation, AllocatorError> {
+        let requirements = self.device.get_buffer_memory_requirements(buffer);
+        let allocation = self.allocate(&requirements, usage, ResourceKind::Buffer)?;
+        let result = unsafe {
+            self.device
+                .bind_buffer_memory(buffer, allocation.memory, allocation.offset)
+        };
+        match result {
+            Ok(()) => Ok(allocation),
+            Err(err) => {
+                unsafe { self.free(allocation) };
+                Err(AllocatorError::VkError(err))
+            }
+        }
+    }
+
+    /// Allocates memory for `image`, which was created with `tiling`, and binds it.
+    pub fn allocate_for_image(
+        &mut self,
+        image: vk::Image,
+        tiling: vk::ImageTiling,
+        usage: MemoryUsage,
+    ) -> Result<Allocation, AllocatorError> {
+        let requirements = self.device.get_image_memory_requirements(image);
+        let kind = match tiling {
+            vk::ImageTiling::Linear => ResourceKind::LinearImage,
+            vk::ImageTiling::Optimal => ResourceKind::OptimalImage,
+        };
+        let allocation = self.allocate(&requirements, usage, kind)?;
+        let result = unsafe {
+            self.device
+                .bind_image_memory(image, allocation.memory, allocation.offset)
+        };
+        match result {
+            Ok(()) => Ok(allocation),
+            Err(err) => {
+                unsafe { self.free(allocation) };
+                Err(AllocatorError::VkError(err))
+            }
+        }
+    }
+
+    /// Returns `allocation` to the allocator.
+    ///
+    /// `allocation` has to come from this allocator and must not be used by the device anymore.
+    /// Allocations that don't belong to a live block or dedicated allocation panic instead of
+    /// being freed, which catches most double frees and frees after `destroy`.
+    pub unsafe fn free(&mut self, allocation: Allocation) {
+        let memory_type_index = allocation.memory_type_index as usize;
+        let block_index = match allocation.block_index {
+            Some(block_index) => block_index,
+            None => {
+                let index = self
+                    .dedicated
+                    .iter()
+                    .position(|&(memory, _)| memory == allocation.memory)
+                    .expect("Dedicated allocation isn't alive in this allocator");
+                self.dedicated.swap_remove(index);
+                self.allocations = self
+                    .allocations
+                    .checked_sub(1)
+                    .expect("More allocations freed than allocated");
+                self.device.free_memory(allocation.memory, None);
+                return;
+            }
+        };
+        let is_empty = {
+            let block = self
+                .pools
+                .get_mut(memory_type_index)
+                .and_then(|pool| pool.get_mut(block_index))
+                .and_then(|block| block.as_mut())
+                .filter(|block| block.memory == allocation.memory)
+                .expect("Allocation from a block that isn't alive in this allocator");
+            assert!(
+                block.allocator.free(allocation.offset),
+                "No allocation at offset {} in this block",
+                allocation.offset
+            );
+            block.allocator.is_empty()
+        };
+        self.allocations = self
+            .allocations
+            .checked_sub(1)
+            .expect("More allocations freed than allocated");
+        let live_blocks = self.pools[memory_type_index]
+            .iter()
+            .filter(|block| block.is_some())
+            .count();
+        if is_empty && live_blocks > 1 {
+            if let Some(block) = self.pools[memory_type_index][block_index].take() {
+                self.device.free_memory(block.memory, None);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> MemoryStats {
+        let mut stats = MemoryStats::default();
+        for block in self
+            .pools
+            .iter()
+            .flat_map(|pool| pool.iter())
+            .filter_map(|block| block.as_ref())
+        {
+            stats.device_allocations += 1;
+            stats.device_bytes += block.allocator.size();
+            stats.allocated_bytes += block.allocator.allocated();
+        }
+        for &(_, size) in &self.dedicated {
+            stats.device_allocations += 1;
+            stats.device_bytes += size;
+            stats.allocated_bytes += size;
+        }
+        stats.allocations = self.allocations;
+        stats
+    }
+
+    /// Frees every block and dedicated allocation, which dropping the allocator does as well.
+    /// Allocations that are still alive become invalid.
+    pub unsafe fn destroy(&mut self) {
+        for pool in &mut self.pools {
+            for block in pool.drain(..).filter_map(|block| block) {
+                self.device.free_memory(block.memory, None);
+            }
+        }
+        for (memory, _) in self.dedicated.drain(..) {
+            self.device.free_memory(memory, None);
+        }
+        self.allocations = 0;
+    }
+
+    fn block_size_for_type(&self, memory_type_index: vk::uint32_t) -> vk::DeviceSize {
+        let heap_index =
+            self.memory_properties.memory_types[memory_type_index as usize].heap_index as usize;
+        let heap_size = self.memory_properties.memory_heaps[heap_index].size;
+        if heap_size <= 1024 * 1024 * 1024 {
+            (heap_size / 8).min(self.block_size)
+        } else {
+            self.block_size
+        }
+    }
+
+    fn allocate_from_type(
+        &mut self,
+        memory_type_index: vk::uint32_t,
+        requirements: &vk::MemoryRequirements,
+        kind: ResourceKind,
+    ) -> Result<Allocation, vk::Result> {
+        let block_size = self.block_size_for_type(memory_type_index);
+        if requirements.size > block_size / 2 {
+            let (memory, mapped_ptr) =
+                self.allocate_device_memory(memory_type_index, requirements.size)?;
+            self.dedicated.push((memory, requirements.size));
+            return Ok(Allocation {
+                memory: memory,
+                offset: 0,
+                size: requirements.size,
+                mapped_ptr: mapped_ptr,
+                memory_type_index: memory_type_index,
+                block_index: None,
+            });
+        }
+        let pool_index = memory_type_index as usize;
+        for block_index in 0..self.pools[pool_index].len() {
+            if let Some(ref mut block) = self.pools[pool_index][block_index] {
+                if let Some(offset) =
+                    block
+                        .allocator
+                        .allocate(requirements.size, requirements.alignment, kind)
+                {
+                    return Ok(block_allocation(
+                        block,
+                        offset,
+                        requirements.size,
+                        memory_type_index,
+                        block_index,
+                    ));
+                }
+            }
+        }
+        let (memory, mapped_ptr) = self.allocate_device_memory(memory_type_index, block_size)?;
+        let mut block = MemoryBlock {
+            memory: memory,
+            mapped_ptr: mapped_ptr,
+            allocator: BlockAllocator::new(block_size, self.buffer_image_granularity),
+        };
+        let offset = block
+            .allocator
+            .allocate(requirements.size, requirements.alignment, kind)
+            .expect("A new block has to fit half its size");
+        let pool = &mut self.pools[pool_index];
+        let block_index = match pool.iter().position(|block| block.is_none()) {
+            Some(block_index) => block_index,
+            None => {
+                pool.push(None);
+                pool.len() - 1
+            }
+        };
+        let allocation = block_allocation(
+            &block,
+            offset,
+            requirements.size,
+            memory_type_index,
+            block_index,
+        );
+        pool[block_index] = Some(block);
+        Ok(allocation)
+    }
+
+    /// Allocates and, for host visible types, maps a whole `vk::DeviceMemory`.
+    fn allocate_device_memory(
+        &self,
+        memory_type_index: vk::uint32_t,
+        size: vk::DeviceSize,
+    ) -> VkResult<(vk::DeviceMemory, *mut vk::c_void)> {
+        let allocate_info = vk::MemoryAllocateInfo {
+            s_type: vk::StructureType::MemoryAllocateInfo,
+            p_next: ptr::null(),
+            allocation_size: size,
+            memory_type_index: memory_type_index,
+        };
+        unsafe {
+            let memory = self.device.allocate_memory(&allocate_info, None)?;
+            let flags =
+                self.memory_properties.memory_types[memory_type_index as usize].property_flags;
+            if !flags.subset(vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT) {
+                return Ok((memory, ptr::null_mut()));
+            }
+            match self
+                .device
+                .map_memory(memory, 0, vk::VK_WHOLE_SIZE, Default::default())
+            {
+                Ok(mapped_ptr) => Ok((memory, mapped_ptr)),
+                Err(err) => {
+                    self.device.free_memory(memory, None);
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+impl<D: DeviceV1_0> Drop for MemoryAllocator<D> {
+    fn drop(&mut self) {
+        if !::std::thread::panicking() {
+            debug_assert_eq!(
+                self.allocations, 0,
+                "MemoryAllocator dropped with live allocations"
+            );
+        }
+        unsafe {
+            self.destroy();
+        }
+    }
+}
+
+fn block_allocation(
+    block: &MemoryBlock,
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    memory_type_index: vk::uint32_t,
+    block_index: usize,
+) -> Allocation {
+    let mapped_ptr = if block.mapped_ptr.is_null() {
+        ptr::null_mut()
+    } else {
+        unsafe { (block.mapped_ptr as *mut u8).offset(offset as isize) as *mut vk::c_void }
+    };
+    Allocation {
+        memory: block.memory,
+        offset: offset,
+        size: size,
+        mapped_ptr: mapped_ptr,
+        memory_type_index: memory_type_index,
+        block_index: Some(block_index),
+    }
+}
@@ -0,0 +1,194 @@
+use vk;
+
+/// Whether a resource is laid out linearly in memory. Buffers and linear images are, optimal
+/// images are not, and the two have to be `buffer_image_granularity` apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    Buffer,
+    LinearImage,
+    OptimalImage,
+}
+
+impl ResourceKind {
+    fn is_linear(self) -> bool {
+        self != ResourceKind::OptimalImage
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Range {
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    linear: bool,
+}
+
+/// Sub-allocates one `vk::DeviceMemory` with a first-fit search through the gaps between the
+/// allocated ranges, which are kept sorted by offset.
+#[derive(Debug)]
+pub struct BlockAllocator {
+    size: vk::DeviceSize,
+    granularity: vk::DeviceSize,
+    ranges: Vec<Range>,
+    allocated: vk::DeviceSize,
+}
+
+impl BlockAllocator {
+    pub fn new(size: vk::DeviceSize, buffer_image_granularity: vk::DeviceSize) -> BlockAllocator {
+        BlockAllocator {
+            size: size,
+            granularity: buffer_image_granularity.max(1),
+            ranges: Vec::new(),
+            allocated: 0,
+        }
+    }
+
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    pub fn allocated(&self) -> vk::DeviceSize {
+        self.allocated
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the offset of the new range, or `None` if no gap is large enough.
+    pub fn allocate(
+        &mut self,
+        size: vk::DeviceSize,
+        alignment: vk::DeviceSize,
+        kind: ResourceKind,
+    ) -> Option<vk::DeviceSize> {
+        let linear = kind.is_linear();
+        let alignment = alignment.max(1);
+        for index in 0..self.ranges.len() + 1 {
+            let (gap_start, previous) = match index {
+                0 => (0, None),
+                _ => {
+                    let previous = self.ranges[index - 1];
+                    (previous.offset + previous.size, Some(previous))
+                }
+            };
+            let (gap_end, next) = match self.ranges.get(index) {
+                Some(&next) => (next.offset, Some(next)),
+                None => (self.size, None),
+            };
+            let mut offset = align_up(gap_start, alignment);
+            if let Some(previous) = previous {
+                if previous.linear != linear
+                    && on_same_page(previous.offset + previous.size, offset, self.granularity)
+                {
+                    offset = align_up(offset, self.granularity);
+                }
+            }
+            let end = offset + size;
+            if end > gap_end {
+                continue;
+            }
+            if let Some(next) = next {
+                if next.linear != linear && on_same_page(end, next.offset, self.granularity) {
+                    continue;
+                }
+            }
+            self.ranges.insert(
+                index,
+                Range {
+                    offset: offset,
+                    size: size,
+                    linear: linear,
+                },
+            );
+            self.allocated += size;
+            return Some(offset);
+        }
+        None
+    }
+
+    /// Frees the range that starts at `offset`. Returns `false` if no range starts there.
+    pub fn free(&mut self, offset: vk::DeviceSize) -> bool {
+        match self
+            .ranges
+            .binary_search_by_key(&offset, |range| range.offset)
+        {
+            Ok(index) => {
+                self.allocated -= self.ranges[index].size;
+                self.ranges.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+pub fn align_up(value: vk::DeviceSize, alignment: vk::DeviceSize) -> vk::DeviceSize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+/// Whether the last byte before `end` and the byte at `start` share a page of `granularity`
+/// bytes, as in "Buffer-Image Granularity" in the spec.
+fn on_same_page(end: vk::DeviceSize, start: vk::DeviceSize, granularity: vk::DeviceSize) -> bool {
+    end > 0 && (end - 1) / granularity == start / granularity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockAllocator, ResourceKind};
+
+    #[test]
+    fn first_fit_with_alignment() {
+        let mut block = BlockAllocator::new(1024, 1);
+        assert_eq!(block.allocate(100, 1, ResourceKind::Buffer), Some(0));
+        assert_eq!(block.allocate(100, 256, ResourceKind::Buffer), Some(256));
+        // The gap between 100 and 256 is used before the end of the block.
+        assert_eq!(block.allocate(50, 4, ResourceKind::Buffer), Some(100));
+        assert_eq!(block.allocated(), 250);
+    }
+
+    #[test]
+    fn full_block() {
+        let mut block = BlockAllocator::new(256, 1);
+        assert_eq!(block.allocate(256, 1, ResourceKind::Buffer), Some(0));
+        assert_eq!(block.allocate(1, 1, ResourceKind::Buffer), None);
+        assert!(block.free(0));
+        assert!(block.is_empty());
+        assert_eq!(block.allocate(256, 1, ResourceKind::Buffer), Some(0));
+    }
+
+    #[test]
+    fn free_unknown_offset() {
+        let mut block = BlockAllocator::new(256, 1);
+        assert_eq!(block.allocate(64, 1, ResourceKind::Buffer), Some(0));
+        assert!(!block.free(32));
+        assert!(block.free(0));
+        assert!(!block.free(0));
+        assert_eq!(block.allocated(), 0);
+    }
+
+    #[test]
+    fn freed_neighbours_coalesce() {
+        let mut block = BlockAllocator::new(300, 1);
+        assert_eq!(block.allocate(100, 1, ResourceKind::Buffer), Some(0));
+        assert_eq!(block.allocate(100, 1, ResourceKind::Buffer), Some(100));
+        assert_eq!(block.allocate(100, 1, ResourceKind::Buffer), Some(200));
+        assert!(block.free(0));
+        assert!(block.free(100));
+        // Only the merged gap of the two freed ranges fits 200 bytes.
+        assert_eq!(block.allocate(200, 1, ResourceKind::Buffer), Some(0));
+        assert_eq!(block.allocated(), 300);
+    }
+
+    #[test]
+    fn buffer_image_granularity() {
+        let mut block = BlockAllocator::new(4096, 1024);
+        assert_eq!(block.allocate(100, 16, ResourceKind::Buffer), Some(0));
+        // An optimal image can't share the page of a buffer.
+        assert_eq!(block.allocate(100, 16, ResourceKind::OptimalImage), Some(1024));
+        // Another buffer can share the page of the first buffer, but not of the image.
+        assert_eq!(block.allocate(100, 16, ResourceKind::Buffer), Some(112));
+        assert_eq!(block.allocate(1000, 16, ResourceKind::Buffer), Some(2048));
+        // Linear images count as linear resources, like buffers.
+        assert_eq!(block.allocate(100, 16, ResourceKind::LinearImage), Some(224));
+    }
+}
@@ -0,0 +1,332 @@
+use device::DeviceV1_0;
+use prelude::*;
+use std::mem::{align_of, size_of};
+use std::ptr;
+use std::slice;
+use util::Align;
+use vk;
+
+use super::block::align_up;
+
+/// A mapped range of a `vk::DeviceMemory` that is unmapped when it is dropped.
+///
+/// Every view is checked against the mapped range and panics if it doesn't fit, like slice
+/// indexing does. Offsets of views are relative to the start of the mapped range.
+///
+/// If the memory type isn't `HOST_COHERENT`, the whole memory object is mapped from the
+/// `non_coherent_atom_size` boundary below `offset`, so that the ranges that are flushed and
+/// invalidated can be rounded out to whole atoms. The mapped range is invalidated when it is
+/// mapped, and flushed before it is unmapped if a mutable view was taken.
+pub struct MappedMemory<'a, D: DeviceV1_0 + 'a> {
+    device: &'a D,
+    memory: vk::DeviceMemory,
+    ptr: *mut u8,
+    offset: vk::DeviceSize,
+    size: vk::DeviceSize,
+    non_coherent_atom_size: Option<vk::DeviceSize>,
+    written: bool,
+}
+
+impl<'a, D: DeviceV1_0> MappedMemory<'a, D> {
+    /// See `DeviceV1_0::map_memory_guard`.
+    pub unsafe fn map(
+        device: &'a D,
+        memory: vk::DeviceMemory,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+        property_flags: vk::MemoryPropertyFlags,
+        non_coherent_atom_size: vk::DeviceSize,
+    ) -> VkResult<MappedMemory<'a, D>> {
+        assert!(
+            size != vk::VK_WHOLE_SIZE,
+            "The size of a guarded mapping has to be known"
+        );
+        let coherent = property_flags.subset(vk::MEMORY_PROPERTY_HOST_COHERENT_BIT);
+        let non_coherent_atom_size = if coherent {
+            None
+        } else {
+            Some(non_coherent_atom_size.max(1))
+        };
+        let (map_offset, map_size) = match non_coherent_atom_size {
+            Some(atom) => (offset / atom * atom, vk::VK_WHOLE_SIZE),
+            None => (offset, size),
+        };
+        let data = device.map_memory(memory, map_offset, map_size, vk::MemoryMapFlags::empty())?;
+        let mapped = MappedMemory {
+            device: device,
+            memory: memory,
+            ptr: (data as *mut u8).offset((offset - map_offset) as isize),
+            offset: offset,
+            size: size,
+            non_coherent_atom_size: non_coherent_atom_size,
+            written: false,
+        };
+        mapped.invalidate()?;
+        Ok(mapped)
+    }
+
+    pub fn memory(&self) -> vk::DeviceMemory {
+        self.memory
+    }
+
+    /// The offset of the mapped range in the memory object.
+    pub fn offset(&self) -> vk::DeviceSize {
+        self.offset
+    }
+
+    pub fn size(&self) -> vk::DeviceSize {
+        self.size
+    }
+
+    pub fn is_coherent(&self) -> bool {
+        self.non_coherent_atom_size.is_none()
+    }
+
+    pub fn as_ptr(&self) -> *mut vk::c_void {
+        self.ptr as *mut vk::c_void
+    }
+
+    /// `len` elements of `T` at `offset`.
+    pub fn slice<T: Copy>(&self, offset: vk::DeviceSize, len: usize) -> &[T] {
+        unsafe { slice::from_raw_parts(self.element_ptr::<T>(offset, len), len) }
+    }
+
+    /// `len` elements of `T` at `offset`, which are flushed when the memory is unmapped.
+    pub fn slice_mut<T: Copy>(&mut self, offset: vk::DeviceSize, len: usize) -> &mut [T] {
+        self.written = true;
+        unsafe { slice::from_raw_parts_mut(self.element_ptr::<T>(offset, len), len) }
+    }
+
+    /// Copies `data` to `offset`.
+    pub fn copy_from_slice<T: Copy>(&mut self, offset: vk::DeviceSize, data: &[T]) {
+        self.slice_mut(offset, data.len()).copy_from_slice(data);
+    }
+
+    /// An `Align` over `size` bytes at `offset`, with elements `alignment` bytes apart. It
+    /// borrows the guard, so it can't outlive the mapping:
+    ///
+    /// ```rust,compile_fail,E0505
+    /// # extern crate ash;
+    /// # use ash::memory::MappedMemory;
+    /// # use ash::version::V1_0;
+    /// fn f(mut memory: MappedMemory<ash::Device<V1_0>>) {
+    ///     let align = memory.align::<u32>(0, 16, 64);
+    ///     drop(memory);
+    ///     align.len();
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub fn align<'m, T>(
+        &'m mut self,
+        offset: vk::DeviceSize,
+        alignment: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Align<'m, T> {
+        self.check_range(offset, size);
+        self.written = true;
+        unsafe {
+            Align::new(
+                self.ptr.offset(offset as isize) as *mut vk::c_void,
+                alignment,
+                size,
+            )
+        }
+    }
+
+    /// Flushes the whole mapped range. Does nothing for host coherent memory.
+    pub fn flush(&self) -> VkResult<()> {
+        self.flush_range(0, self.size)
+    }
+
+    /// Flushes `size` bytes at `offset`, rounded out to `non_coherent_atom_size`.
+    pub fn flush_range(&self, offset: vk::DeviceSize, size: vk::DeviceSize) -> VkResult<()> {
+        self.check_range(offset, size);
+        match self.memory_range(offset, size) {
+            Some(range) => unsafe { self.device.flush_mapped_memory_ranges(&[range]) },
+            None => Ok(()),
+        }
+    }
+
+    /// Invalidates the whole mapped range, so that writes of the device become visible. Does
+    /// nothing for host coherent memory.
+    pub fn invalidate(&self) -> VkResult<()> {
+        self.invalidate_range(0, self.size)
+    }
+
+    /// Invalidates `size` bytes at `offset`, rounded out to `non_coherent_atom_size`.
+    pub fn invalidate_range(&self, offset: vk::DeviceSize, size: vk::DeviceSize) -> VkResult<()> {
+        self.check_range(offset, size);
+        match self.memory_range(offset, size) {
+            Some(range) => unsafe { self.device.invalidate_mapped_memory_ranges(&[range]) },
+            None => Ok(()),
+        }
+    }
+
+    /// Flushes if needed and unmaps the memory, returning the error of the flush that dropping
+    /// the guard would ignore.
+    pub fn unmap(mut self) -> VkResult<()> {
+        let result = self.flush_written();
+        unsafe {
+            self.device.unmap_memory(self.memory);
+        }
+        // Already unmapped, so `drop` has nothing left to do.
+        self.memory = vk::DeviceMemory::null();
+        result
+    }
+
+    fn flush_written(&mut self) -> VkResult<()> {
+        if self.written {
+            self.written = false;
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_range(&self, offset: vk::DeviceSize, size: vk::DeviceSize) {
+        let end = offset.checked_add(size);
+        assert!(
+            end.map_or(false, |end| end <= self.size),
+            "Range of {} bytes at offset {} is out of the {} mapped bytes",
+            size,
+            offset,
+            self.size
+        );
+    }
+
+    fn element_ptr<T>(&self, offset: vk::DeviceSize, len: usize) -> *mut T {
+        let size = (size_of::<T>() as vk::DeviceSize)
+            .checked_mul(len as vk::DeviceSize)
+            .expect("Size overflows vk::DeviceSize");
+        self.check_range(offset, size);
+        let ptr = unsafe { self.ptr.offset(offset as isize) };
+        assert!(
+            ptr as usize % align_of::<T>() == 0,
+            "Offset {} is not aligned for a type with an alignment of {}",
+            offset,
+            align_of::<T>()
+        );
+        ptr as *mut T
+    }
+
+    /// The range to flush or invalidate, or `None` if the memory is host coherent.
+    fn memory_range(
+        &self,
+        offset: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Option<vk::MappedMemoryRange> {
+        let atom = self.non_coherent_atom_size?;
+        let start = self.offset + offset;
+        let end = start + size;
+        let range_start = start / atom * atom;
+        let range_end = align_up(end, atom);
+        // Past the end of the mapped range the end of the memory object is unknown, so the
+        // rest of the mapping is used instead of a range that could leave the allocation.
+        let range_size = if range_end > self.offset + self.size {
+            vk::VK_WHOLE_SIZE
+        } else {
+            range_end - range_start
+        };
+        Some(vk::MappedMemoryRange {
+            s_type: vk::StructureType::MappedMemoryRange,
+            p_next: ptr::null(),
+            memory: self.memory,
+            offset: range_start,
+            size: range_size,
+        })
+    }
+}
+
+impl<'a, D: DeviceV1_0> Drop for MappedMemory<'a, D> {
+    fn drop(&mut self) {
+        if self.memory == vk::DeviceMemory::null() {
+            return;
+        }
+        let _ = self.flush_written();
+        unsafe {
+            self.device.unmap_memory(self.memory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Never called, because the guards below are already unmapped and host coherent.
+    struct NoDevice;
+
+    impl DeviceV1_0 for NoDevice {
+        fn handle(&self) -> vk::Device {
+            unsafe { vk::Device::null() }
+        }
+
+        fn fp_v1_0(&self) -> &vk::DeviceFnV1_0 {
+            unreachable!()
+        }
+    }
+
+    fn mapped<'a>(
+        bytes: &'a mut [u32],
+        non_coherent_atom_size: Option<vk::DeviceSize>,
+    ) -> MappedMemory<'a, NoDevice> {
+        static DEVICE: NoDevice = NoDevice;
+        MappedMemory {
+            device: &DEVICE,
+            memory: vk::DeviceMemory::null(),
+            ptr: bytes.as_mut_ptr() as *mut u8,
+            offset: 0,
+            size: (bytes.len() * size_of::<u32>()) as vk::DeviceSize,
+            non_coherent_atom_size: non_coherent_atom_size,
+            written: false,
+        }
+    }
+
+    #[test]
+    fn views_in_bounds() {
+        let mut bytes = [0u32; 4];
+        {
+            let mut memory = mapped(&mut bytes, None);
+            memory.copy_from_slice(8, &[1u32, 2]);
+            memory.slice_mut::<u32>(0, 1)[0] = 3;
+            assert_eq!(memory.slice::<u32>(0, 4), &[3, 0, 1, 2]);
+            memory.align::<u32>(4, 8, 12).set(1, 4);
+        }
+        assert_eq!(bytes, [3, 0, 1, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range of 8 bytes at offset 12 is out of the 16 mapped bytes")]
+    fn write_out_of_bounds() {
+        let mut bytes = [0u32; 4];
+        mapped(&mut bytes, None).copy_from_slice(12, &[1u32, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range of 16 bytes at offset 4 is out of the 16 mapped bytes")]
+    fn align_out_of_bounds() {
+        let mut bytes = [0u32; 4];
+        mapped(&mut bytes, None).align::<u32>(4, 4, 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "Offset 2 is not aligned")]
+    fn misaligned_view() {
+        let mut bytes = [0u32; 4];
+        mapped(&mut bytes, None).slice::<u32>(2, 1);
+    }
+
+    #[test]
+    fn ranges_are_rounded_to_atoms() {
+        let mut bytes = [0u32; 64];
+        let mut memory = mapped(&mut bytes, Some(64));
+        assert!(mapped(&mut [0u32; 4], None).memory_range(0, 16).is_none());
+
+        memory.offset = 100;
+        let range = memory.memory_range(10, 20).unwrap();
+        assert_eq!((range.offset, range.size), (64, 128));
+        // Past the end of the mapped range, the rest of the mapping is used.
+        let range = memory.memory_range(200, 56).unwrap();
+        assert_eq!((range.offset, range.size), (256, vk::VK_WHOLE_SIZE));
+    }
+}
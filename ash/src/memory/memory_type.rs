@@ -0,0 +1,284 @@
+use std::cmp::Reverse;
+use std::error::Error;
+use std::fmt;
+use vk;
+
+/// Flags that a memory type must have, and flags that it should have if there is a choice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MemoryTypeRequest {
+    pub required: vk::MemoryPropertyFlags,
+    pub preferred: vk::MemoryPropertyFlags,
+}
+
+impl MemoryTypeRequest {
+    pub fn new(required: vk::MemoryPropertyFlags) -> MemoryTypeRequest {
+        MemoryTypeRequest {
+            required: required,
+            preferred: vk::MemoryPropertyFlags::empty(),
+        }
+    }
+
+    pub fn preferred(mut self, preferred: vk::MemoryPropertyFlags) -> MemoryTypeRequest {
+        self.preferred = preferred;
+        self
+    }
+
+    fn wants_device_local(&self) -> bool {
+        (self.required | self.preferred).intersects(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryTypeError {
+    /// `memory_type_bits` doesn't allow any of the memory types of the device.
+    NoAllowedType { memory_type_bits: vk::uint32_t },
+    /// For every request, the allowed memory types and the required flags each of them lacks.
+    MissingFlags {
+        memory_type_bits: vk::uint32_t,
+        requests: Vec<(MemoryTypeRequest, Vec<(vk::uint32_t, vk::MemoryPropertyFlags)>)>,
+    },
+}
+
+impl fmt::Display for MemoryTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MemoryTypeError::{:?}", self)
+    }
+}
+
+impl Error for MemoryTypeError {
+    fn description(&self) -> &str {
+        "MemoryTypeError"
+    }
+}
+
+/// The memory types in `memory_type_bits` that have the required flags of `request`, best
+/// first.
+///
+/// Types with more of the preferred flags come first. Between those, a type whose heap is
+/// `MEMORY_HEAP_DEVICE_LOCAL_BIT` comes first if the request asks for `DEVICE_LOCAL` and last
+/// otherwise, so that host memory on a discrete GPU doesn't use up the small device local heap
+/// that is also host visible. On an integrated GPU, where every heap is device local, this makes
+/// no difference. After that, types with fewer flags that weren't asked for and types on larger
+/// heaps come first.
+pub fn rank_memory_types(
+    memory_type_bits: vk::uint32_t,
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    request: MemoryTypeRequest,
+) -> Vec<vk::uint32_t> {
+    let memory_types =
+        &memory_properties.memory_types[..memory_properties.memory_type_count as usize];
+    let mut candidates: Vec<vk::uint32_t> = (0..memory_types.len() as vk::uint32_t)
+        .filter(|&index| {
+            memory_type_bits & (1 << index) != 0
+                && memory_types[index as usize]
+                    .property_flags
+                    .subset(request.required)
+        })
+        .collect();
+    let wants_device_local = request.wants_device_local();
+    candidates.sort_by_key(|&index| {
+        let memory_type = &memory_types[index as usize];
+        let heap = &memory_properties.memory_heaps[memory_type.heap_index as usize];
+        let flags = memory_type.property_flags;
+        let preferred = (flags & request.preferred).flags().count_ones();
+        let extra = (flags - (request.required | request.preferred))
+            .flags()
+            .count_ones();
+        let device_local_heap = heap.flags.subset(vk::MEMORY_HEAP_DEVICE_LOCAL_BIT);
+        (
+            Reverse(preferred),
+            device_local_heap != wants_device_local,
+            extra,
+            Reverse(heap.size),
+            index,
+        )
+    });
+    candidates
+}
+
+/// Finds the best memory type for the first request in `requests` that any allowed type can
+/// satisfy, so the requests are fallbacks in order.
+///
+/// ```rust,ignore
+/// let index = find_memory_type_index(
+///     &device.get_buffer_memory_requirements(buffer),
+///     &memory_properties,
+///     &[
+///         MemoryTypeRequest::new(vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT)
+///             .preferred(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT),
+///     ],
+/// )?;
+/// ```
+pub fn find_memory_type_index(
+    memory_requirements: &vk::MemoryRequirements,
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    requests: &[MemoryTypeRequest],
+) -> Result<vk::uint32_t, MemoryTypeError> {
+    let memory_type_bits = memory_requirements.memory_type_bits;
+    for &request in requests {
+        let ranked = rank_memory_types(memory_type_bits, memory_properties, request);
+        if let Some(&index) = ranked.first() {
+            return Ok(index);
+        }
+    }
+    Err(memory_type_error(memory_type_bits, memory_properties, requests))
+}
+
+/// Why none of the memory types in `memory_type_bits` satisfies any of `requests`.
+pub fn memory_type_error(
+    memory_type_bits: vk::uint32_t,
+    memory_properties: &vk::PhysicalDeviceMemoryProperties,
+    requests: &[MemoryTypeRequest],
+) -> MemoryTypeError {
+    let allowed: Vec<vk::uint32_t> = (0..memory_properties.memory_type_count)
+        .filter(|&index| memory_type_bits & (1 << index) != 0)
+        .collect();
+    if allowed.is_empty() {
+        return MemoryTypeError::NoAllowedType {
+            memory_type_bits: memory_type_bits,
+        };
+    }
+    let requests = requests
+        .iter()
+        .map(|&request| {
+            let missing = allowed
+                .iter()
+                .map(|&index| {
+                    let flags = memory_properties.memory_types[index as usize].property_flags;
+                    (index, request.required - flags)
+                })
+                .collect();
+            (request, missing)
+        })
+        .collect();
+    MemoryTypeError::MissingFlags {
+        memory_type_bits: memory_type_bits,
+        requests: requests,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_memory_type_index, rank_memory_types, MemoryTypeError, MemoryTypeRequest};
+    use std::mem;
+    use vk;
+
+    const DEVICE_LOCAL: vk::MemoryPropertyFlags = vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT;
+    const HOST_VISIBLE: vk::MemoryPropertyFlags = vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT;
+    const HOST_COHERENT: vk::MemoryPropertyFlags = vk::MEMORY_PROPERTY_HOST_COHERENT_BIT;
+    const HOST_CACHED: vk::MemoryPropertyFlags = vk::MEMORY_PROPERTY_HOST_CACHED_BIT;
+
+    fn memory_properties(
+        heaps: &[(vk::DeviceSize, vk::MemoryHeapFlags)],
+        types: &[(vk::MemoryPropertyFlags, vk::uint32_t)],
+    ) -> vk::PhysicalDeviceMemoryProperties {
+        let mut properties: vk::PhysicalDeviceMemoryProperties = unsafe { mem::zeroed() };
+        properties.memory_heap_count = heaps.len() as vk::uint32_t;
+        for (heap, &(size, flags)) in properties.memory_heaps.iter_mut().zip(heaps) {
+            heap.size = size;
+            heap.flags = flags;
+        }
+        properties.memory_type_count = types.len() as vk::uint32_t;
+        for (memory_type, &(flags, heap_index)) in properties.memory_types.iter_mut().zip(types) {
+            memory_type.property_flags = flags;
+            memory_type.heap_index = heap_index;
+        }
+        properties
+    }
+
+    /// A discrete GPU with a large device local heap, a small device local and host visible
+    /// heap, and system memory.
+    fn discrete() -> vk::PhysicalDeviceMemoryProperties {
+        memory_properties(
+            &[
+                (8 << 30, vk::MEMORY_HEAP_DEVICE_LOCAL_BIT),
+                (256 << 20, vk::MEMORY_HEAP_DEVICE_LOCAL_BIT),
+                (16 << 30, vk::MemoryHeapFlags::empty()),
+            ],
+            &[
+                (DEVICE_LOCAL, 0),
+                (DEVICE_LOCAL | HOST_VISIBLE | HOST_COHERENT, 1),
+                (HOST_VISIBLE | HOST_COHERENT, 2),
+                (HOST_VISIBLE | HOST_COHERENT | HOST_CACHED, 2),
+            ],
+        )
+    }
+
+    #[test]
+    fn device_local() {
+        let request = MemoryTypeRequest::new(DEVICE_LOCAL);
+        assert_eq!(rank_memory_types(!0, &discrete(), request), vec![0, 1]);
+    }
+
+    #[test]
+    fn host_memory_avoids_the_device_local_heap() {
+        let request = MemoryTypeRequest::new(HOST_VISIBLE | HOST_COHERENT);
+        assert_eq!(rank_memory_types(!0, &discrete(), request), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn preferred_flags_come_first() {
+        let upload = MemoryTypeRequest::new(HOST_VISIBLE | HOST_COHERENT).preferred(DEVICE_LOCAL);
+        assert_eq!(rank_memory_types(!0, &discrete(), upload), vec![1, 2, 3]);
+        let readback = MemoryTypeRequest::new(HOST_VISIBLE | HOST_COHERENT).preferred(HOST_CACHED);
+        assert_eq!(rank_memory_types(!0, &discrete(), readback), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn larger_heaps_first() {
+        let properties = memory_properties(
+            &[
+                (1 << 30, vk::MEMORY_HEAP_DEVICE_LOCAL_BIT),
+                (4 << 30, vk::MEMORY_HEAP_DEVICE_LOCAL_BIT),
+            ],
+            &[(DEVICE_LOCAL, 0), (DEVICE_LOCAL, 1)],
+        );
+        let request = MemoryTypeRequest::new(DEVICE_LOCAL);
+        assert_eq!(rank_memory_types(!0, &properties, request), vec![1, 0]);
+    }
+
+    #[test]
+    fn memory_type_bits_filter() {
+        let request = MemoryTypeRequest::new(DEVICE_LOCAL);
+        assert_eq!(rank_memory_types(0b10, &discrete(), request), vec![1]);
+        assert_eq!(rank_memory_types(0b1100, &discrete(), request), vec![]);
+    }
+
+    #[test]
+    fn fallback_requests() {
+        let requirements = vk::MemoryRequirements {
+            size: 256,
+            alignment: 16,
+            memory_type_bits: 0b1100,
+        };
+        let requests = [
+            MemoryTypeRequest::new(DEVICE_LOCAL | HOST_VISIBLE),
+            MemoryTypeRequest::new(HOST_VISIBLE),
+        ];
+        assert_eq!(find_memory_type_index(&requirements, &discrete(), &requests), Ok(2));
+    }
+
+    #[test]
+    fn errors() {
+        let mut requirements = vk::MemoryRequirements {
+            size: 256,
+            alignment: 16,
+            memory_type_bits: 0b1_0000,
+        };
+        let request = MemoryTypeRequest::new(DEVICE_LOCAL);
+        assert_eq!(
+            find_memory_type_index(&requirements, &discrete(), &[request]),
+            Err(MemoryTypeError::NoAllowedType {
+                memory_type_bits: 0b1_0000,
+            })
+        );
+        requirements.memory_type_bits = 0b100;
+        assert_eq!(
+            find_memory_type_index(&requirements, &discrete(), &[request]),
+            Err(MemoryTypeError::MissingFlags {
+                memory_type_bits: 0b100,
+                requests: vec![(request, vec![(2, DEVICE_LOCAL)])],
+            })
+        );
+    }
+}
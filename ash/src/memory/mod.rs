@@ -0,0 +1,12 @@
+//! Memory type selection and sub-allocation of device memory.
+pub use self::allocator::{Allocation, AllocatorError, MemoryAllocator, MemoryStats, MemoryUsage,
+                          DEFAULT_BLOCK_SIZE};
+pub use self::block::ResourceKind;
+pub use self::mapped::MappedMemory;
+pub use self::memory_type::{find_memory_type_index, rank_memory_types, MemoryTypeError,
+                            MemoryTypeRequest};
+
+mod allocator;
+mod block;
+mod mapped;
+mod memory_type;
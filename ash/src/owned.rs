@@ -0,0 +1,374 @@
+//! Owning wrappers that destroy their handle when they are dropped.
+//!
+//! Every child object holds an `Arc` of its `Device`, and every `Device` created through
+//! `Device::create` holds an `Arc` of its `Instance`, so parents are destroyed after their last
+//! child no matter in which order the wrappers are dropped.
+//!
+//! ```rust,ignore
+//! let instance = owned::Instance::create(&entry, &instance_info, None)?;
+//! let device = owned::Device::create(&instance, physical_device, &device_info, None)?;
+//! let buffer = owned::Buffer::create(&device, &buffer_info, None)?;
+//! device.bind_buffer_memory(buffer.handle(), memory.handle(), 0)?;
+//! ```
+//!
+//! There are wrappers for every object that `DeviceV1_0` creates and destroys, and for
+//! `VkSwapchainKHR`. Command buffers and descriptor sets are freed with their pool, and the
+//! objects of the other extensions aren't covered.
+//!
+//! The wrappers don't wait for the device, so a handle that the device is still using must be
+//! kept alive, for example until the fence of the submission that uses it is signaled.
+//!
+//! The allocation callbacks that an object was created with are kept and passed again when it is
+//! destroyed, possibly on a different thread, which the callbacks have to allow.
+use device::DeviceV1_0;
+use entry::EntryV1_0;
+use extensions;
+use instance::InstanceV1_0;
+use prelude::*;
+use std::ops::Deref;
+use std::sync::Arc;
+use version::V1_0;
+use vk;
+use {DeviceError, InstanceError};
+
+pub struct Instance {
+    instance: ::Instance<V1_0>,
+    allocation_callbacks: Option<vk::AllocationCallbacks>,
+}
+
+unsafe impl Send for Instance {}
+unsafe impl Sync for Instance {}
+
+impl Instance {
+    pub unsafe fn create(
+        entry: &::Entry<V1_0>,
+        create_info: &vk::InstanceCreateInfo,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Result<Arc<Instance>, InstanceError> {
+        let instance = entry.create_instance(create_info, allocation_callbacks)?;
+        Ok(Instance::from_raw(instance, allocation_callbacks))
+    }
+
+    /// Takes ownership of `instance`, which was created with `allocation_callbacks`.
+    pub unsafe fn from_raw(
+        instance: ::Instance<V1_0>,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Arc<Instance> {
+        Arc::new(Instance {
+            instance: instance,
+            allocation_callbacks: allocation_callbacks.cloned(),
+        })
+    }
+}
+
+impl Deref for Instance {
+    type Target = ::Instance<V1_0>;
+    fn deref(&self) -> &::Instance<V1_0> {
+        &self.instance
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        unsafe {
+            self.instance
+                .destroy_instance(self.allocation_callbacks.as_ref());
+        }
+    }
+}
+
+pub struct Device {
+    device: ::Device<V1_0>,
+    allocation_callbacks: Option<vk::AllocationCallbacks>,
+    instance: Option<Arc<Instance>>,
+}
+
+unsafe impl Send for Device {}
+unsafe impl Sync for Device {}
+
+impl Device {
+    pub unsafe fn create(
+        instance: &Arc<Instance>,
+        physical_device: vk::PhysicalDevice,
+        create_info: &vk::DeviceCreateInfo,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Result<Arc<Device>, DeviceError> {
+        let device = instance.create_device(physical_device, create_info, allocation_callbacks)?;
+        Ok(Device::from_raw(
+            device,
+            Some(instance.clone()),
+            allocation_callbacks,
+        ))
+    }
+
+    /// Takes ownership of `device`, which was created with `allocation_callbacks`. If `instance`
+    /// is given, it is kept alive until the device is destroyed.
+    pub unsafe fn from_raw(
+        device: ::Device<V1_0>,
+        instance: Option<Arc<Instance>>,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Arc<Device> {
+        Arc::new(Device {
+            device: device,
+            allocation_callbacks: allocation_callbacks.cloned(),
+            instance: instance,
+        })
+    }
+
+    pub fn instance(&self) -> Option<&Arc<Instance>> {
+        self.instance.as_ref()
+    }
+}
+
+impl Deref for Device {
+    type Target = ::Device<V1_0>;
+    fn deref(&self) -> &::Device<V1_0> {
+        &self.device
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .destroy_device(self.allocation_callbacks.as_ref());
+        }
+    }
+}
+
+macro_rules! device_child {
+    ($name: ident, $destroy: ident) => {
+        pub struct $name {
+            device: Arc<Device>,
+            handle: vk::$name,
+            allocation_callbacks: Option<vk::AllocationCallbacks>,
+        }
+
+        unsafe impl Send for $name {}
+        unsafe impl Sync for $name {}
+
+        impl $name {
+            /// Takes ownership of `handle`, which was created from `device` with
+            /// `allocation_callbacks`.
+            pub unsafe fn from_raw(
+                device: Arc<Device>,
+                handle: vk::$name,
+                allocation_callbacks: Option<&vk::AllocationCallbacks>,
+            ) -> $name {
+                $name {
+                    device: device,
+                    handle: handle,
+                    allocation_callbacks: allocation_callbacks.cloned(),
+                }
+            }
+
+            pub fn handle(&self) -> vk::$name {
+                self.handle
+            }
+
+            pub fn device(&self) -> &Arc<Device> {
+                &self.device
+            }
+
+            /// Gives up ownership without destroying the handle.
+            pub fn into_raw(mut self) -> vk::$name {
+                let handle = self.handle;
+                self.handle = vk::$name::null();
+                handle
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                if self.handle != vk::$name::null() {
+                    unsafe {
+                        self.device
+                            .$destroy(self.handle, self.allocation_callbacks.as_ref());
+                    }
+                }
+            }
+        }
+    };
+    ($name: ident, $destroy: ident, $method: ident = $create: ident($create_info: ident)) => {
+        device_child!($name, $destroy);
+
+        impl $name {
+            pub unsafe fn $method(
+                device: &Arc<Device>,
+                create_info: &vk::$create_info,
+                allocation_callbacks: Option<&vk::AllocationCallbacks>,
+            ) -> VkResult<$name> {
+                let handle = device.$create(create_info, allocation_callbacks)?;
+                Ok($name::from_raw(
+                    device.clone(),
+                    handle,
+                    allocation_callbacks,
+                ))
+            }
+        }
+    };
+}
+
+device_child!(Buffer, destroy_buffer, create = create_buffer(BufferCreateInfo));
+device_child!(BufferView, destroy_buffer_view, create = create_buffer_view(BufferViewCreateInfo));
+device_child!(
+    CommandPool,
+    destroy_command_pool,
+    create = create_command_pool(CommandPoolCreateInfo)
+);
+device_child!(
+    DescriptorPool,
+    destroy_descriptor_pool,
+    create = create_descriptor_pool(DescriptorPoolCreateInfo)
+);
+device_child!(
+    DescriptorSetLayout,
+    destroy_descriptor_set_layout,
+    create = create_descriptor_set_layout(DescriptorSetLayoutCreateInfo)
+);
+device_child!(DeviceMemory, free_memory, allocate = allocate_memory(MemoryAllocateInfo));
+device_child!(Event, destroy_event, create = create_event(EventCreateInfo));
+device_child!(Fence, destroy_fence, create = create_fence(FenceCreateInfo));
+device_child!(Framebuffer, destroy_framebuffer, create = create_framebuffer(FramebufferCreateInfo));
+device_child!(Image, destroy_image, create = create_image(ImageCreateInfo));
+device_child!(ImageView, destroy_image_view, create = create_image_view(ImageViewCreateInfo));
+device_child!(
+    PipelineCache,
+    destroy_pipeline_cache,
+    create = create_pipeline_cache(PipelineCacheCreateInfo)
+);
+device_child!(
+    PipelineLayout,
+    destroy_pipeline_layout,
+    create = create_pipeline_layout(PipelineLayoutCreateInfo)
+);
+device_child!(QueryPool, destroy_query_pool, create = create_query_pool(QueryPoolCreateInfo));
+device_child!(RenderPass, destroy_render_pass, create = create_render_pass(RenderPassCreateInfo));
+device_child!(Sampler, destroy_sampler, create = create_sampler(SamplerCreateInfo));
+device_child!(Semaphore, destroy_semaphore, create = create_semaphore(SemaphoreCreateInfo));
+device_child!(
+    ShaderModule,
+    destroy_shader_module,
+    create = create_shader_module(ShaderModuleCreateInfo)
+);
+device_child!(Pipeline, destroy_pipeline);
+
+impl Pipeline {
+    /// If any pipeline fails, the ones that were created are destroyed again.
+    pub unsafe fn create_graphics_pipelines(
+        device: &Arc<Device>,
+        pipeline_cache: vk::PipelineCache,
+        create_infos: &[vk::GraphicsPipelineCreateInfo],
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline>> {
+        let result =
+            device.create_graphics_pipelines(pipeline_cache, create_infos, allocation_callbacks);
+        Pipeline::wrap(device, result, allocation_callbacks)
+    }
+
+    /// If any pipeline fails, the ones that were created are destroyed again.
+    pub unsafe fn create_compute_pipelines(
+        device: &Arc<Device>,
+        pipeline_cache: vk::PipelineCache,
+        create_infos: &[vk::ComputePipelineCreateInfo],
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline>> {
+        let result =
+            device.create_compute_pipelines(pipeline_cache, create_infos, allocation_callbacks);
+        Pipeline::wrap(device, result, allocation_callbacks)
+    }
+
+    unsafe fn wrap(
+        device: &Arc<Device>,
+        result: Result<Vec<vk::Pipeline>, (Vec<vk::Pipeline>, vk::Result)>,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Vec<Pipeline>> {
+        let (pipelines, err_code) = match result {
+            Ok(pipelines) => (pipelines, None),
+            Err((pipelines, err_code)) => (pipelines, Some(err_code)),
+        };
+        let pipelines: Vec<Pipeline> = pipelines
+            .into_iter()
+            .map(|handle| Pipeline::from_raw(device.clone(), handle, allocation_callbacks))
+            .collect();
+        match err_code {
+            None => Ok(pipelines),
+            Some(err_code) => Err(err_code),
+        }
+    }
+}
+
+/// A `VkSwapchainKHR`, which is destroyed through the swapchain loader that created it.
+pub struct Swapchain {
+    device: Arc<Device>,
+    loader: extensions::Swapchain,
+    handle: vk::SwapchainKHR,
+    allocation_callbacks: Option<vk::AllocationCallbacks>,
+}
+
+unsafe impl Send for Swapchain {}
+unsafe impl Sync for Swapchain {}
+
+impl Swapchain {
+    /// `loader` has to be loaded for `device`.
+    pub unsafe fn create(
+        device: &Arc<Device>,
+        loader: &extensions::Swapchain,
+        create_info: &vk::SwapchainCreateInfoKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> VkResult<Swapchain> {
+        let handle = loader.create_swapchain_khr(create_info, allocation_callbacks)?;
+        Ok(Swapchain::from_raw(
+            device.clone(),
+            loader.clone(),
+            handle,
+            allocation_callbacks,
+        ))
+    }
+
+    /// Takes ownership of `handle`, which was created from `device` through `loader` with
+    /// `allocation_callbacks`.
+    pub unsafe fn from_raw(
+        device: Arc<Device>,
+        loader: extensions::Swapchain,
+        handle: vk::SwapchainKHR,
+        allocation_callbacks: Option<&vk::AllocationCallbacks>,
+    ) -> Swapchain {
+        Swapchain {
+            device: device,
+            loader: loader,
+            handle: handle,
+            allocation_callbacks: allocation_callbacks.cloned(),
+        }
+    }
+
+    pub fn handle(&self) -> vk::SwapchainKHR {
+        self.handle
+    }
+
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    pub fn loader(&self) -> &extensions::Swapchain {
+        &self.loader
+    }
+
+    /// Gives up ownership without destroying the handle.
+    pub fn into_raw(mut self) -> vk::SwapchainKHR {
+        let handle = self.handle;
+        self.handle = vk::SwapchainKHR::null();
+        handle
+    }
+}
+
+impl Drop for Swapchain {
+    fn drop(&mut self) {
+        if self.handle != vk::SwapchainKHR::null() {
+            unsafe {
+                self.loader
+                    .destroy_swapchain_khr(self.handle, self.allocation_callbacks.as_ref());
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Helpers for reading the pointers and strings that Vulkan hands to us.
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use vk;
+
+/// The string at `ptr`, or an empty string if `ptr` is null.
+pub unsafe fn lossy_str<'a>(ptr: *const vk::c_char) -> Cow<'a, str> {
+    if ptr.is_null() {
+        Cow::Borrowed("")
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy()
+    }
+}
+
+/// The `count` elements at `ptr`, or an empty slice if `ptr` is null.
+pub unsafe fn array<'a, T>(ptr: *const T, count: usize) -> &'a [T] {
+    if ptr.is_null() || count == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, count)
+    }
+}
+
+/// Calls a user callback from a function that Vulkan calls. Unwinding into the driver is
+/// undefined behaviour, so a panic is caught and reported instead.
+pub fn call_callback<F: FnOnce()>(callback: F) {
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(callback)) {
+        let message = payload
+            .downcast_ref::<&'static str>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<String>().map(|message| &message[..]))
+            .unwrap_or("Box<Any>");
+        report_panic(message);
+    }
+}
+
+#[cfg(feature = "log")]
+fn report_panic(message: &str) {
+    error!("Debug callback panicked: {}", message);
+}
+
+#[cfg(not(feature = "log"))]
+fn report_panic(message: &str) {
+    eprintln!("Debug callback panicked: {}", message);
+}
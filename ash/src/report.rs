@@ -0,0 +1,642 @@
+//! A description of a physical device for bug reports, similar to `vulkaninfo`.
+//!
+//! ```rust,ignore
+//! for report in DeviceReport::query_all(&instance)? {
+//!     println!("{}", report);
+//!     fs::write("device.json", report.to_json())?;
+//! }
+//! ```
+//!
+//! `to_json` needs the `json` feature.
+use std::ffi::CStr;
+use std::fmt;
+use prelude::*;
+use vk;
+use version::InstanceV1_0;
+
+/// Everything `InstanceV1_0` can query about a physical device.
+#[derive(Debug, Clone)]
+pub struct DeviceReport {
+    pub properties: vk::PhysicalDeviceProperties,
+    pub features: vk::PhysicalDeviceFeatures,
+    pub memory_properties: vk::PhysicalDeviceMemoryProperties,
+    pub queue_families: Vec<vk::QueueFamilyProperties>,
+    pub extensions: Vec<vk::ExtensionProperties>,
+    pub layers: Vec<vk::LayerProperties>,
+    /// The properties of every format in `vk::Format::ALL`.
+    pub formats: Vec<(vk::Format, vk::FormatProperties)>,
+}
+
+impl DeviceReport {
+    pub fn query<I: InstanceV1_0>(
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<DeviceReport> {
+        let formats = vk::Format::ALL
+            .iter()
+            .map(|&format| {
+                let properties =
+                    instance.get_physical_device_format_properties(physical_device, format);
+                (format, properties)
+            })
+            .collect();
+        Ok(DeviceReport {
+            properties: instance.get_physical_device_properties(physical_device),
+            features: instance.get_physical_device_features(physical_device),
+            memory_properties: instance.get_physical_device_memory_properties(physical_device),
+            queue_families: instance.get_physical_device_queue_family_properties(physical_device),
+            extensions: instance.enumerate_device_extension_properties(physical_device)?,
+            layers: instance.enumerate_device_layer_properties(physical_device)?,
+            formats: formats,
+        })
+    }
+
+    /// Reports for every physical device of `instance`.
+    pub fn query_all<I: InstanceV1_0>(instance: &I) -> VkResult<Vec<DeviceReport>> {
+        instance
+            .enumerate_physical_devices()?
+            .into_iter()
+            .map(|physical_device| DeviceReport::query(instance, physical_device))
+            .collect()
+    }
+
+    pub fn device_name(&self) -> String {
+        c_str(&self.properties.device_name)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write_json(&mut json, &self.to_value(), 0);
+        json.push('\n');
+        json
+    }
+
+    fn to_value(&self) -> Value {
+        let properties = &self.properties;
+        let memory = &self.memory_properties;
+        let memory_heaps = memory.memory_heaps[..memory.memory_heap_count as usize]
+            .iter()
+            .map(|heap| {
+                Value::Object(vec![
+                    ("size", heap.size.into_value()),
+                    ("flags", flag_names(heap.flags.flags(), MEMORY_HEAP_FLAGS)),
+                ])
+            })
+            .collect();
+        let memory_types = memory.memory_types[..memory.memory_type_count as usize]
+            .iter()
+            .map(|memory_type| {
+                Value::Object(vec![
+                    ("heap_index", memory_type.heap_index.into_value()),
+                    (
+                        "property_flags",
+                        flag_names(memory_type.property_flags.flags(), MEMORY_PROPERTY_FLAGS),
+                    ),
+                ])
+            })
+            .collect();
+        let queue_families = self.queue_families
+            .iter()
+            .map(|family| {
+                let granularity = family.min_image_transfer_granularity;
+                Value::Object(vec![
+                    ("queue_flags", flag_names(family.queue_flags.flags(), QUEUE_FLAGS)),
+                    ("queue_count", family.queue_count.into_value()),
+                    ("timestamp_valid_bits", family.timestamp_valid_bits.into_value()),
+                    (
+                        "min_image_transfer_granularity",
+                        [granularity.width, granularity.height, granularity.depth].into_value(),
+                    ),
+                ])
+            })
+            .collect();
+        let extensions = self.extensions
+            .iter()
+            .map(|extension| {
+                Value::Object(vec![
+                    ("name", Value::String(c_str(&extension.extension_name))),
+                    ("spec_version", extension.spec_version.into_value()),
+                ])
+            })
+            .collect();
+        let layers = self.layers
+            .iter()
+            .map(|layer| {
+                Value::Object(vec![
+                    ("name", Value::String(c_str(&layer.layer_name))),
+                    (
+                        "spec_version",
+                        Value::String(vk::Version::from_raw(layer.spec_version).to_string()),
+                    ),
+                    ("implementation_version", layer.implementation_version.into_value()),
+                    ("description", Value::String(c_str(&layer.description))),
+                ])
+            })
+            .collect();
+        let formats = self.formats
+            .iter()
+            .map(|&(format, ref format_properties)| {
+                Value::Object(vec![
+                    ("format", Value::String(format!("{:?}", format))),
+                    (
+                        "linear_tiling_features",
+                        flag_names(
+                            format_properties.linear_tiling_features.flags(),
+                            FORMAT_FEATURE_FLAGS,
+                        ),
+                    ),
+                    (
+                        "optimal_tiling_features",
+                        flag_names(
+                            format_properties.optimal_tiling_features.flags(),
+                            FORMAT_FEATURE_FLAGS,
+                        ),
+                    ),
+                    (
+                        "buffer_features",
+                        flag_names(format_properties.buffer_features.flags(), FORMAT_FEATURE_FLAGS),
+                    ),
+                ])
+            })
+            .collect();
+        let features = self.features
+            .iter()
+            .map(|(name, enabled)| (name, Value::Bool(enabled)))
+            .collect();
+        let sparse = &properties.sparse_properties;
+        Value::Object(vec![
+            ("device_name", Value::String(self.device_name())),
+            (
+                "api_version",
+                Value::String(vk::Version::from_raw(properties.api_version).to_string()),
+            ),
+            (
+                "driver_version",
+                Value::String(vk::DriverVersion::from_properties(properties).to_string()),
+            ),
+            ("vendor_id", properties.vendor_id.into_value()),
+            ("device_id", properties.device_id.into_value()),
+            ("device_type", Value::String(format!("{:?}", properties.device_type))),
+            (
+                "pipeline_cache_uuid",
+                Value::String(
+                    properties
+                        .pipeline_cache_uuid
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect(),
+                ),
+            ),
+            ("limits", limits_value(&properties.limits)),
+            (
+                "sparse_properties",
+                Value::Object(vec![
+                    (
+                        "residency_standard2dblock_shape",
+                        Value::Bool(sparse.residency_standard2dblock_shape != 0),
+                    ),
+                    (
+                        "residency_standard2dmultisample_block_shape",
+                        Value::Bool(sparse.residency_standard2dmultisample_block_shape != 0),
+                    ),
+                    (
+                        "residency_standard3dblock_shape",
+                        Value::Bool(sparse.residency_standard3dblock_shape != 0),
+                    ),
+                    (
+                        "residency_aligned_mip_size",
+                        Value::Bool(sparse.residency_aligned_mip_size != 0),
+                    ),
+                    (
+                        "residency_non_resident_strict",
+                        Value::Bool(sparse.residency_non_resident_strict != 0),
+                    ),
+                ]),
+            ),
+            ("features", Value::Object(features)),
+            ("memory_heaps", Value::List(memory_heaps)),
+            ("memory_types", Value::List(memory_types)),
+            ("queue_families", Value::List(queue_families)),
+            ("extensions", Value::List(extensions)),
+            ("layers", Value::List(layers)),
+            ("formats", Value::List(formats)),
+        ])
+    }
+}
+
+impl fmt::Display for DeviceReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_value() {
+            Value::Object(fields) => for (name, value) in fields {
+                write_text(f, name, &value, 0)?;
+            },
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+}
+
+/// The common shape of the text and the JSON output.
+enum Value {
+    /// Already formatted, so that it is valid in both outputs.
+    Number(String),
+    /// Not finite floats have no JSON representation and are written as `null` there.
+    Float(f32),
+    Bool(bool),
+    String(String),
+    List(Vec<Value>),
+    Object(Vec<(&'static str, Value)>),
+}
+
+trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+macro_rules! number_into_value {
+    ($($ty: ty),*) => {
+        $(
+            impl IntoValue for $ty {
+                fn into_value(self) -> Value {
+                    Value::Number(self.to_string())
+                }
+            }
+        )*
+    }
+}
+
+number_into_value!(u32, i32, u64, usize);
+
+impl IntoValue for f32 {
+    fn into_value(self) -> Value {
+        Value::Float(self)
+    }
+}
+
+/// A `vk::Bool32`, which is a plain `u32` otherwise.
+struct Bool(vk::Bool32);
+
+impl IntoValue for Bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self.0 != 0)
+    }
+}
+
+macro_rules! array_into_value {
+    ($($ty: ty),*) => {
+        $(
+            impl IntoValue for $ty {
+                fn into_value(self) -> Value {
+                    Value::List(self.iter().map(|&value| value.into_value()).collect())
+                }
+            }
+        )*
+    }
+}
+
+array_into_value!([u32; 2], [u32; 3], [f32; 2]);
+
+impl IntoValue for vk::SampleCountFlags {
+    fn into_value(self) -> Value {
+        flag_names(self.flags(), SAMPLE_COUNT_FLAGS)
+    }
+}
+
+fn limits_value(limits: &vk::PhysicalDeviceLimits) -> Value {
+    macro_rules! limits {
+        ($($field: ident $(: $wrap: ident)*),*) => {
+            Value::Object(vec![$((stringify!($field), $($wrap)*(limits.$field).into_value())),*])
+        }
+    }
+    limits!(
+        max_image_dimension1d,
+        max_image_dimension2d,
+        max_image_dimension3d,
+        max_image_dimension_cube,
+        max_image_array_layers,
+        max_texel_buffer_elements,
+        max_uniform_buffer_range,
+        max_storage_buffer_range,
+        max_push_constants_size,
+        max_memory_allocation_count,
+        max_sampler_allocation_count,
+        buffer_image_granularity,
+        sparse_address_space_size,
+        max_bound_descriptor_sets,
+        max_per_stage_descriptor_samplers,
+        max_per_stage_descriptor_uniform_buffers,
+        max_per_stage_descriptor_storage_buffers,
+        max_per_stage_descriptor_sampled_images,
+        max_per_stage_descriptor_storage_images,
+        max_per_stage_descriptor_input_attachments,
+        max_per_stage_resources,
+        max_descriptor_set_samplers,
+        max_descriptor_set_uniform_buffers,
+        max_descriptor_set_uniform_buffers_dynamic,
+        max_descriptor_set_storage_buffers,
+        max_descriptor_set_storage_buffers_dynamic,
+        max_descriptor_set_sampled_images,
+        max_descriptor_set_storage_images,
+        max_descriptor_set_input_attachments,
+        max_vertex_input_attributes,
+        max_vertex_input_bindings,
+        max_vertex_input_attribute_offset,
+        max_vertex_input_binding_stride,
+        max_vertex_output_components,
+        max_tessellation_generation_level,
+        max_tessellation_patch_size,
+        max_tessellation_control_per_vertex_input_components,
+        max_tessellation_control_per_vertex_output_components,
+        max_tessellation_control_per_patch_output_components,
+        max_tessellation_control_total_output_components,
+        max_tessellation_evaluation_input_components,
+        max_tessellation_evaluation_output_components,
+        max_geometry_shader_invocations,
+        max_geometry_input_components,
+        max_geometry_output_components,
+        max_geometry_output_vertices,
+        max_geometry_total_output_components,
+        max_fragment_input_components,
+        max_fragment_output_attachments,
+        max_fragment_dual_src_attachments,
+        max_fragment_combined_output_resources,
+        max_compute_shared_memory_size,
+        max_compute_work_group_count,
+        max_compute_work_group_invocations,
+        max_compute_work_group_size,
+        sub_pixel_precision_bits,
+        sub_texel_precision_bits,
+        mipmap_precision_bits,
+        max_draw_indexed_index_value,
+        max_draw_indirect_count,
+        max_sampler_lod_bias,
+        max_sampler_anisotropy,
+        max_viewports,
+        max_viewport_dimensions,
+        viewport_bounds_range,
+        viewport_sub_pixel_bits,
+        min_memory_map_alignment,
+        min_texel_buffer_offset_alignment,
+        min_uniform_buffer_offset_alignment,
+        min_storage_buffer_offset_alignment,
+        min_texel_offset,
+        max_texel_offset,
+        min_texel_gather_offset,
+        max_texel_gather_offset,
+        min_interpolation_offset,
+        max_interpolation_offset,
+        sub_pixel_interpolation_offset_bits,
+        max_framebuffer_width,
+        max_framebuffer_height,
+        max_framebuffer_layers,
+        framebuffer_color_sample_counts,
+        framebuffer_depth_sample_counts,
+        framebuffer_stencil_sample_counts,
+        framebuffer_no_attachments_sample_counts,
+        max_color_attachments,
+        sampled_image_color_sample_counts,
+        sampled_image_integer_sample_counts,
+        sampled_image_depth_sample_counts,
+        sampled_image_stencil_sample_counts,
+        storage_image_sample_counts,
+        max_sample_mask_words,
+        timestamp_compute_and_graphics: Bool,
+        timestamp_period,
+        max_clip_distances,
+        max_cull_distances,
+        max_combined_clip_and_cull_distances,
+        discrete_queue_priorities,
+        point_size_range,
+        line_width_range,
+        point_size_granularity,
+        line_width_granularity,
+        strict_lines: Bool,
+        standard_sample_locations: Bool,
+        optimal_buffer_copy_offset_alignment,
+        optimal_buffer_copy_row_pitch_alignment,
+        non_coherent_atom_size
+    )
+}
+
+const SAMPLE_COUNT_FLAGS: &'static [(vk::Flags, &'static str)] = &[
+    (0b1, "1"),
+    (0b10, "2"),
+    (0b100, "4"),
+    (0b1000, "8"),
+    (0b10000, "16"),
+    (0b100000, "32"),
+    (0b1000000, "64"),
+];
+
+const QUEUE_FLAGS: &'static [(vk::Flags, &'static str)] = &[
+    (0b1, "GRAPHICS"),
+    (0b10, "COMPUTE"),
+    (0b100, "TRANSFER"),
+    (0b1000, "SPARSE_BINDING"),
+];
+
+const MEMORY_HEAP_FLAGS: &'static [(vk::Flags, &'static str)] = &[(0b1, "DEVICE_LOCAL")];
+
+const MEMORY_PROPERTY_FLAGS: &'static [(vk::Flags, &'static str)] = &[
+    (0b1, "DEVICE_LOCAL"),
+    (0b10, "HOST_VISIBLE"),
+    (0b100, "HOST_COHERENT"),
+    (0b1000, "HOST_CACHED"),
+    (0b10000, "LAZILY_ALLOCATED"),
+];
+
+const FORMAT_FEATURE_FLAGS: &'static [(vk::Flags, &'static str)] = &[
+    (0b1, "SAMPLED_IMAGE"),
+    (0b10, "STORAGE_IMAGE"),
+    (0b100, "STORAGE_IMAGE_ATOMIC"),
+    (0b1000, "UNIFORM_TEXEL_BUFFER"),
+    (0b10000, "STORAGE_TEXEL_BUFFER"),
+    (0b100000, "STORAGE_TEXEL_BUFFER_ATOMIC"),
+    (0b1000000, "VERTEX_BUFFER"),
+    (0b10000000, "COLOR_ATTACHMENT"),
+    (0b100000000, "COLOR_ATTACHMENT_BLEND"),
+    (0b1000000000, "DEPTH_STENCIL_ATTACHMENT"),
+    (0b10000000000, "BLIT_SRC"),
+    (0b100000000000, "BLIT_DST"),
+    (0b1000000000000, "SAMPLED_IMAGE_FILTER_LINEAR"),
+];
+
+/// The names of the bits that are set, with unknown bits as a hex number.
+fn flag_names(flags: vk::Flags, names: &[(vk::Flags, &'static str)]) -> Value {
+    let mut values: Vec<Value> = names
+        .iter()
+        .filter(|&&(bit, _)| flags & bit != 0)
+        .map(|&(_, name)| Value::String(name.to_owned()))
+        .collect();
+    let known = names.iter().fold(0, |known, &(bit, _)| known | bit);
+    if flags & !known != 0 {
+        values.push(Value::String(format!("0x{:x}", flags & !known)));
+    }
+    Value::List(values)
+}
+
+fn c_str(chars: &[vk::c_char]) -> String {
+    unsafe { CStr::from_ptr(chars.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn is_scalar(value: &Value) -> bool {
+    match *value {
+        Value::List(ref values) => values.iter().all(|value| match *value {
+            Value::List(_) | Value::Object(_) => false,
+            _ => true,
+        }),
+        Value::Object(_) => false,
+        _ => true,
+    }
+}
+
+fn write_scalar(f: &mut fmt::Formatter, value: &Value) -> fmt::Result {
+    match *value {
+        Value::Number(ref number) => write!(f, "{}", number),
+        Value::Float(value) => write!(f, "{}", value),
+        Value::Bool(value) => write!(f, "{}", value),
+        Value::String(ref string) => write!(f, "{}", string),
+        Value::List(ref values) => {
+            write!(f, "[")?;
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    write!(f, ", ")?;
+                }
+                write_scalar(f, value)?;
+            }
+            write!(f, "]")
+        }
+        Value::Object(_) => unreachable!(),
+    }
+}
+
+fn write_text(f: &mut fmt::Formatter, name: &str, value: &Value, depth: usize) -> fmt::Result {
+    let indent = "    ".repeat(depth);
+    if is_scalar(value) {
+        write!(f, "{}{} = ", indent, name)?;
+        write_scalar(f, value)?;
+        return writeln!(f);
+    }
+    writeln!(f, "{}{}:", indent, name)?;
+    match *value {
+        Value::Object(ref fields) => for &(name, ref value) in fields {
+            write_text(f, name, value, depth + 1)?;
+        },
+        Value::List(ref values) => for (index, value) in values.iter().enumerate() {
+            write_text(f, &format!("[{}]", index), value, depth + 1)?;
+        },
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+fn write_json(json: &mut String, value: &Value, depth: usize) {
+    let indent = "  ".repeat(depth + 1);
+    match *value {
+        Value::Number(ref number) => json.push_str(number),
+        Value::Float(value) if value.is_finite() => json.push_str(&value.to_string()),
+        Value::Float(_) => json.push_str("null"),
+        Value::Bool(value) => json.push_str(if value { "true" } else { "false" }),
+        Value::String(ref string) => write_json_string(json, string),
+        Value::List(ref values) => {
+            if values.is_empty() {
+                json.push_str("[]");
+                return;
+            }
+            json.push_str("[\n");
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    json.push_str(",\n");
+                }
+                json.push_str(&indent);
+                write_json(json, value, depth + 1);
+            }
+            json.push('\n');
+            json.push_str(&indent[2..]);
+            json.push(']');
+        }
+        Value::Object(ref fields) => {
+            if fields.is_empty() {
+                json.push_str("{}");
+                return;
+            }
+            json.push_str("{\n");
+            for (index, &(name, ref value)) in fields.iter().enumerate() {
+                if index > 0 {
+                    json.push_str(",\n");
+                }
+                json.push_str(&indent);
+                write_json_string(json, name);
+                json.push_str(": ");
+                write_json(json, value, depth + 1);
+            }
+            json.push('\n');
+            json.push_str(&indent[2..]);
+            json.push('}');
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn write_json_string(json: &mut String, string: &str) {
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    fn json(value: Value) -> String {
+        let mut json = String::new();
+        write_json(&mut json, &value, 0);
+        json
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(json(1.5f32.into_value()), "1.5");
+        assert_eq!(json(::std::f32::NAN.into_value()), "null");
+        assert_eq!(json(::std::f32::INFINITY.into_value()), "null");
+        assert_eq!(json(::std::f32::NEG_INFINITY.into_value()), "null");
+        assert_eq!(
+            json([1.0, ::std::f32::INFINITY].into_value()),
+            "[\n  1,\n  null\n]"
+        );
+    }
+
+    #[test]
+    fn bools() {
+        assert_eq!(json(Bool(vk::VK_TRUE).into_value()), "true");
+        assert_eq!(json(Bool(0).into_value()), "false");
+        // Any other value is true as well.
+        assert_eq!(json(Bool(2).into_value()), "true");
+    }
+
+    #[test]
+    fn string_escapes() {
+        let string = "\"quoted\" \\ tab\t cr\r lf\n bell\u{7} del\u{7f} \u{e9}";
+        assert_eq!(
+            json(Value::String(string.to_string())),
+            "\"\\\"quoted\\\" \\\\ tab\\t cr\\r lf\\n bell\\u0007 del\u{7f} \u{e9}\""
+        );
+        assert_eq!(
+            json(Value::Object(vec![("na\"me", Value::List(Vec::new()))])),
+            "{\n  \"na\\\"me\": []\n}"
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Picks a physical device from hard requirements and a score.
+//!
+//! ```rust,ignore
+//! let selection = PhysicalDeviceSelector::new()
+//!     .extension(Swapchain::name())
+//!     .queue_flags(vk::QUEUE_GRAPHICS_BIT)
+//!     .present_support(&surface_loader, surface)
+//!     .limits(LimitRequirements {
+//!         max_push_constants_size: Some(128),
+//!         max_bound_descriptor_sets: Some(4),
+//!         ..Default::default()
+//!     })
+//!     .select(&instance)?;
+//! for rejected in &selection.rejected {
+//!     println!("{:?}: {:?}", rejected.physical_device, rejected.reasons);
+//! }
+//! let physical_device = selection.best().expect("No suitable device").info.physical_device;
+//! ```
+use std::env;
+use std::ffi::{CStr, CString};
+use prelude::*;
+use vk;
+use extensions::Surface;
+use limits::{LimitRequirements, LimitViolation};
+use version::InstanceV1_0;
+
+/// Restricts the selection to one device when it is set. The value is either the index of the
+/// device in `enumerate_physical_devices` or a part of its name, ignoring case.
+pub const FORCE_DEVICE_ENV_VAR: &'static str = "ASH_PHYSICAL_DEVICE";
+
+/// Everything the selector queried about a physical device.
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceInfo {
+    pub physical_device: vk::PhysicalDevice,
+    /// The position in `enumerate_physical_devices`.
+    pub index: usize,
+    pub properties: vk::PhysicalDeviceProperties,
+    pub features: vk::PhysicalDeviceFeatures,
+    pub memory_properties: vk::PhysicalDeviceMemoryProperties,
+    pub queue_families: Vec<vk::QueueFamilyProperties>,
+    pub extensions: Vec<CString>,
+}
+
+impl PhysicalDeviceInfo {
+    pub fn query<I: InstanceV1_0>(
+        instance: &I,
+        physical_device: vk::PhysicalDevice,
+        index: usize,
+    ) -> VkResult<PhysicalDeviceInfo> {
+        let extensions = instance
+            .enumerate_device_extension_properties(physical_device)?
+            .iter()
+            .map(|extension| {
+                unsafe { CStr::from_ptr(extension.extension_name.as_ptr()) }.to_owned()
+            })
+            .collect();
+        Ok(PhysicalDeviceInfo {
+            physical_device: physical_device,
+            index: index,
+            properties: instance.get_physical_device_properties(physical_device),
+            features: instance.get_physical_device_features(physical_device),
+            memory_properties: instance.get_physical_device_memory_properties(physical_device),
+            queue_families: instance.get_physical_device_queue_family_properties(physical_device),
+            extensions: extensions,
+        })
+    }
+
+    pub fn device_name(&self) -> String {
+        unsafe { CStr::from_ptr(self.properties.device_name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    pub fn api_version(&self) -> vk::Version {
+        vk::Version::from_raw(self.properties.api_version)
+    }
+
+    pub fn supports_extension(&self, name: &CStr) -> bool {
+        self.extensions.iter().any(|extension| extension.as_c_str() == name)
+    }
+
+    /// The combined size of the heaps with `MEMORY_HEAP_DEVICE_LOCAL_BIT`.
+    pub fn device_local_memory(&self) -> vk::DeviceSize {
+        let memory = &self.memory_properties;
+        memory.memory_heaps[..memory.memory_heap_count as usize]
+            .iter()
+            .filter(|heap| heap.flags.subset(vk::MEMORY_HEAP_DEVICE_LOCAL_BIT))
+            .map(|heap| heap.size)
+            .sum()
+    }
+}
+
+/// Why a device did not pass the requirements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RejectionReason {
+    /// `FORCE_DEVICE_ENV_VAR` selects another device.
+    NotForced,
+    DeviceType(vk::PhysicalDeviceType),
+    ApiVersion {
+        required: vk::Version,
+        actual: vk::Version,
+    },
+    MissingExtensions(Vec<CString>),
+    /// Names of the `vk::PhysicalDeviceFeatures` fields that aren't supported.
+    MissingFeatures(Vec<&'static str>),
+    Limits(Vec<LimitViolation>),
+    /// No queue family has all of these flags.
+    NoQueueFamily(vk::QueueFlags),
+    NoPresentSupport(vk::SurfaceKHR),
+    /// Querying the device failed.
+    VkError(vk::Result),
+}
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub info: PhysicalDeviceInfo,
+    pub score: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rejected {
+    pub info: Option<PhysicalDeviceInfo>,
+    pub physical_device: vk::PhysicalDevice,
+    pub reasons: Vec<RejectionReason>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Selection {
+    /// The devices that pass every requirement, from the highest to the lowest score.
+    pub suitable: Vec<Candidate>,
+    pub rejected: Vec<Rejected>,
+}
+
+impl Selection {
+    pub fn best(&self) -> Option<&Candidate> {
+        self.suitable.first()
+    }
+}
+
+/// Prefers discrete over integrated over virtual GPUs over CPUs, and more device local memory
+/// between devices of the same type.
+pub fn default_score(info: &PhysicalDeviceInfo) -> i64 {
+    let type_score = match info.properties.device_type {
+        vk::PhysicalDeviceType::DiscreteGpu => 4,
+        vk::PhysicalDeviceType::IntegratedGpu => 3,
+        vk::PhysicalDeviceType::VirtualGpu => 2,
+        vk::PhysicalDeviceType::Cpu => 1,
+        vk::PhysicalDeviceType::Other => 0,
+    };
+    let memory_mib = (info.device_local_memory() / (1024 * 1024)) as i64;
+    type_score * (1 << 40) + memory_mib
+}
+
+/// Collects requirements for a physical device and ranks the devices that meet them.
+pub struct PhysicalDeviceSelector<'a> {
+    device_types: Vec<vk::PhysicalDeviceType>,
+    api_version: Option<vk::Version>,
+    extensions: Vec<&'a CStr>,
+    features: vk::PhysicalDeviceFeatures,
+    limit_requirements: LimitRequirements,
+    queue_flags: Vec<vk::QueueFlags>,
+    present_support: Vec<(&'a Surface, vk::SurfaceKHR)>,
+    score: Box<Fn(&PhysicalDeviceInfo) -> i64 + 'a>,
+    env_var: Option<&'a str>,
+}
+
+impl<'a> Default for PhysicalDeviceSelector<'a> {
+    fn default() -> PhysicalDeviceSelector<'a> {
+        PhysicalDeviceSelector::new()
+    }
+}
+
+impl<'a> PhysicalDeviceSelector<'a> {
+    pub fn new() -> PhysicalDeviceSelector<'a> {
+        PhysicalDeviceSelector {
+            device_types: Vec::new(),
+            api_version: None,
+            extensions: Vec::new(),
+            features: Default::default(),
+            limit_requirements: Default::default(),
+            queue_flags: Vec::new(),
+            present_support: Vec::new(),
+            score: Box::new(default_score),
+            env_var: Some(FORCE_DEVICE_ENV_VAR),
+        }
+    }
+
+    /// Only accepts devices of these types. Every type is accepted by default.
+    pub fn device_types(mut self, device_types: &[vk::PhysicalDeviceType]) -> Self {
+        self.device_types = device_types.to_vec();
+        self
+    }
+
+    pub fn min_api_version(mut self, version: vk::Version) -> Self {
+        self.api_version = Some(version);
+        self
+    }
+
+    pub fn extension(mut self, name: &'a CStr) -> Self {
+        self.extensions.push(name);
+        self
+    }
+
+    pub fn extensions<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = &'a CStr>,
+    {
+        self.extensions.extend(names);
+        self
+    }
+
+    /// Every feature that is set has to be supported.
+    pub fn features(mut self, features: vk::PhysicalDeviceFeatures) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Requires the device to meet `requirements`.
+    pub fn limits(mut self, requirements: LimitRequirements) -> Self {
+        self.limit_requirements = requirements;
+        self
+    }
+
+    /// Requires a queue family that has all of `flags`.
+    pub fn queue_flags(mut self, flags: vk::QueueFlags) -> Self {
+        self.queue_flags.push(flags);
+        self
+    }
+
+    /// Requires a queue family that can present to `surface`.
+    pub fn present_support(mut self, surface_loader: &'a Surface, surface: vk::SurfaceKHR) -> Self {
+        self.present_support.push((surface_loader, surface));
+        self
+    }
+
+    /// Replaces `default_score`. Devices with a higher score come first.
+    pub fn score<F>(mut self, score: F) -> Self
+    where
+        F: Fn(&PhysicalDeviceInfo) -> i64 + 'a,
+    {
+        self.score = Box::new(score);
+        self
+    }
+
+    /// Reads the forced device from another environment variable, or ignores the environment
+    /// with `None`.
+    pub fn force_device_env_var(mut self, env_var: Option<&'a str>) -> Self {
+        self.env_var = env_var;
+        self
+    }
+
+    /// Checks every device of `instance` against the requirements.
+    pub fn select<I: InstanceV1_0>(&self, instance: &I) -> VkResult<Selection> {
+        let physical_devices = instance.enumerate_physical_devices()?;
+        let forced = self.env_var.and_then(|env_var| env::var(env_var).ok());
+        let mut suitable = Vec::new();
+        let mut rejected = Vec::new();
+        for (index, &physical_device) in physical_devices.iter().enumerate() {
+            let info = match PhysicalDeviceInfo::query(instance, physical_device, index) {
+                Ok(info) => info,
+                Err(err) => {
+                    rejected.push(Rejected {
+                        info: None,
+                        physical_device: physical_device,
+                        reasons: vec![RejectionReason::VkError(err)],
+                    });
+                    continue;
+                }
+            };
+            let mut reasons = Vec::new();
+            if let Some(ref forced) = forced {
+                if !is_forced(&info, forced) {
+                    reasons.push(RejectionReason::NotForced);
+                }
+            }
+            reasons.extend(self.check(&info));
+            if reasons.is_empty() {
+                let score = (self.score)(&info);
+                suitable.push(Candidate {
+                    info: info,
+                    score: score,
+                });
+            } else {
+                rejected.push(Rejected {
+                    info: Some(info),
+                    physical_device: physical_device,
+                    reasons: reasons,
+                });
+            }
+        }
+        suitable.sort_by(|a, b| b.score.cmp(&a.score));
+        Ok(Selection {
+            suitable: suitable,
+            rejected: rejected,
+        })
+    }
+
+    /// Every requirement that `info` doesn't meet.
+    pub fn check(&self, info: &PhysicalDeviceInfo) -> Vec<RejectionReason> {
+        let mut reasons = Vec::new();
+        let device_type = info.properties.device_type;
+        if !self.device_types.is_empty() && !self.device_types.contains(&device_type) {
+            reasons.push(RejectionReason::DeviceType(device_type));
+        }
+        if let Some(required) = self.api_version {
+            if info.api_version() < required {
+                reasons.push(RejectionReason::ApiVersion {
+                    required: required,
+                    actual: info.api_version(),
+                });
+            }
+        }
+        let missing_extensions: Vec<CString> = self.extensions
+            .iter()
+            .filter(|name| !info.supports_extension(name))
+            .map(|&name| name.to_owned())
+            .collect();
+        if !missing_extensions.is_empty() {
+            reasons.push(RejectionReason::MissingExtensions(missing_extensions));
+        }
+        let missing_features = self.features.missing_from(&info.features);
+        if !missing_features.is_empty() {
+            reasons.push(RejectionReason::MissingFeatures(missing_features));
+        }
+        let violations = self.limit_requirements.check_properties(&info.properties);
+        if !violations.is_empty() {
+            reasons.push(RejectionReason::Limits(violations));
+        }
+        for &flags in &self.queue_flags {
+            let found = info.queue_families
+                .iter()
+                .any(|family| family.queue_count > 0 && family.queue_flags.subset(flags));
+            if !found {
+                reasons.push(RejectionReason::NoQueueFamily(flags));
+            }
+        }
+        for &(surface_loader, surface) in &self.present_support {
+            let found = (0..info.queue_families.len()).any(|family_index| {
+                surface_loader.get_physical_device_surface_support_khr(
+                    info.physical_device,
+                    family_index as vk::uint32_t,
+                    surface,
+                )
+            });
+            if !found {
+                reasons.push(RejectionReason::NoPresentSupport(surface));
+            }
+        }
+        reasons
+    }
+}
+
+fn is_forced(info: &PhysicalDeviceInfo, forced: &str) -> bool {
+    match forced.trim().parse::<usize>() {
+        Ok(index) => info.index == index,
+        Err(_) => info.device_name()
+            .to_lowercase()
+            .contains(&forced.trim().to_lowercase()),
+    }
+}
@@ -0,0 +1,361 @@
+//! Tracking of the handles that are created and destroyed through ash, for debug builds.
+//!
+//! With the `handle-tracking` feature, every handle that `EntryV1_0`, `InstanceV1_0`,
+//! `DeviceV1_0` or an extension loader creates is recorded together with the backtrace of its
+//! creation. A handle is identified by its value and the instance or device that created it, and
+//! the same value can be handed out more than once, for example for two identical samplers, so
+//! what is recorded is how often it was created and not destroyed yet. Destroying it more often
+//! than that panics with the backtrace of the last destroy, and so does passing it to a
+//! `DeviceV1_0` method when all of its creations were destroyed. When a device or an instance is
+//! destroyed, the objects created from it that are still alive are reported as leaks, through
+//! `log` with the `log` feature and on stderr otherwise.
+//!
+//! The uses that are checked are the handles that `DeviceV1_0` methods take as arguments, alone or
+//! in a slice, the semaphores and the fence of `queue_submit` and the image of
+//! `create_image_view`. The handles in other create infos and structs aren't. Command buffers and
+//! descriptor sets are owned by their pool and aren't tracked, and neither are handles that were
+//! created without ash. Drivers reuse the values of destroyed handles, so a use after destroy is
+//! only caught until the value is handed out again. Without the feature, all of this compiles to
+//! nothing.
+#[cfg(feature = "handle-tracking")]
+use raw;
+use vk::{self, Handle};
+
+#[cfg(any(test, feature = "handle-tracking"))]
+mod registry {
+    use std::backtrace::Backtrace;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, MutexGuard};
+    use vk;
+
+    pub type Handle = (vk::ObjectType, vk::uint64_t);
+
+    /// The instance or device that created a handle, and the handle. Non-dispatchable handles are
+    /// only unique per device, while instances and devices are unique in the process.
+    pub type Key = (Option<Handle>, Handle);
+
+    struct Record {
+        /// The backtraces of the creations that weren't destroyed yet.
+        alive: Vec<Backtrace>,
+        destroyed: Option<Backtrace>,
+    }
+
+    lazy_static! {
+        static ref REGISTRY: Mutex<HashMap<Key, Record>> = Mutex::new(HashMap::new());
+    }
+
+    fn registry() -> MutexGuard<'static, HashMap<Key, Record>> {
+        // A panic while the lock is held is one of ours, and it leaves the map consistent.
+        match REGISTRY.lock() {
+            Ok(registry) => registry,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub fn created(key: Key) {
+        registry()
+            .entry(key)
+            .or_insert_with(|| Record {
+                alive: Vec::new(),
+                destroyed: None,
+            })
+            .alive
+            .push(Backtrace::force_capture());
+    }
+
+    pub fn destroyed(key: Key) {
+        destroy(&mut registry(), key);
+    }
+
+    fn destroy(registry: &mut HashMap<Key, Record>, key: Key) {
+        if let Some(record) = registry.get_mut(&key) {
+            if record.alive.pop().is_some() {
+                record.destroyed = Some(Backtrace::force_capture());
+            } else if let Some(ref backtrace) = record.destroyed {
+                let (_, (object_type, raw)) = key;
+                panic!(
+                    "{:?} {:#x} is destroyed more often than it was created. It was last \
+                     destroyed at:\n{}",
+                    object_type, raw, backtrace
+                );
+            }
+        }
+    }
+
+    pub fn used(key: Key) {
+        if let Some(&Record {
+            ref alive,
+            destroyed: Some(ref backtrace),
+        }) = registry().get(&key)
+        {
+            if alive.is_empty() {
+                let (_, (object_type, raw)) = key;
+                panic!(
+                    "{:?} {:#x} is used after it was destroyed at:\n{}",
+                    object_type, raw, backtrace
+                );
+            }
+        }
+    }
+
+    /// Marks `parent`, which is an instance or a device, as destroyed, reports the children of
+    /// `parent` that are still alive and forgets all of its descendants, whose handles are no
+    /// longer valid. Returns the number of leaks.
+    pub fn parent_destroyed(parent: Handle) -> usize {
+        let mut registry = registry();
+        let key = registry.keys().find(|key| key.1 == parent).cloned();
+        if let Some(key) = key {
+            destroy(&mut registry, key);
+        }
+        forget_children(&mut registry, parent)
+    }
+
+    fn forget_children(registry: &mut HashMap<Key, Record>, parent: Handle) -> usize {
+        let children: Vec<Key> = registry
+            .keys()
+            .filter(|key| key.0 == Some(parent))
+            .cloned()
+            .collect();
+        let mut leaks = 0;
+        for key in children {
+            if let Some(record) = registry.remove(&key) {
+                for backtrace in &record.alive {
+                    report_leak(parent, key.1, backtrace);
+                }
+                leaks += record.alive.len();
+            }
+            leaks += forget_children(registry, key.1);
+        }
+        leaks
+    }
+
+    #[cfg(feature = "log")]
+    fn report_leak(parent: Handle, handle: Handle, backtrace: &Backtrace) {
+        error!(
+            "{:?} {:#x} is still alive when its {:?} {:#x} is destroyed. It was created at:\n{}",
+            handle.0, handle.1, parent.0, parent.1, backtrace
+        );
+    }
+
+    #[cfg(not(feature = "log"))]
+    fn report_leak(parent: Handle, handle: Handle, backtrace: &Backtrace) {
+        eprintln!(
+            "{:?} {:#x} is still alive when its {:?} {:#x} is destroyed. It was created at:\n{}",
+            handle.0, handle.1, parent.0, parent.1, backtrace
+        );
+    }
+}
+
+#[cfg(feature = "handle-tracking")]
+fn handle<H: Handle>(handle: H) -> Option<registry::Handle> {
+    match handle.as_raw() {
+        0 => None,
+        raw => Some((H::TYPE, raw)),
+    }
+}
+
+#[cfg(feature = "handle-tracking")]
+fn key<P: Handle, H: Handle>(parent: P, handle: H) -> Option<registry::Key> {
+    self::handle(handle).map(|handle| (self::handle(parent), handle))
+}
+
+/// Records `instance`, which has no parent.
+#[inline]
+pub fn created_instance(instance: vk::Instance) -> vk::Instance {
+    #[cfg(feature = "handle-tracking")]
+    {
+        if let Some(handle) = handle(instance) {
+            registry::created((None, handle));
+        }
+    }
+    instance
+}
+
+/// Records `handle` as a child of `parent`.
+#[inline]
+pub fn created<P: Handle, H: Handle>(parent: P, handle: H) -> H {
+    #[cfg(feature = "handle-tracking")]
+    {
+        if let Some(key) = key(parent, handle) {
+            registry::created(key);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = parent;
+    handle
+}
+
+/// Records the handles in `handles` that aren't null, as children of `parent`.
+#[inline]
+pub fn created_all<P: Handle, H: Handle>(parent: P, handles: Vec<H>) -> Vec<H> {
+    #[cfg(feature = "handle-tracking")]
+    {
+        for &handle in &handles {
+            created(parent, handle);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = parent;
+    handles
+}
+
+/// Marks one creation of the child `handle` of `parent` as destroyed, and panics if all of them
+/// already were.
+#[inline]
+pub fn destroyed<P: Handle, H: Handle>(parent: P, handle: H) -> H {
+    #[cfg(feature = "handle-tracking")]
+    {
+        if let Some(key) = key(parent, handle) {
+            registry::destroyed(key);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = parent;
+    handle
+}
+
+/// Panics if all creations of the child `handle` of `parent` were destroyed.
+#[inline]
+pub fn used<P: Handle, H: Handle>(parent: P, handle: H) -> H {
+    #[cfg(feature = "handle-tracking")]
+    {
+        if let Some(key) = key(parent, handle) {
+            registry::used(key);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = parent;
+    handle
+}
+
+/// `used` for every handle in `handles`.
+#[inline]
+pub fn used_all<P: Handle, H: Handle>(parent: P, handles: &[H]) {
+    #[cfg(feature = "handle-tracking")]
+    {
+        for &handle in handles {
+            used(parent, handle);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = (parent, handles);
+}
+
+/// `used` for the semaphores in `submits`.
+#[inline]
+pub unsafe fn used_by_submits<P: Handle>(parent: P, submits: &[vk::SubmitInfo]) {
+    #[cfg(feature = "handle-tracking")]
+    {
+        for submit in submits {
+            let count = submit.wait_semaphore_count as usize;
+            used_all(parent, raw::array(submit.p_wait_semaphores, count));
+            let count = submit.signal_semaphore_count as usize;
+            used_all(parent, raw::array(submit.p_signal_semaphores, count));
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = (parent, submits);
+}
+
+/// Marks `parent` as destroyed and reports the children that it leaked.
+#[inline]
+pub fn parent_destroyed<P: Handle>(parent: P) {
+    #[cfg(feature = "handle-tracking")]
+    {
+        if let Some(handle) = handle(parent) {
+            registry::parent_destroyed(handle);
+        }
+    }
+    #[cfg(not(feature = "handle-tracking"))]
+    let _ = parent;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::registry::{self, Handle, Key};
+    use vk;
+
+    // The registry is shared by all tests, so every test uses its own devices.
+    fn device(raw: vk::uint64_t) -> Handle {
+        (vk::ObjectType::Device, raw)
+    }
+
+    fn sampler(device: Handle, raw: vk::uint64_t) -> Key {
+        (Some(device), (vk::ObjectType::Sampler, raw))
+    }
+
+    #[test]
+    fn identical_handles_on_one_device() {
+        let device = device(0x100);
+        registry::created((None, device));
+        registry::created(sampler(device, 1));
+        registry::created(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+        registry::used(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+        assert_eq!(registry::parent_destroyed(device), 0);
+    }
+
+    #[test]
+    fn same_handle_on_two_devices() {
+        let (first, second) = (device(0x200), device(0x201));
+        registry::created((None, first));
+        registry::created((None, second));
+        registry::created(sampler(first, 1));
+        registry::created(sampler(second, 1));
+        registry::destroyed(sampler(first, 1));
+        registry::used(sampler(second, 1));
+        registry::destroyed(sampler(second, 1));
+        assert_eq!(registry::parent_destroyed(first), 0);
+        assert_eq!(registry::parent_destroyed(second), 0);
+    }
+
+    #[test]
+    fn leaks_of_identical_handles() {
+        let device = device(0x300);
+        registry::created((None, device));
+        registry::created(sampler(device, 1));
+        registry::created(sampler(device, 1));
+        registry::created(sampler(device, 2));
+        registry::destroyed(sampler(device, 1));
+        assert_eq!(registry::parent_destroyed(device), 2);
+    }
+
+    #[test]
+    fn leaks_of_grandchildren() {
+        let instance = (vk::ObjectType::Instance, 0x400);
+        let device = device(0x401);
+        registry::created((None, instance));
+        registry::created((Some(instance), device));
+        registry::created(sampler(device, 1));
+        assert_eq!(registry::parent_destroyed(instance), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Sampler 0x1 is destroyed more often than it was created")]
+    fn destroyed_too_often() {
+        let device = device(0x500);
+        registry::created(sampler(device, 1));
+        registry::created(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Sampler 0x1 is used after it was destroyed")]
+    fn used_after_destroy() {
+        let device = device(0x600);
+        registry::created(sampler(device, 1));
+        registry::destroyed(sampler(device, 1));
+        registry::used(sampler(device, 1));
+    }
+
+    #[test]
+    fn unknown_handles() {
+        let device = device(0x700);
+        registry::destroyed(sampler(device, 1));
+        registry::used(sampler(device, 1));
+        assert_eq!(registry::parent_destroyed(device), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use std::iter::Iterator;
 use std::marker::PhantomData;
-use std::mem::size_of;
+use std::mem::{align_of, size_of};
 use vk;
 
 /// `Align` handles dynamic alignment. x86 aligns on 4 byte boundries but GPUs
@@ -11,23 +11,37 @@ use vk;
 /// expects a 4 byte padding in between each Vec3<f32>, if the alignment is 16 bytes.
 /// `Vec3<f32>, 4bytes, Vec3<f32>, 4bytes, Vec3<f32>...`. Align is able to take a slice
 /// that is allocated on 4 bytes boundries, and insert the correct amount of paddings.
+///
+/// `'m` is the lifetime of the memory that `Align` points into, such as a `MappedMemory`.
 #[derive(Debug, Clone)]
-pub struct Align<T> {
+pub struct Align<'m, T: 'm> {
     ptr: *mut vk::c_void,
     elem_size: vk::DeviceSize,
     size: vk::DeviceSize,
-    _m: PhantomData<T>,
+    _m: PhantomData<&'m mut T>,
 }
 
 #[derive(Debug)]
-pub struct AlignIter<'a, T: 'a> {
-    align: &'a mut Align<T>,
+pub struct AlignIter<'a, 'm: 'a, T: 'm> {
+    align: &'a mut Align<'m, T>,
     current: vk::DeviceSize,
 }
 
-impl<T: Copy> Align<T> {
+#[derive(Debug)]
+pub struct AlignReadIter<'a, 'm: 'a, T: 'm> {
+    align: &'a Align<'m, T>,
+    current: vk::DeviceSize,
+}
+
+impl<'m, T: Copy> Align<'m, T> {
     pub fn copy_from_slice(&mut self, slice: &[T]) {
         use std::slice::from_raw_parts_mut;
+        assert!(
+            slice.len() <= self.len(),
+            "Slice of {} elements doesn't fit into {} elements",
+            slice.len(),
+            self.len()
+        );
         if self.elem_size == size_of::<T>() as u64 {
             unsafe {
                 let mapped_slice = from_raw_parts_mut(self.ptr as *mut T, slice.len());
@@ -39,21 +53,45 @@ impl<T: Copy> Align<T> {
             }
         }
     }
+
+    /// Copies every element out of the aligned layout, for example to read back a buffer.
+    pub fn copy_to_vec(&self) -> Vec<T> {
+        self.iter().cloned().collect()
+    }
+
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: T) {
+        let len = self.len();
+        match self.get_mut(index) {
+            Some(element) => *element = value,
+            None => panic!("Index {} is out of bounds for {} elements", index, len),
+        }
+    }
 }
 
 fn calc_padding(adr: vk::DeviceSize, align: vk::DeviceSize) -> vk::DeviceSize {
     (align - adr % align) % align
 }
 
-impl<T> Align<T> {
+impl<'m, T> Align<'m, T> {
+    /// `alignment` is the distance that the start of each element is rounded up to, and doesn't
+    /// have to be a power of two. It has to keep every element aligned for `T`. Elements that
+    /// don't fit completely into `size` are left out.
     pub unsafe fn new(
         ptr: *mut vk::c_void,
         alignment: vk::DeviceSize,
         size: vk::DeviceSize,
     ) -> Self {
+        let alignment = alignment.max(1);
         let padding = calc_padding(size_of::<T>() as vk::DeviceSize, alignment);
         let elem_size = size_of::<T>() as vk::DeviceSize + padding;
-        assert!(calc_padding(size, alignment) == 0, "size must be aligned");
+        assert!(size_of::<T>() != 0, "Align doesn't support zero sized types");
+        assert!(
+            ptr as usize % align_of::<T>() == 0 && elem_size % align_of::<T>() as u64 == 0,
+            "Elements {} bytes apart from {:p} are not aligned for T",
+            elem_size,
+            ptr
+        );
         Align {
             ptr,
             elem_size,
@@ -62,25 +100,214 @@ impl<T> Align<T> {
         }
     }
 
-    pub fn iter_mut(&mut self) -> AlignIter<T> {
+    /// Uses the size of `memory_requirements`, with the elements packed as in a slice of `T`.
+    pub unsafe fn from_memory_requirements(
+        ptr: *mut vk::c_void,
+        memory_requirements: &vk::MemoryRequirements,
+    ) -> Self {
+        Align::new(ptr, align_of::<T>() as u64, memory_requirements.size)
+    }
+
+    /// One element every `stride` bytes, such as a dynamic uniform buffer with a stride that is
+    /// a multiple of `min_uniform_buffer_offset_alignment`.
+    pub unsafe fn with_stride(
+        ptr: *mut vk::c_void,
+        stride: vk::DeviceSize,
+        size: vk::DeviceSize,
+    ) -> Self {
+        assert!(
+            stride >= size_of::<T>() as vk::DeviceSize,
+            "Stride {} is smaller than the element",
+            stride
+        );
+        Align::new(ptr, stride, size)
+    }
+
+    /// The distance between the starts of two elements.
+    pub fn stride(&self) -> vk::DeviceSize {
+        self.elem_size
+    }
+
+    /// The offset of the element at `index`, for example as the dynamic offset of a descriptor.
+    pub fn offset(&self, index: usize) -> vk::DeviceSize {
+        index as vk::DeviceSize * self.elem_size
+    }
+
+    pub fn len(&self) -> usize {
+        let elem = size_of::<T>() as vk::DeviceSize;
+        if self.size < elem {
+            0
+        } else {
+            ((self.size - elem) / self.elem_size + 1) as usize
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() {
+            unsafe { Some(&*self.element_ptr(self.offset(index))) }
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() {
+            unsafe { Some(&mut *self.element_ptr(self.offset(index))) }
+        } else {
+            None
+        }
+    }
+
+    pub fn iter<'a>(&'a self) -> AlignReadIter<'a, 'm, T> {
+        AlignReadIter {
+            current: 0,
+            align: self,
+        }
+    }
+
+    pub fn iter_mut<'a>(&'a mut self) -> AlignIter<'a, 'm, T> {
         AlignIter {
             current: 0,
             align: self,
         }
     }
+
+    fn element_ptr(&self, offset: vk::DeviceSize) -> *mut T {
+        // Need to cast to *mut u8 because () has size 0
+        unsafe { (self.ptr as *mut u8).offset(offset as isize) as *mut T }
+    }
+
+    fn element_fits(&self, offset: vk::DeviceSize) -> bool {
+        offset + size_of::<T>() as vk::DeviceSize <= self.size
+    }
 }
 
-impl<'a, T: Copy + 'a> Iterator for AlignIter<'a, T> {
+impl<'a, 'm, T> Iterator for AlignIter<'a, 'm, T> {
     type Item = &'a mut T;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current == self.align.size {
+        if !self.align.element_fits(self.current) {
             return None;
         }
-        unsafe {
-            // Need to cast to *mut u8 because () has size 0
-            let ptr = (self.align.ptr as *mut u8).offset(self.current as isize) as *mut T;
-            self.current += self.align.elem_size;
-            Some(&mut *ptr)
+        let ptr = self.align.element_ptr(self.current);
+        self.current += self.align.elem_size;
+        unsafe { Some(&mut *ptr) }
+    }
+}
+
+impl<'a, 'm, T> Iterator for AlignReadIter<'a, 'm, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.align.element_fits(self.current) {
+            return None;
+        }
+        let ptr = self.align.element_ptr(self.current);
+        self.current += self.align.elem_size;
+        unsafe { Some(&*ptr) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `u64`s, so that the memory is aligned for every element type below.
+    fn memory(size: usize) -> Vec<u64> {
+        vec![0; size / 8]
+    }
+
+    fn bytes(memory: &[u64]) -> &[u8] {
+        unsafe { ::std::slice::from_raw_parts(memory.as_ptr() as *const u8, memory.len() * 8) }
+    }
+
+    #[test]
+    fn non_power_of_two_alignment() {
+        let mut memory = memory(40);
+        {
+            let mut align = unsafe { Align::<u32>::new(memory.as_mut_ptr() as _, 12, 40) };
+            assert_eq!((align.stride(), align.len()), (12, 4));
+            align.copy_from_slice(&[1, 2, 3, 4]);
+            assert_eq!(align.get(3), Some(&4));
+            assert_eq!(align.get(4), None);
+        }
+        let bytes = bytes(&memory);
+        for (index, &offset) in [0, 12, 24, 36].iter().enumerate() {
+            assert_eq!(bytes[offset], index as u8 + 1);
         }
     }
+
+    #[test]
+    fn partial_last_element() {
+        let mut memory = memory(56);
+        let mut align = unsafe { Align::<[f32; 3]>::new(memory.as_mut_ptr() as _, 16, 56) };
+        // The fourth element would start at 48 and end at 60.
+        assert_eq!(align.len(), 3);
+        assert_eq!(align.iter().count(), 3);
+        assert_eq!(align.iter_mut().count(), 3);
+        align.copy_from_slice(&[[1.0; 3], [2.0; 3], [3.0; 3]]);
+        assert_eq!(align.copy_to_vec(), vec![[1.0; 3], [2.0; 3], [3.0; 3]]);
+
+        // Exactly enough for the last element without its padding.
+        let align = unsafe { Align::<[f32; 3]>::new(memory.as_mut_ptr() as _, 16, 44) };
+        assert_eq!(align.len(), 3);
+        let align = unsafe { Align::<[f32; 3]>::new(memory.as_mut_ptr() as _, 16, 8) };
+        assert!(align.is_empty());
+    }
+
+    #[test]
+    fn stride_offsets() {
+        let mut memory = memory(1024);
+        let mut align =
+            unsafe { Align::<[f32; 4]>::with_stride(memory.as_mut_ptr() as _, 256, 1024) };
+        assert_eq!(align.len(), 4);
+        let offsets: Vec<_> = (0..align.len()).map(|index| align.offset(index)).collect();
+        assert_eq!(offsets, vec![0, 256, 512, 768]);
+        align.set(2, [5.0; 4]);
+        let memory: &[f32] =
+            unsafe { ::std::slice::from_raw_parts(memory.as_ptr() as *const f32, 256) };
+        assert_eq!(&memory[512 / 4..512 / 4 + 4], &[5.0; 4]);
+        assert_eq!(memory[512 / 4 + 4], 0.0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut memory = memory(64);
+        let values = [1u16, 2, 3, 4, 5];
+        let mut align = unsafe { Align::<u16>::new(memory.as_mut_ptr() as _, 8, 64) };
+        assert_eq!(align.len(), 8);
+        align.copy_from_slice(&values);
+        assert_eq!(&align.copy_to_vec()[..5], &values);
+
+        let requirements = vk::MemoryRequirements {
+            size: 10,
+            alignment: 256,
+            memory_type_bits: !0,
+        };
+        let mut packed = unsafe {
+            Align::<u16>::from_memory_requirements(memory.as_mut_ptr() as _, &requirements)
+        };
+        assert_eq!((packed.stride(), packed.len()), (2, 5));
+        packed.copy_from_slice(&values);
+        assert_eq!(packed.copy_to_vec(), values.to_vec());
+        assert_eq!(&bytes(&memory)[..10], &[1, 0, 2, 0, 3, 0, 4, 0, 5, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index 3 is out of bounds for 3 elements")]
+    fn set_out_of_bounds() {
+        let mut memory = memory(48);
+        let mut align = unsafe { Align::<u32>::new(memory.as_mut_ptr() as _, 16, 40) };
+        align.set(3, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice of 4 elements doesn't fit into 3 elements")]
+    fn copy_out_of_bounds() {
+        let mut memory = memory(48);
+        let mut align = unsafe { Align::<u32>::new(memory.as_mut_ptr() as _, 16, 40) };
+        align.copy_from_slice(&[1, 2, 3, 4]);
+    }
 }
@@ -0,0 +1,374 @@
+//! Collects validation layer messages so that tests can fail on them.
+//!
+//! ```rust,ignore
+//! let collector = ValidationCollector::new(&entry);
+//! // Chaining the collector into the create info also collects the messages of
+//! // `create_instance` and `destroy_instance`.
+//! let debug_info = unsafe { collector.instance_create_info() };
+//! instance_info.p_next = &debug_info as *const _ as *const vk::c_void;
+//! let instance = entry.create_instance(&instance_info, None)?;
+//! let callback = collector.register(&instance)?;
+//! assert!(collector.validation_layer_found());
+//! render_one_frame(&device);
+//! collector.assert_no_errors();
+//! ```
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+use vk;
+use version::{EntryV1_0, FunctionPointers};
+use extensions::{DebugReport, DebugReportCallback, DebugReportClosure, DebugReportMessage,
+                 ExtensionError};
+use Instance;
+
+/// Names of the validation layer, from the newest to the oldest.
+pub const VALIDATION_LAYER_NAMES: &'static [&'static str] = &[
+    "VK_LAYER_KHRONOS_validation",
+    "VK_LAYER_LUNARG_standard_validation",
+];
+
+/// Returns the first layer from `VALIDATION_LAYER_NAMES` that the loader knows about, so that it
+/// can be enabled when the instance is created.
+pub fn find_validation_layer<E: EntryV1_0>(entry: &E) -> Option<CString> {
+    let layers = match entry.enumerate_instance_layer_properties() {
+        Ok(layers) => layers,
+        Err(_) => return None,
+    };
+    VALIDATION_LAYER_NAMES
+        .iter()
+        .find(|&&name| {
+            layers.iter().any(|layer| unsafe {
+                CStr::from_ptr(layer.layer_name.as_ptr()).to_bytes() == name.as_bytes()
+            })
+        })
+        .map(|&name| CString::new(name).unwrap())
+}
+
+/// An owned copy of a message from the debug report callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationMessage {
+    pub flags: vk::DebugReportFlagsEXT,
+    pub object_type: vk::DebugReportObjectTypeEXT,
+    pub object: vk::uint64_t,
+    pub location: vk::size_t,
+    pub message_code: vk::int32_t,
+    pub layer_prefix: String,
+    pub message: String,
+}
+
+impl ValidationMessage {
+    pub fn is_error(&self) -> bool {
+        self.flags.intersects(vk::DEBUG_REPORT_ERROR_BIT_EXT)
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.flags.intersects(
+            vk::DEBUG_REPORT_WARNING_BIT_EXT | vk::DEBUG_REPORT_PERFORMANCE_WARNING_BIT_EXT,
+        )
+    }
+}
+
+impl<'a, 'm> From<&'a DebugReportMessage<'m>> for ValidationMessage {
+    fn from(message: &'a DebugReportMessage<'m>) -> ValidationMessage {
+        ValidationMessage {
+            flags: message.flags,
+            object_type: message.object_type,
+            object: message.object,
+            location: message.location,
+            message_code: message.message_code,
+            layer_prefix: message.layer_prefix.clone().into_owned(),
+            message: message.message.clone().into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[{}] code {}, object 0x{:x}: {}",
+            self.layer_prefix,
+            self.message_code,
+            self.object,
+            self.message
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum ValidationError {
+    ExtensionError(ExtensionError),
+    VkError(vk::Result),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ValidationError::{:?}", self)
+    }
+}
+
+impl Error for ValidationError {
+    fn description(&self) -> &str {
+        "ValidationError"
+    }
+}
+
+/// Stores every message of the debug report callbacks that it is chained into or registered
+/// with.
+pub struct ValidationCollector {
+    messages: Arc<Mutex<Vec<ValidationMessage>>>,
+    // Boxed, so that the create info from `instance_create_info` can point to it.
+    closure: Box<DebugReportClosure>,
+    flags: vk::DebugReportFlagsEXT,
+    validation_layer_found: bool,
+}
+
+impl ValidationCollector {
+    /// Collects messages of every severity.
+    pub fn new<E: EntryV1_0>(entry: &E) -> ValidationCollector {
+        ValidationCollector::with_flags(entry, vk::DebugReportFlagsEXT::all())
+    }
+
+    pub fn with_flags<E: EntryV1_0>(
+        entry: &E,
+        flags: vk::DebugReportFlagsEXT,
+    ) -> ValidationCollector {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        ValidationCollector {
+            closure: Box::new(collect(messages.clone())),
+            messages: messages,
+            flags: flags,
+            validation_layer_found: find_validation_layer(entry).is_some(),
+        }
+    }
+
+    /// A callback create info to chain into `InstanceCreateInfo::p_next`, so that the messages
+    /// of `create_instance` and `destroy_instance` are collected as well. The instance needs
+    /// `VK_EXT_debug_report` enabled.
+    ///
+    /// The create info points into the collector, which therefore has to outlive the instance.
+    pub unsafe fn instance_create_info(&self) -> vk::DebugReportCallbackCreateInfoEXT {
+        DebugReport::closure_create_info(self.flags, &self.closure)
+    }
+
+    /// Collects the messages about the objects of `instance` until the returned callback is
+    /// dropped. The callback borrows `instance`, and it has to be dropped before the instance is
+    /// destroyed.
+    pub fn register<'i, V: FunctionPointers>(
+        &self,
+        instance: &'i Instance<V>,
+    ) -> Result<ValidationCallback<'i>, ValidationError> {
+        let debug_report = instance
+            .load::<DebugReport>()
+            .map_err(|err| ValidationError::ExtensionError(err))?;
+        let collect = collect(self.messages.clone());
+        let callback =
+            unsafe { debug_report.create_debug_report_callback(self.flags, collect, None) }
+                .map_err(|err| ValidationError::VkError(err))?;
+        Ok(ValidationCallback {
+            debug_report: debug_report,
+            callback: Some(callback),
+            _instance: PhantomData,
+        })
+    }
+
+    /// Whether `enumerate_instance_layer_properties` lists one of `VALIDATION_LAYER_NAMES`.
+    /// Without it, an empty collector doesn't mean that the code is valid.
+    pub fn validation_layer_found(&self) -> bool {
+        self.validation_layer_found
+    }
+
+    /// A copy of all messages received so far.
+    pub fn messages(&self) -> Vec<ValidationMessage> {
+        self.filter(|_| true)
+    }
+
+    pub fn errors(&self) -> Vec<ValidationMessage> {
+        self.filter(|message| message.is_error())
+    }
+
+    pub fn warnings(&self) -> Vec<ValidationMessage> {
+        self.filter(|message| message.is_warning())
+    }
+
+    pub fn with_message_code(&self, message_code: vk::int32_t) -> Vec<ValidationMessage> {
+        self.filter(|message| message.message_code == message_code)
+    }
+
+    pub fn for_object<H: vk::Handle>(&self, object: H) -> Vec<ValidationMessage> {
+        let raw = object.as_raw();
+        self.filter(|message| message.object == raw)
+    }
+
+    pub fn filter<F>(&self, mut predicate: F) -> Vec<ValidationMessage>
+    where
+        F: FnMut(&ValidationMessage) -> bool,
+    {
+        self.lock()
+            .iter()
+            .filter(|message| predicate(message))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes and returns all messages received so far.
+    pub fn take(&self) -> Vec<ValidationMessage> {
+        ::std::mem::replace(&mut *self.lock(), Vec::new())
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Panics with every error message if there are any.
+    pub fn assert_no_errors(&self) {
+        let errors = self.errors();
+        if !errors.is_empty() {
+            let report: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+            panic!(
+                "{} validation error(s):\n{}",
+                errors.len(),
+                report.join("\n")
+            );
+        }
+    }
+
+    /// Panics if any message has `message_code`.
+    pub fn assert_no_message_code(&self, message_code: vk::int32_t) {
+        let messages = self.with_message_code(message_code);
+        if !messages.is_empty() {
+            let report: Vec<String> = messages.iter().map(|message| message.to_string()).collect();
+            panic!(
+                "{} validation message(s) with code {}:\n{}",
+                messages.len(),
+                message_code,
+                report.join("\n")
+            );
+        }
+    }
+
+    fn lock<'a>(&'a self) -> MutexGuard<'a, Vec<ValidationMessage>> {
+        lock(&self.messages)
+    }
+}
+
+fn lock<'a>(messages: &'a Mutex<Vec<ValidationMessage>>) -> MutexGuard<'a, Vec<ValidationMessage>> {
+    // A panicking test can poison the lock, the messages are still worth reporting.
+    match messages.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// The closure that the callbacks of a collector call.
+fn collect(messages: Arc<Mutex<Vec<ValidationMessage>>>) -> DebugReportClosure {
+    Box::new(move |message| lock(&messages).push(message.into()))
+}
+
+/// A debug report callback registered by `ValidationCollector::register`, which is destroyed
+/// when this is dropped.
+pub struct ValidationCallback<'i> {
+    debug_report: DebugReport,
+    // Only `None` while it is destroyed.
+    callback: Option<DebugReportCallback>,
+    _instance: PhantomData<&'i ()>,
+}
+
+impl<'i> Drop for ValidationCallback<'i> {
+    fn drop(&mut self) {
+        if let Some(callback) = self.callback.take() {
+            unsafe {
+                self.debug_report
+                    .destroy_debug_report_callback(callback, None);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::panic;
+
+    fn collector() -> ValidationCollector {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        ValidationCollector {
+            closure: Box::new(collect(messages.clone())),
+            messages: messages,
+            flags: vk::DebugReportFlagsEXT::all(),
+            validation_layer_found: false,
+        }
+    }
+
+    // Calls the callback of the create info the way the loader does.
+    fn report(
+        create_info: &vk::DebugReportCallbackCreateInfoEXT,
+        flags: vk::DebugReportFlagsEXT,
+        message_code: vk::int32_t,
+        message: &[u8],
+    ) -> vk::Bool32 {
+        let layer_prefix = CStr::from_bytes_with_nul(b"Validation\0").unwrap();
+        let message = CStr::from_bytes_with_nul(message).unwrap();
+        unsafe {
+            (create_info.pfn_callback)(
+                flags,
+                vk::DebugReportObjectTypeEXT::Buffer,
+                0x42,
+                0,
+                message_code,
+                layer_prefix.as_ptr(),
+                message.as_ptr(),
+                create_info.p_user_data,
+            )
+        }
+    }
+
+    #[test]
+    fn instance_create_info() {
+        let collector = collector();
+        let create_info = unsafe { collector.instance_create_info() };
+        let result = report(
+            &create_info,
+            vk::DEBUG_REPORT_ERROR_BIT_EXT,
+            7,
+            b"Invalid\0",
+        );
+        assert_eq!(result, vk::VK_FALSE);
+        report(&create_info, vk::DEBUG_REPORT_WARNING_BIT_EXT, 8, b"Slow\0");
+        assert_eq!(
+            collector.errors(),
+            vec![ValidationMessage {
+                flags: vk::DEBUG_REPORT_ERROR_BIT_EXT,
+                object_type: vk::DebugReportObjectTypeEXT::Buffer,
+                object: 0x42,
+                location: 0,
+                message_code: 7,
+                layer_prefix: "Validation".to_string(),
+                message: "Invalid".to_string(),
+            }]
+        );
+        assert_eq!(collector.warnings().len(), 1);
+        assert_eq!(collector.with_message_code(8)[0].message, "Slow");
+    }
+
+    #[test]
+    fn poisoned_lock() {
+        let collector = collector();
+        let create_info = unsafe { collector.instance_create_info() };
+        let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            let _guard = collector.messages.lock().unwrap();
+            panic!("poisoning the messages");
+        }));
+        assert!(collector.messages.is_poisoned());
+        report(
+            &create_info,
+            vk::DEBUG_REPORT_ERROR_BIT_EXT,
+            1,
+            b"After the panic\0",
+        );
+        assert_eq!(collector.take().len(), 1);
+    }
+}
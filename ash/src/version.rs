@@ -22,6 +22,7 @@ impl FunctionPointers for V1_0 {
 #[derive(Clone)]
 pub struct InstanceFpV1_0 {
     pub instance_fn: vk::InstanceFnV1_0,
+    pub static_fn: vk::StaticFn,
 }
 
 #[allow(non_camel_case_types)]
@@ -52,6 +53,7 @@ pub trait EntryLoader: Sized {
 
 pub trait InstanceLoader: Sized {
     fn fp_v1_0(&self) -> &vk::InstanceFnV1_0;
+    fn static_fn(&self) -> &vk::StaticFn;
     unsafe fn load(
         static_fn: &vk::StaticFn,
         instance: vk::Instance,
@@ -59,6 +61,7 @@ pub trait InstanceLoader: Sized {
 }
 
 pub trait DeviceLoader: Sized {
+    fn instance_fn(&self) -> &vk::InstanceFnV1_0;
     unsafe fn load(
         instance_fn: &vk::InstanceFnV1_0,
         device: vk::Device,
@@ -66,6 +69,9 @@ pub trait DeviceLoader: Sized {
 }
 
 impl DeviceLoader for DeviceFpV1_0 {
+    fn instance_fn(&self) -> &vk::InstanceFnV1_0 {
+        &self.instance_fn
+    }
     unsafe fn load(
         instance_fn: &vk::InstanceFnV1_0,
         device: vk::Device,
@@ -73,7 +79,10 @@ impl DeviceLoader for DeviceFpV1_0 {
         let device_fn = vk::DeviceFnV1_0::load(|name| {
             mem::transmute(instance_fn.get_device_proc_addr(device, name.as_ptr()))
         })?;
-        Ok(DeviceFpV1_0 { device_fn: device_fn })
+        Ok(DeviceFpV1_0 {
+            device_fn: device_fn,
+            instance_fn: instance_fn.clone(),
+        })
     }
 }
 
@@ -81,6 +90,9 @@ impl InstanceLoader for InstanceFpV1_0 {
     fn fp_v1_0(&self) -> &vk::InstanceFnV1_0 {
         &self.instance_fn
     }
+    fn static_fn(&self) -> &vk::StaticFn {
+        &self.static_fn
+    }
     unsafe fn load(
         static_fn: &vk::StaticFn,
         instance: vk::Instance,
@@ -88,7 +100,10 @@ impl InstanceLoader for InstanceFpV1_0 {
         let instance_fn = vk::InstanceFnV1_0::load(|name| {
             mem::transmute(static_fn.get_instance_proc_addr(instance, name.as_ptr()))
         })?;
-        Ok(InstanceFpV1_0 { instance_fn: instance_fn })
+        Ok(InstanceFpV1_0 {
+            instance_fn: instance_fn,
+            static_fn: static_fn.clone(),
+        })
     }
 }
 
@@ -96,4 +111,5 @@ impl InstanceLoader for InstanceFpV1_0 {
 #[derive(Clone)]
 pub struct DeviceFpV1_0 {
     pub device_fn: vk::DeviceFnV1_0,
+    pub instance_fn: vk::InstanceFnV1_0,
 }
@@ -0,0 +1,92 @@
+//! Vertex input descriptions derived from the vertex struct.
+//!
+//! `#[derive(Vertex)]` from the `ash-derive` crate implements `Vertex` with one attribute per
+//! field, at the offset of the field and with the format of its type. Attributes get consecutive
+//! locations starting at 0.
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate ash_derive;
+//!
+//! #[derive(Clone, Copy, Vertex)]
+//! struct Vertex {
+//!     pos: [f32; 3],
+//!     #[vertex(format = "R8g8b8a8Unorm")]
+//!     color: [u8; 4],
+//!     #[vertex(location = 4)]
+//!     uv: [f32; 2],
+//! }
+//!
+//! let bindings = [Vertex::binding_description(0, vk::VertexInputRate::Vertex)];
+//! let attributes = Vertex::attribute_descriptions(0);
+//! ```
+//!
+//! `#[vertex(format = "...")]` takes the name of a `vk::Format` variant and replaces the inferred
+//! format, which is always `*Sfloat`, `*Sint` or `*Uint`. `#[vertex(location = N)]` sets the
+//! location of a field, and the fields after it continue from there.
+use vk;
+
+pub trait Vertex {
+    fn binding_description(
+        binding: vk::uint32_t,
+        input_rate: vk::VertexInputRate,
+    ) -> vk::VertexInputBindingDescription;
+
+    fn attribute_descriptions(binding: vk::uint32_t) -> Vec<vk::VertexInputAttributeDescription>;
+}
+
+/// The format of a vertex attribute of this type.
+pub trait VertexFormat {
+    const FORMAT: vk::Format;
+}
+
+/// The number of locations that an attribute of `format` uses. 64 bit formats with three or
+/// four components use two.
+pub fn location_count(format: vk::Format) -> vk::uint32_t {
+    match format {
+        vk::Format::R64g64b64Uint
+        | vk::Format::R64g64b64Sint
+        | vk::Format::R64g64b64Sfloat
+        | vk::Format::R64g64b64a64Uint
+        | vk::Format::R64g64b64a64Sint
+        | vk::Format::R64g64b64a64Sfloat => 2,
+        _ => 1,
+    }
+}
+
+macro_rules! vertex_format {
+    ($($ty: ty => $one: ident, $two: ident, $three: ident, $four: ident;)*) => {
+        $(
+            impl VertexFormat for $ty {
+                const FORMAT: vk::Format = vk::Format::$one;
+            }
+
+            impl VertexFormat for [$ty; 1] {
+                const FORMAT: vk::Format = vk::Format::$one;
+            }
+
+            impl VertexFormat for [$ty; 2] {
+                const FORMAT: vk::Format = vk::Format::$two;
+            }
+
+            impl VertexFormat for [$ty; 3] {
+                const FORMAT: vk::Format = vk::Format::$three;
+            }
+
+            impl VertexFormat for [$ty; 4] {
+                const FORMAT: vk::Format = vk::Format::$four;
+            }
+        )*
+    }
+}
+
+vertex_format! {
+    f32 => R32Sfloat, R32g32Sfloat, R32g32b32Sfloat, R32g32b32a32Sfloat;
+    f64 => R64Sfloat, R64g64Sfloat, R64g64b64Sfloat, R64g64b64a64Sfloat;
+    i32 => R32Sint, R32g32Sint, R32g32b32Sint, R32g32b32a32Sint;
+    u32 => R32Uint, R32g32Uint, R32g32b32Uint, R32g32b32a32Uint;
+    i16 => R16Sint, R16g16Sint, R16g16b16Sint, R16g16b16a16Sint;
+    u16 => R16Uint, R16g16Uint, R16g16b16Uint, R16g16b16a16Uint;
+    i8 => R8Sint, R8g8Sint, R8g8b8Sint, R8g8b8a8Sint;
+    u8 => R8Uint, R8g8Uint, R8g8b8Uint, R8g8b8a8Uint;
+}
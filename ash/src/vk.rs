@@ -11,7 +11,7 @@ pub fn unloaded_function_panic() -> ! {
 }
 
 macro_rules! handle_nondispatchable {
-    ($name: ident) => {
+    ($name: ident, $object_type: ident) => {
         #[repr(C)]
         #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
         pub struct $name (uint64_t);
@@ -21,6 +21,13 @@ macro_rules! handle_nondispatchable {
                 $name(0)
             }
         }
+
+        impl Handle for $name {
+            const TYPE: ObjectType = ObjectType::$object_type;
+            fn as_raw(self) -> uint64_t {
+                self.0
+            }
+        }
         impl fmt::Pointer for $name {
             fn fmt(&self, f: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {
                 write!(f, "0x{:x}", self.0)
@@ -179,24 +186,221 @@ macro_rules! vk_bitflags_wrapped {
     }
 }
 
+/// Implements `PartialEq`, `Eq` and `Hash` field by field. The `floats` are compared and hashed
+/// by their bit pattern, which keeps `Eq` reflexive and allows structs with `c_float` members
+/// to be used as keys in caches.
+macro_rules! vk_bitwise_eq_hash {
+    ($name: ident, [$($field: ident),*], floats [$($float: ident),*]) => {
+        impl PartialEq for $name {
+            fn eq(&self, other: &$name) -> bool {
+                $(self.$field == other.$field &&)*
+                $(self.$float.float_bits() == other.$float.float_bits() &&)*
+                true
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$field.hash(state);)*
+                $(self.$float.float_bits().hash(state);)*
+            }
+        }
+    }
+}
+
 #[macro_export]
+#[deprecated(since = "0.21.0", note = "use `vk::Version::new(major, minor, patch).raw()`")]
 macro_rules! vk_make_version {
-    ($major: expr, $minor: expr, $patch: expr) => ((($major as u32) << 22) | (($minor as u32) << 12) | $patch as u32)
+    ($major: expr, $minor: expr, $patch: expr) => {
+        $crate::vk::Version::new($major as u32, $minor as u32, $patch as u32).raw()
+    };
 }
 
 #[macro_export]
+#[deprecated(since = "0.21.0", note = "use `vk::Version::new(major, 0, 0).raw()`")]
 macro_rules! vk_version_major {
-    ($major: expr) => (($major as uint32_t) << 22)
+    ($major: expr) => {
+        $crate::vk::Version::new($major as u32, 0, 0).raw()
+    };
 }
 
 #[macro_export]
+#[deprecated(since = "0.21.0", note = "use `vk::Version::new(0, minor, 0).raw()`")]
 macro_rules! vk_version_minor {
-    ($minor: expr) => ((($minor as uint32_t) << 12) & 0x3ff)
+    ($minor: expr) => {
+        $crate::vk::Version::new(0, $minor as u32, 0).raw()
+    };
 }
 
 #[macro_export]
+#[deprecated(since = "0.21.0", note = "use `vk::Version::new(0, 0, patch).raw()`")]
 macro_rules! vk_version_patch {
-    ($minor: expr) => (($minor as uint32_t) & 0xfff)
+    ($patch: expr) => {
+        $crate::vk::Version::new(0, 0, $patch as u32).raw()
+    };
+}
+
+/// A Vulkan version number, packed the same way as `ApplicationInfo::api_version` and
+/// `PhysicalDeviceProperties::api_version`.
+///
+/// ```rust,ignore
+/// let version = vk::Version::from(properties.api_version);
+/// if version >= vk::Version::new(1, 0, 61) {
+///     println!("Vulkan {}", version);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(u32);
+
+impl Version {
+    /// `major` is truncated to 10 bits, `minor` to 10 bits and `patch` to 12 bits.
+    pub fn new(major: u32, minor: u32, patch: u32) -> Version {
+        Version(((major & 0x3ff) << 22) | ((minor & 0x3ff) << 12) | (patch & 0xfff))
+    }
+
+    pub fn from_raw(raw: u32) -> Version {
+        Version(raw)
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.0
+    }
+
+    pub fn major(&self) -> u32 {
+        self.0 >> 22
+    }
+
+    pub fn minor(&self) -> u32 {
+        (self.0 >> 12) & 0x3ff
+    }
+
+    pub fn patch(&self) -> u32 {
+        self.0 & 0xfff
+    }
+}
+
+impl From<u32> for Version {
+    fn from(raw: u32) -> Version {
+        Version(raw)
+    }
+}
+
+impl From<Version> for u32 {
+    fn from(version: Version) -> u32 {
+        version.0
+    }
+}
+
+impl ::std::fmt::Display for Version {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major(), self.minor(), self.patch())
+    }
+}
+
+/// Parses `"major.minor.patch"` or `"major.minor"`, in which case the patch is 0.
+impl ::std::str::FromStr for Version {
+    type Err = ParseVersionError;
+    fn from_str(s: &str) -> ::std::result::Result<Version, ParseVersionError> {
+        let mut numbers = [0u32; 3];
+        let mut count = 0;
+        for (index, part) in s.trim().split('.').enumerate() {
+            if index >= numbers.len() {
+                return Err(ParseVersionError::TooManyParts);
+            }
+            numbers[index] = part.parse().map_err(|_| ParseVersionError::InvalidNumber)?;
+            count += 1;
+        }
+        if count < 2 {
+            return Err(ParseVersionError::TooFewParts);
+        }
+        if numbers[0] > 0x3ff || numbers[1] > 0x3ff || numbers[2] > 0xfff {
+            return Err(ParseVersionError::OutOfRange);
+        }
+        Ok(Version::new(numbers[0], numbers[1], numbers[2]))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseVersionError {
+    TooFewParts,
+    TooManyParts,
+    InvalidNumber,
+    /// The major or minor number is larger than 1023, or the patch number larger than 4095.
+    OutOfRange,
+}
+
+impl ParseVersionError {
+    fn message(&self) -> &'static str {
+        match *self {
+            ParseVersionError::TooFewParts => "version needs at least a major and a minor number",
+            ParseVersionError::TooManyParts => "version has more than three numbers",
+            ParseVersionError::InvalidNumber => "version contains an invalid number",
+            ParseVersionError::OutOfRange => "version number doesn't fit in its packed field",
+        }
+    }
+}
+
+impl ::std::fmt::Display for ParseVersionError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl ::std::error::Error for ParseVersionError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
+pub const VENDOR_ID_NVIDIA: u32 = 0x10de;
+
+/// `PhysicalDeviceProperties::driver_version` decoded for the vendor that packed it.
+///
+/// The spec leaves the encoding of the driver version to the vendor. Most follow the
+/// `api_version` layout, NVIDIA packs four numbers into 10, 8, 8 and 6 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DriverVersion {
+    Standard(Version),
+    Nvidia {
+        major: u32,
+        minor: u32,
+        secondary: u32,
+        tertiary: u32,
+    },
+}
+
+impl DriverVersion {
+    pub fn new(vendor_id: u32, driver_version: u32) -> DriverVersion {
+        match vendor_id {
+            VENDOR_ID_NVIDIA => DriverVersion::Nvidia {
+                major: driver_version >> 22,
+                minor: (driver_version >> 14) & 0xff,
+                secondary: (driver_version >> 6) & 0xff,
+                tertiary: driver_version & 0x3f,
+            },
+            _ => DriverVersion::Standard(Version(driver_version)),
+        }
+    }
+
+    pub fn from_properties(properties: &PhysicalDeviceProperties) -> DriverVersion {
+        DriverVersion::new(properties.vendor_id, properties.driver_version)
+    }
+}
+
+impl ::std::fmt::Display for DriverVersion {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            DriverVersion::Standard(version) => version.fmt(f),
+            DriverVersion::Nvidia {
+                major,
+                minor,
+                secondary,
+                tertiary,
+            } => write!(f, "{}.{}.{}.{}", major, minor, secondary, tertiary),
+        }
+    }
 }
 
 pub mod types {
@@ -204,6 +408,7 @@ pub mod types {
     use std::ops::*;
     use std::fmt;
     use std::ffi::CStr;
+    use std::hash::{Hash, Hasher};
     use super::*;
     use libc;
     pub type c_void = libc::c_void;
@@ -216,10 +421,10 @@ pub mod types {
     pub type int32_t = libc::int32_t;
     pub type Display = *const c_void;
     pub type Window = libc::c_ulong;
-    pub type VisualID = *const c_void;
+    pub type VisualID = libc::c_ulong;
     pub type xcb_connection_t = *const c_void;
     pub type xcb_window_t = u32;
-    pub type xcb_visualid_t = *const c_void;
+    pub type xcb_visualid_t = u32;
     pub type MirConnection = *const c_void;
     pub type MirSurface = *const c_void;
     pub type HINSTANCE = *const c_void;
@@ -272,6 +477,8 @@ pub mod types {
     vk_bitflags_wrapped!(DisplaySurfaceCreateFlagsKHR, 0b0, Flags);
     vk_bitflags_wrapped!(IOSSurfaceCreateFlagsMVK, 0b0, Flags);
     vk_bitflags_wrapped!(MacOSSurfaceCreateFlagsMVK, 0b0, Flags);
+    vk_bitflags_wrapped!(DebugUtilsMessengerCreateFlagsEXT, 0b0, Flags);
+    vk_bitflags_wrapped!(DebugUtilsMessengerCallbackDataFlagsEXT, 0b0, Flags);
 
     pub const VK_MAX_PHYSICAL_DEVICE_NAME_SIZE: size_t = 256;
     pub const VK_UUID_SIZE: size_t = 16;
@@ -314,6 +521,37 @@ pub mod types {
     pub const VK_MVK_IOS_SURFACE_EXTENSION_NAME: &'static str = "VK_MVK_ios_surface";
     pub const VK_MVK_MACOS_SURFACE_SPEC_VERSION: uint32_t = 2;
     pub const VK_MVK_MACOS_SURFACE_EXTENSION_NAME: &'static str = "VK_MVK_macos_surface";
+    pub const VK_EXT_DEBUG_UTILS_SPEC_VERSION: uint32_t = 1;
+    pub const VK_EXT_DEBUG_UTILS_EXTENSION_NAME: &'static str = "VK_EXT_debug_utils";
+
+    /// The bit pattern of a float, or an array of floats, used by `vk_bitwise_eq_hash!`.
+    trait FloatBits {
+        type Bits: Eq + Hash;
+        fn float_bits(&self) -> Self::Bits;
+    }
+
+    impl FloatBits for c_float {
+        type Bits = uint32_t;
+        fn float_bits(&self) -> uint32_t {
+            self.to_bits()
+        }
+    }
+
+    impl FloatBits for [c_float; 2] {
+        type Bits = [uint32_t; 2];
+        fn float_bits(&self) -> [uint32_t; 2] {
+            [self[0].to_bits(), self[1].to_bits()]
+        }
+    }
+
+    /// Fixed size string arrays are only defined up to the null terminator, everything behind
+    /// it is ignored for comparisons.
+    fn until_nul(chars: &[c_char]) -> &[c_char] {
+        match chars.iter().position(|&c| c == 0) {
+            Some(len) => &chars[..len],
+            None => chars,
+        }
+    }
 
     #[derive(Debug, Clone)]
     #[repr(C)]
@@ -379,7 +617,7 @@ pub mod types {
         }
     }
 
-    #[derive(Default, Debug, Clone)]
+    #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct PhysicalDeviceFeatures {
         pub robust_buffer_access: Bool32,
@@ -439,7 +677,122 @@ pub mod types {
         pub inherited_queries: Bool32,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    macro_rules! physical_device_features_impl {
+        ($($field: ident),*) => {
+            impl PhysicalDeviceFeatures {
+                /// The names of all fields, in declaration order.
+                pub const FIELD_NAMES: &'static [&'static str] = &[$(stringify!($field)),*];
+
+                /// The features that are set in both.
+                pub fn intersection(
+                    &self,
+                    other: &PhysicalDeviceFeatures,
+                ) -> PhysicalDeviceFeatures {
+                    PhysicalDeviceFeatures {
+                        $($field: (self.$field != 0 && other.$field != 0) as Bool32),*
+                    }
+                }
+
+                /// The features that are set in either.
+                pub fn union(&self, other: &PhysicalDeviceFeatures) -> PhysicalDeviceFeatures {
+                    PhysicalDeviceFeatures {
+                        $($field: (self.$field != 0 || other.$field != 0) as Bool32),*
+                    }
+                }
+
+                /// Whether every feature that is set in `self` is also set in `other`.
+                pub fn is_subset_of(&self, other: &PhysicalDeviceFeatures) -> bool {
+                    $(
+                        if self.$field != 0 && other.$field == 0 {
+                            return false;
+                        }
+                    )*
+                    true
+                }
+
+                /// The names of the features that are set in `self` but not in `other`.
+                ///
+                /// ```rust,ignore
+                /// let unsupported = wanted.missing_from(&supported);
+                /// ```
+                pub fn missing_from(&self, other: &PhysicalDeviceFeatures) -> Vec<&'static str> {
+                    let mut missing = Vec::new();
+                    $(
+                        if self.$field != 0 && other.$field == 0 {
+                            missing.push(stringify!($field));
+                        }
+                    )*
+                    missing
+                }
+
+                /// Every field name together with whether the feature is set, in declaration
+                /// order.
+                pub fn iter(&self) -> ::std::vec::IntoIter<(&'static str, bool)> {
+                    vec![$((stringify!($field), self.$field != 0)),*].into_iter()
+                }
+            }
+        }
+    }
+
+    physical_device_features_impl!(
+        robust_buffer_access,
+        full_draw_index_uint32,
+        image_cube_array,
+        independent_blend,
+        geometry_shader,
+        tessellation_shader,
+        sample_rate_shading,
+        dual_src_blend,
+        logic_op,
+        multi_draw_indirect,
+        draw_indirect_first_instance,
+        depth_clamp,
+        depth_bias_clamp,
+        fill_mode_non_solid,
+        depth_bounds,
+        wide_lines,
+        large_points,
+        alpha_to_one,
+        multi_viewport,
+        sampler_anisotropy,
+        texture_compression_etc2,
+        texture_compression_astc_ldr,
+        texture_compression_bc,
+        occlusion_query_precise,
+        pipeline_statistics_query,
+        vertex_pipeline_stores_and_atomics,
+        fragment_stores_and_atomics,
+        shader_tessellation_and_geometry_point_size,
+        shader_image_gather_extended,
+        shader_storage_image_extended_formats,
+        shader_storage_image_multisample,
+        shader_storage_image_read_without_format,
+        shader_storage_image_write_without_format,
+        shader_uniform_buffer_array_dynamic_indexing,
+        shader_sampled_image_array_dynamic_indexing,
+        shader_storage_buffer_array_dynamic_indexing,
+        shader_storage_image_array_dynamic_indexing,
+        shader_clip_distance,
+        shader_cull_distance,
+        shader_float64,
+        shader_int64,
+        shader_int16,
+        shader_resource_residency,
+        shader_resource_min_lod,
+        sparse_binding,
+        sparse_residency_buffer,
+        sparse_residency_image2d,
+        sparse_residency_image3d,
+        sparse_residency2samples,
+        sparse_residency4samples,
+        sparse_residency8samples,
+        sparse_residency16samples,
+        sparse_residency_aliased,
+        variable_multisample_rate,
+        inherited_queries
+    );
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct FormatProperties {
         pub linear_tiling_features: FormatFeatureFlags,
@@ -447,7 +800,7 @@ pub mod types {
         pub buffer_features: FormatFeatureFlags,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageFormatProperties {
         pub max_extent: Extent3D,
@@ -529,6 +882,35 @@ pub mod types {
         }
     }
 
+    impl PartialEq for PhysicalDeviceProperties {
+        fn eq(&self, other: &PhysicalDeviceProperties) -> bool {
+            self.api_version == other.api_version && self.driver_version == other.driver_version &&
+                self.vendor_id == other.vendor_id &&
+                self.device_id == other.device_id &&
+                self.device_type == other.device_type &&
+                until_nul(&self.device_name) == until_nul(&other.device_name) &&
+                self.pipeline_cache_uuid == other.pipeline_cache_uuid &&
+                self.limits == other.limits &&
+                self.sparse_properties == other.sparse_properties
+        }
+    }
+
+    impl Eq for PhysicalDeviceProperties {}
+
+    impl Hash for PhysicalDeviceProperties {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.api_version.hash(state);
+            self.driver_version.hash(state);
+            self.vendor_id.hash(state);
+            self.device_id.hash(state);
+            self.device_type.hash(state);
+            until_nul(&self.device_name).hash(state);
+            self.pipeline_cache_uuid.hash(state);
+            self.limits.hash(state);
+            self.sparse_properties.hash(state);
+        }
+    }
+
     #[repr(C)]
     pub struct PhysicalDeviceLimits {
         pub max_image_dimension1d: uint32_t,
@@ -1122,7 +1504,63 @@ pub mod types {
         }
     }
 
-    #[derive(Debug, Clone, Hash)]
+    vk_bitwise_eq_hash!(
+        PhysicalDeviceLimits,
+        [
+            max_image_dimension1d, max_image_dimension2d, max_image_dimension3d,
+            max_image_dimension_cube, max_image_array_layers, max_texel_buffer_elements,
+            max_uniform_buffer_range, max_storage_buffer_range, max_push_constants_size,
+            max_memory_allocation_count, max_sampler_allocation_count, buffer_image_granularity,
+            sparse_address_space_size, max_bound_descriptor_sets,
+            max_per_stage_descriptor_samplers, max_per_stage_descriptor_uniform_buffers,
+            max_per_stage_descriptor_storage_buffers, max_per_stage_descriptor_sampled_images,
+            max_per_stage_descriptor_storage_images, max_per_stage_descriptor_input_attachments,
+            max_per_stage_resources, max_descriptor_set_samplers,
+            max_descriptor_set_uniform_buffers, max_descriptor_set_uniform_buffers_dynamic,
+            max_descriptor_set_storage_buffers, max_descriptor_set_storage_buffers_dynamic,
+            max_descriptor_set_sampled_images, max_descriptor_set_storage_images,
+            max_descriptor_set_input_attachments, max_vertex_input_attributes,
+            max_vertex_input_bindings, max_vertex_input_attribute_offset,
+            max_vertex_input_binding_stride, max_vertex_output_components,
+            max_tessellation_generation_level, max_tessellation_patch_size,
+            max_tessellation_control_per_vertex_input_components,
+            max_tessellation_control_per_vertex_output_components,
+            max_tessellation_control_per_patch_output_components,
+            max_tessellation_control_total_output_components,
+            max_tessellation_evaluation_input_components,
+            max_tessellation_evaluation_output_components, max_geometry_shader_invocations,
+            max_geometry_input_components, max_geometry_output_components,
+            max_geometry_output_vertices, max_geometry_total_output_components,
+            max_fragment_input_components, max_fragment_output_attachments,
+            max_fragment_dual_src_attachments, max_fragment_combined_output_resources,
+            max_compute_shared_memory_size, max_compute_work_group_count,
+            max_compute_work_group_invocations, max_compute_work_group_size,
+            sub_pixel_precision_bits, sub_texel_precision_bits, mipmap_precision_bits,
+            max_draw_indexed_index_value, max_draw_indirect_count, max_viewports,
+            max_viewport_dimensions, viewport_sub_pixel_bits, min_memory_map_alignment,
+            min_texel_buffer_offset_alignment, min_uniform_buffer_offset_alignment,
+            min_storage_buffer_offset_alignment, min_texel_offset, max_texel_offset,
+            min_texel_gather_offset, max_texel_gather_offset,
+            sub_pixel_interpolation_offset_bits, max_framebuffer_width, max_framebuffer_height,
+            max_framebuffer_layers, framebuffer_color_sample_counts,
+            framebuffer_depth_sample_counts, framebuffer_stencil_sample_counts,
+            framebuffer_no_attachments_sample_counts, max_color_attachments,
+            sampled_image_color_sample_counts, sampled_image_integer_sample_counts,
+            sampled_image_depth_sample_counts, sampled_image_stencil_sample_counts,
+            storage_image_sample_counts, max_sample_mask_words, timestamp_compute_and_graphics,
+            max_clip_distances, max_cull_distances, max_combined_clip_and_cull_distances,
+            discrete_queue_priorities, strict_lines, standard_sample_locations,
+            optimal_buffer_copy_offset_alignment, optimal_buffer_copy_row_pitch_alignment,
+            non_coherent_atom_size
+        ],
+        floats [
+            max_sampler_lod_bias, max_sampler_anisotropy, viewport_bounds_range,
+            min_interpolation_offset, max_interpolation_offset, timestamp_period,
+            point_size_range, line_width_range, point_size_granularity, line_width_granularity
+        ]
+    );
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct PhysicalDeviceSparseProperties {
         pub residency_standard2dblock_shape: Bool32,
@@ -1132,7 +1570,7 @@ pub mod types {
         pub residency_non_resident_strict: Bool32,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct QueueFamilyProperties {
         pub queue_flags: QueueFlags,
@@ -1187,14 +1625,41 @@ pub mod types {
         }
     }
 
-    #[derive(Debug, Clone, Hash)]
+    impl PhysicalDeviceMemoryProperties {
+        fn valid_memory_types(&self) -> &[MemoryType] {
+            &self.memory_types[..self.memory_type_count as usize]
+        }
+
+        fn valid_memory_heaps(&self) -> &[MemoryHeap] {
+            &self.memory_heaps[..self.memory_heap_count as usize]
+        }
+    }
+
+    /// Only the first `memory_type_count` types and `memory_heap_count` heaps are compared.
+    impl PartialEq for PhysicalDeviceMemoryProperties {
+        fn eq(&self, other: &PhysicalDeviceMemoryProperties) -> bool {
+            self.valid_memory_types() == other.valid_memory_types() &&
+                self.valid_memory_heaps() == other.valid_memory_heaps()
+        }
+    }
+
+    impl Eq for PhysicalDeviceMemoryProperties {}
+
+    impl Hash for PhysicalDeviceMemoryProperties {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.valid_memory_types().hash(state);
+            self.valid_memory_heaps().hash(state);
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct MemoryType {
         pub property_flags: MemoryPropertyFlags,
         pub heap_index: uint32_t,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct MemoryHeap {
         pub size: DeviceSize,
@@ -1262,6 +1727,22 @@ pub mod types {
         }
     }
 
+    impl PartialEq for ExtensionProperties {
+        fn eq(&self, other: &ExtensionProperties) -> bool {
+            until_nul(&self.extension_name) == until_nul(&other.extension_name) &&
+                self.spec_version == other.spec_version
+        }
+    }
+
+    impl Eq for ExtensionProperties {}
+
+    impl Hash for ExtensionProperties {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            until_nul(&self.extension_name).hash(state);
+            self.spec_version.hash(state);
+        }
+    }
+
     #[repr(C)]
     pub struct LayerProperties {
         pub layer_name: [c_char; VK_MAX_EXTENSION_NAME_SIZE],
@@ -1314,6 +1795,26 @@ pub mod types {
         }
     }
 
+    impl PartialEq for LayerProperties {
+        fn eq(&self, other: &LayerProperties) -> bool {
+            until_nul(&self.layer_name) == until_nul(&other.layer_name) &&
+                self.spec_version == other.spec_version &&
+                self.implementation_version == other.implementation_version &&
+                until_nul(&self.description) == until_nul(&other.description)
+        }
+    }
+
+    impl Eq for LayerProperties {}
+
+    impl Hash for LayerProperties {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            until_nul(&self.layer_name).hash(state);
+            self.spec_version.hash(state);
+            self.implementation_version.hash(state);
+            until_nul(&self.description).hash(state);
+        }
+    }
+
     #[derive(Debug, Clone)]
     #[repr(C)]
     pub struct SubmitInfo {
@@ -1347,7 +1848,7 @@ pub mod types {
         pub size: DeviceSize,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct MemoryRequirements {
         pub size: DeviceSize,
@@ -1355,7 +1856,7 @@ pub mod types {
         pub memory_type_bits: uint32_t,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SparseImageMemoryRequirements {
         pub format_properties: SparseImageFormatProperties,
@@ -1365,7 +1866,7 @@ pub mod types {
         pub image_mip_tail_stride: DeviceSize,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SparseImageFormatProperties {
         pub aspect_mask: ImageAspectFlags,
@@ -1398,7 +1899,7 @@ pub mod types {
         pub p_binds: *const SparseMemoryBind,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SparseMemoryBind {
         pub resource_offset: DeviceSize,
@@ -1424,7 +1925,7 @@ pub mod types {
         pub p_binds: *const SparseImageMemoryBind,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SparseImageMemoryBind {
         pub subresource: ImageSubresource,
@@ -1435,7 +1936,7 @@ pub mod types {
         pub flags: SparseMemoryBindFlags,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageSubresource {
         pub aspect_mask: ImageAspectFlags,
@@ -1443,7 +1944,7 @@ pub mod types {
         pub array_layer: uint32_t,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct Offset3D {
         pub x: int32_t,
@@ -1531,7 +2032,7 @@ pub mod types {
         pub initial_layout: ImageLayout,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SubresourceLayout {
         pub offset: DeviceSize,
@@ -1554,7 +2055,7 @@ pub mod types {
         pub subresource_range: ImageSubresourceRange,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ComponentMapping {
         pub r: ComponentSwizzle,
@@ -1563,7 +2064,7 @@ pub mod types {
         pub a: ComponentSwizzle,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageSubresourceRange {
         pub aspect_mask: ImageAspectFlags,
@@ -1638,7 +2139,7 @@ pub mod types {
         pub p_data: *const c_void,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SpecializationMapEntry {
         pub constant_id: uint32_t,
@@ -1658,7 +2159,7 @@ pub mod types {
         pub p_vertex_attribute_descriptions: *const VertexInputAttributeDescription,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct VertexInputBindingDescription {
         pub binding: uint32_t,
@@ -1666,7 +2167,7 @@ pub mod types {
         pub input_rate: VertexInputRate,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct VertexInputAttributeDescription {
         pub location: uint32_t,
@@ -1717,21 +2218,23 @@ pub mod types {
         pub max_depth: c_float,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    vk_bitwise_eq_hash!(Viewport, [], floats [x, y, width, height, min_depth, max_depth]);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct Rect2D {
         pub offset: Offset2D,
         pub extent: Extent2D,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct Offset2D {
         pub x: int32_t,
         pub y: int32_t,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct Extent2D {
         pub width: uint32_t,
@@ -1787,7 +2290,7 @@ pub mod types {
         pub max_depth_bounds: c_float,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct StencilOpState {
         pub fail_op: StencilOp,
@@ -1849,7 +2352,7 @@ pub mod types {
         }
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct PipelineColorBlendAttachmentState {
         pub blend_enable: Bool32,
@@ -1896,7 +2399,7 @@ pub mod types {
         pub p_push_constant_ranges: *const PushConstantRange,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct PushConstantRange {
         pub stage_flags: ShaderStageFlags,
@@ -1958,7 +2461,7 @@ pub mod types {
         pub p_pool_sizes: *const DescriptorPoolSize,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DescriptorPoolSize {
         pub typ: DescriptorType,
@@ -1990,7 +2493,7 @@ pub mod types {
         pub p_texel_buffer_view: *const BufferView,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DescriptorImageInfo {
         pub sampler: Sampler,
@@ -1998,7 +2501,7 @@ pub mod types {
         pub image_layout: ImageLayout,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DescriptorBufferInfo {
         pub buffer: Buffer,
@@ -2048,7 +2551,7 @@ pub mod types {
         pub p_dependencies: *const SubpassDependency,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct AttachmentDescription {
         pub flags: AttachmentDescriptionFlags,
@@ -2077,14 +2580,14 @@ pub mod types {
         pub p_preserve_attachments: *const uint32_t,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct AttachmentReference {
         pub attachment: uint32_t,
         pub layout: ImageLayout,
     }
 
-    #[derive(Debug, Clone, Hash)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SubpassDependency {
         pub src_subpass: uint32_t,
@@ -2137,7 +2640,7 @@ pub mod types {
         pub pipeline_statistics: QueryPipelineStatisticFlags,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct BufferCopy {
         pub src_offset: DeviceSize,
@@ -2145,7 +2648,7 @@ pub mod types {
         pub size: DeviceSize,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageCopy {
         pub src_subresource: ImageSubresourceLayers,
@@ -2155,7 +2658,7 @@ pub mod types {
         pub extent: Extent3D,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageSubresourceLayers {
         pub aspect_mask: ImageAspectFlags,
@@ -2164,6 +2667,7 @@ pub mod types {
         pub layer_count: uint32_t,
     }
 
+    #[derive(PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageBlit {
         pub src_subresource: ImageSubresourceLayers,
@@ -2210,7 +2714,7 @@ pub mod types {
         }
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct BufferImageCopy {
         pub buffer_offset: DeviceSize,
@@ -2228,7 +2732,9 @@ pub mod types {
         pub stencil: uint32_t,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    vk_bitwise_eq_hash!(ClearDepthStencilValue, [stencil], floats [depth]);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ClearAttachment {
         pub aspect_mask: ImageAspectFlags,
@@ -2236,7 +2742,7 @@ pub mod types {
         pub clear_value: ClearValue,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ClearRect {
         pub rect: Rect2D,
@@ -2244,7 +2750,7 @@ pub mod types {
         pub layer_count: uint32_t,
     }
 
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct ImageResolve {
         pub src_subresource: ImageSubresourceLayers,
@@ -2304,7 +2810,7 @@ pub mod types {
         pub p_clear_values: *const ClearValue,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DispatchIndirectCommand {
         pub x: uint32_t,
@@ -2312,7 +2818,7 @@ pub mod types {
         pub z: uint32_t,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DrawIndexedIndirectCommand {
         pub index_count: uint32_t,
@@ -2322,7 +2828,7 @@ pub mod types {
         pub first_instance: uint32_t,
     }
 
-    #[derive(Debug, Clone, Copy)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DrawIndirectCommand {
         pub vertex_count: uint32_t,
@@ -2331,7 +2837,7 @@ pub mod types {
         pub first_instance: uint32_t,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SurfaceCapabilitiesKHR {
         pub min_image_count: uint32_t,
@@ -2346,7 +2852,7 @@ pub mod types {
         pub supported_usage_flags: ImageUsageFlags,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct SurfaceFormatKHR {
         pub format: Format,
@@ -2470,14 +2976,14 @@ pub mod types {
         pub persistent_content: Bool32,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DisplayModeParametersKHR {
         pub visible_region: Extent2D,
         pub refresh_rate: uint32_t,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DisplayModePropertiesKHR {
         pub display_mode: DisplayModeKHR,
@@ -2493,7 +2999,7 @@ pub mod types {
         pub parameters: DisplayModeParametersKHR,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DisplayPlaneCapabilitiesKHR {
         pub supported_alpha: DisplayPlaneAlphaFlagsKHR,
@@ -2507,7 +3013,7 @@ pub mod types {
         pub max_dst_extent: Extent2D,
     }
 
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     #[repr(C)]
     pub struct DisplayPlanePropertiesKHR {
         pub current_display: DisplayKHR,
@@ -2562,6 +3068,93 @@ pub mod types {
         }
     }
 
+    #[derive(Debug, Clone)]
+    #[repr(C)]
+    pub struct DebugUtilsObjectNameInfoEXT {
+        pub s_type: StructureType,
+        pub p_next: *const c_void,
+        pub object_type: ObjectType,
+        pub object_handle: uint64_t,
+        pub p_object_name: *const c_char,
+    }
+
+    #[derive(Debug, Clone)]
+    #[repr(C)]
+    pub struct DebugUtilsObjectTagInfoEXT {
+        pub s_type: StructureType,
+        pub p_next: *const c_void,
+        pub object_type: ObjectType,
+        pub object_handle: uint64_t,
+        pub tag_name: uint64_t,
+        pub tag_size: size_t,
+        pub p_tag: *const c_void,
+    }
+
+    #[derive(Debug, Clone)]
+    #[repr(C)]
+    pub struct DebugUtilsLabelEXT {
+        pub s_type: StructureType,
+        pub p_next: *const c_void,
+        pub p_label_name: *const c_char,
+        pub color: [c_float; 4],
+    }
+
+    #[derive(Debug, Clone)]
+    #[repr(C)]
+    pub struct DebugUtilsMessengerCallbackDataEXT {
+        pub s_type: StructureType,
+        pub p_next: *const c_void,
+        pub flags: DebugUtilsMessengerCallbackDataFlagsEXT,
+        pub p_message_id_name: *const c_char,
+        pub message_id_number: int32_t,
+        pub p_message: *const c_char,
+        pub queue_label_count: uint32_t,
+        pub p_queue_labels: *const DebugUtilsLabelEXT,
+        pub cmd_buf_label_count: uint32_t,
+        pub p_cmd_buf_labels: *const DebugUtilsLabelEXT,
+        pub object_count: uint32_t,
+        pub p_objects: *const DebugUtilsObjectNameInfoEXT,
+    }
+
+    #[repr(C)]
+    pub struct DebugUtilsMessengerCreateInfoEXT {
+        pub s_type: StructureType,
+        pub p_next: *const c_void,
+        pub flags: DebugUtilsMessengerCreateFlagsEXT,
+        pub message_severity: DebugUtilsMessageSeverityFlagsEXT,
+        pub message_type: DebugUtilsMessageTypeFlagsEXT,
+        pub pfn_user_callback: PFN_vkDebugUtilsMessengerCallbackEXT,
+        pub p_user_data: *mut c_void,
+    }
+
+    impl Clone for DebugUtilsMessengerCreateInfoEXT {
+        fn clone(&self) -> DebugUtilsMessengerCreateInfoEXT {
+            DebugUtilsMessengerCreateInfoEXT {
+                s_type: self.s_type.clone(),
+                p_next: self.p_next.clone(),
+                flags: self.flags.clone(),
+                message_severity: self.message_severity.clone(),
+                message_type: self.message_type.clone(),
+                pfn_user_callback: self.pfn_user_callback,
+                p_user_data: self.p_user_data.clone(),
+            }
+        }
+    }
+
+    impl fmt::Debug for DebugUtilsMessengerCreateInfoEXT {
+        fn fmt(&self, fmt: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {
+            fmt.debug_struct("DebugUtilsMessengerCreateInfoEXT")
+                .field("s_type", &self.s_type)
+                .field("p_next", &self.p_next)
+                .field("flags", &self.flags)
+                .field("message_severity", &self.message_severity)
+                .field("message_type", &self.message_type)
+                .field("pfn_user_callback", &(self.pfn_user_callback as *const ()))
+                .field("p_user_data", &self.p_user_data)
+                .finish()
+        }
+    }
+
     #[derive(Debug, Clone)]
     #[repr(C)]
     pub struct IOSSurfaceCreateInfoMVK {
@@ -2582,7 +3175,7 @@ pub mod types {
 
     /// Temporary Hard-Coded union hack; will be automatically generated when actual unions become stable
     #[repr(C)]
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct ClearColorValue {
         data: [u8; 16],
     }
@@ -2648,7 +3241,7 @@ pub mod types {
 
     /// Temporary Hard-Coded union hack; will be automatically generated when actual unions become stable
     #[repr(C)]
-    #[derive(Debug, Clone, Copy, Hash)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct ClearValue {
         data: [u8; 16],
     }
@@ -2766,6 +3359,11 @@ pub mod types {
         DebugReportCallbackCreateInfoExt = 1000011000,
         IOSSurfaceCreateInfoMvk = 1000122000,
         MacOSSurfaceCreateInfoMvk = 1000123000,
+        DebugUtilsObjectNameInfoExt = 1000128000,
+        DebugUtilsObjectTagInfoExt = 1000128001,
+        DebugUtilsLabelExt = 1000128002,
+        DebugUtilsMessengerCallbackDataExt = 1000128003,
+        DebugUtilsMessengerCreateInfoExt = 1000128004,
     }
 
     #[repr(C)]
@@ -3082,6 +3680,197 @@ pub mod types {
         Astc12x12SrgbBlock = 184,
     }
 
+    impl Format {
+        /// Every format, in the order of their values.
+        pub const ALL: &'static [Format] = &[
+            Format::Undefined,
+            Format::R4g4UnormPack8,
+            Format::R4g4b4a4UnormPack16,
+            Format::B4g4r4a4UnormPack16,
+            Format::R5g6b5UnormPack16,
+            Format::B5g6r5UnormPack16,
+            Format::R5g5b5a1UnormPack16,
+            Format::B5g5r5a1UnormPack16,
+            Format::A1r5g5b5UnormPack16,
+            Format::R8Unorm,
+            Format::R8Snorm,
+            Format::R8Uscaled,
+            Format::R8Sscaled,
+            Format::R8Uint,
+            Format::R8Sint,
+            Format::R8Srgb,
+            Format::R8g8Unorm,
+            Format::R8g8Snorm,
+            Format::R8g8Uscaled,
+            Format::R8g8Sscaled,
+            Format::R8g8Uint,
+            Format::R8g8Sint,
+            Format::R8g8Srgb,
+            Format::R8g8b8Unorm,
+            Format::R8g8b8Snorm,
+            Format::R8g8b8Uscaled,
+            Format::R8g8b8Sscaled,
+            Format::R8g8b8Uint,
+            Format::R8g8b8Sint,
+            Format::R8g8b8Srgb,
+            Format::B8g8r8Unorm,
+            Format::B8g8r8Snorm,
+            Format::B8g8r8Uscaled,
+            Format::B8g8r8Sscaled,
+            Format::B8g8r8Uint,
+            Format::B8g8r8Sint,
+            Format::B8g8r8Srgb,
+            Format::R8g8b8a8Unorm,
+            Format::R8g8b8a8Snorm,
+            Format::R8g8b8a8Uscaled,
+            Format::R8g8b8a8Sscaled,
+            Format::R8g8b8a8Uint,
+            Format::R8g8b8a8Sint,
+            Format::R8g8b8a8Srgb,
+            Format::B8g8r8a8Unorm,
+            Format::B8g8r8a8Snorm,
+            Format::B8g8r8a8Uscaled,
+            Format::B8g8r8a8Sscaled,
+            Format::B8g8r8a8Uint,
+            Format::B8g8r8a8Sint,
+            Format::B8g8r8a8Srgb,
+            Format::A8b8g8r8UnormPack32,
+            Format::A8b8g8r8SnormPack32,
+            Format::A8b8g8r8UscaledPack32,
+            Format::A8b8g8r8SscaledPack32,
+            Format::A8b8g8r8UintPack32,
+            Format::A8b8g8r8SintPack32,
+            Format::A8b8g8r8SrgbPack32,
+            Format::A2r10g10b10UnormPack32,
+            Format::A2r10g10b10SnormPack32,
+            Format::A2r10g10b10UscaledPack32,
+            Format::A2r10g10b10SscaledPack32,
+            Format::A2r10g10b10UintPack32,
+            Format::A2r10g10b10SintPack32,
+            Format::A2b10g10r10UnormPack32,
+            Format::A2b10g10r10SnormPack32,
+            Format::A2b10g10r10UscaledPack32,
+            Format::A2b10g10r10SscaledPack32,
+            Format::A2b10g10r10UintPack32,
+            Format::A2b10g10r10SintPack32,
+            Format::R16Unorm,
+            Format::R16Snorm,
+            Format::R16Uscaled,
+            Format::R16Sscaled,
+            Format::R16Uint,
+            Format::R16Sint,
+            Format::R16Sfloat,
+            Format::R16g16Unorm,
+            Format::R16g16Snorm,
+            Format::R16g16Uscaled,
+            Format::R16g16Sscaled,
+            Format::R16g16Uint,
+            Format::R16g16Sint,
+            Format::R16g16Sfloat,
+            Format::R16g16b16Unorm,
+            Format::R16g16b16Snorm,
+            Format::R16g16b16Uscaled,
+            Format::R16g16b16Sscaled,
+            Format::R16g16b16Uint,
+            Format::R16g16b16Sint,
+            Format::R16g16b16Sfloat,
+            Format::R16g16b16a16Unorm,
+            Format::R16g16b16a16Snorm,
+            Format::R16g16b16a16Uscaled,
+            Format::R16g16b16a16Sscaled,
+            Format::R16g16b16a16Uint,
+            Format::R16g16b16a16Sint,
+            Format::R16g16b16a16Sfloat,
+            Format::R32Uint,
+            Format::R32Sint,
+            Format::R32Sfloat,
+            Format::R32g32Uint,
+            Format::R32g32Sint,
+            Format::R32g32Sfloat,
+            Format::R32g32b32Uint,
+            Format::R32g32b32Sint,
+            Format::R32g32b32Sfloat,
+            Format::R32g32b32a32Uint,
+            Format::R32g32b32a32Sint,
+            Format::R32g32b32a32Sfloat,
+            Format::R64Uint,
+            Format::R64Sint,
+            Format::R64Sfloat,
+            Format::R64g64Uint,
+            Format::R64g64Sint,
+            Format::R64g64Sfloat,
+            Format::R64g64b64Uint,
+            Format::R64g64b64Sint,
+            Format::R64g64b64Sfloat,
+            Format::R64g64b64a64Uint,
+            Format::R64g64b64a64Sint,
+            Format::R64g64b64a64Sfloat,
+            Format::B10g11r11UfloatPack32,
+            Format::E5b9g9r9UfloatPack32,
+            Format::D16Unorm,
+            Format::X8D24UnormPack32,
+            Format::D32Sfloat,
+            Format::S8Uint,
+            Format::D16UnormS8Uint,
+            Format::D24UnormS8Uint,
+            Format::D32SfloatS8Uint,
+            Format::Bc1RgbUnormBlock,
+            Format::Bc1RgbSrgbBlock,
+            Format::Bc1RgbaUnormBlock,
+            Format::Bc1RgbaSrgbBlock,
+            Format::Bc2UnormBlock,
+            Format::Bc2SrgbBlock,
+            Format::Bc3UnormBlock,
+            Format::Bc3SrgbBlock,
+            Format::Bc4UnormBlock,
+            Format::Bc4SnormBlock,
+            Format::Bc5UnormBlock,
+            Format::Bc5SnormBlock,
+            Format::Bc6hUfloatBlock,
+            Format::Bc6hSfloatBlock,
+            Format::Bc7UnormBlock,
+            Format::Bc7SrgbBlock,
+            Format::Etc2R8g8b8UnormBlock,
+            Format::Etc2R8g8b8SrgbBlock,
+            Format::Etc2R8g8b8a1UnormBlock,
+            Format::Etc2R8g8b8a1SrgbBlock,
+            Format::Etc2R8g8b8a8UnormBlock,
+            Format::Etc2R8g8b8a8SrgbBlock,
+            Format::EacR11UnormBlock,
+            Format::EacR11SnormBlock,
+            Format::EacR11g11UnormBlock,
+            Format::EacR11g11SnormBlock,
+            Format::Astc4x4UnormBlock,
+            Format::Astc4x4SrgbBlock,
+            Format::Astc5x4UnormBlock,
+            Format::Astc5x4SrgbBlock,
+            Format::Astc5x5UnormBlock,
+            Format::Astc5x5SrgbBlock,
+            Format::Astc6x5UnormBlock,
+            Format::Astc6x5SrgbBlock,
+            Format::Astc6x6UnormBlock,
+            Format::Astc6x6SrgbBlock,
+            Format::Astc8x5UnormBlock,
+            Format::Astc8x5SrgbBlock,
+            Format::Astc8x6UnormBlock,
+            Format::Astc8x6SrgbBlock,
+            Format::Astc8x8UnormBlock,
+            Format::Astc8x8SrgbBlock,
+            Format::Astc10x5UnormBlock,
+            Format::Astc10x5SrgbBlock,
+            Format::Astc10x6UnormBlock,
+            Format::Astc10x6SrgbBlock,
+            Format::Astc10x8UnormBlock,
+            Format::Astc10x8SrgbBlock,
+            Format::Astc10x10UnormBlock,
+            Format::Astc10x10SrgbBlock,
+            Format::Astc12x10UnormBlock,
+            Format::Astc12x10SrgbBlock,
+            Format::Astc12x12UnormBlock,
+            Format::Astc12x12SrgbBlock,
+        ];
+    }
+
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub enum ImageType {
@@ -3436,6 +4225,43 @@ pub mod types {
         DebugReport = 28,
     }
 
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum ObjectType {
+        Unknown = 0,
+        Instance = 1,
+        PhysicalDevice = 2,
+        Device = 3,
+        Queue = 4,
+        Semaphore = 5,
+        CommandBuffer = 6,
+        Fence = 7,
+        DeviceMemory = 8,
+        Buffer = 9,
+        Image = 10,
+        Event = 11,
+        QueryPool = 12,
+        BufferView = 13,
+        ImageView = 14,
+        ShaderModule = 15,
+        PipelineCache = 16,
+        PipelineLayout = 17,
+        RenderPass = 18,
+        Pipeline = 19,
+        DescriptorSetLayout = 20,
+        Sampler = 21,
+        DescriptorPool = 22,
+        DescriptorSet = 23,
+        Framebuffer = 24,
+        CommandPool = 25,
+        SurfaceKhr = 1000000000,
+        SwapchainKhr = 1000001000,
+        DisplayKhr = 1000002000,
+        DisplayModeKhr = 1000002001,
+        DebugReportCallbackExt = 1000011000,
+        DebugUtilsMessengerExt = 1000128000,
+    }
+
     #[repr(C)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
     pub enum DebugReportErrorEXT {
@@ -3443,9 +4269,16 @@ pub mod types {
         CallbackRef = 1,
     }
 
+    /// Implemented by every handle type, so that handles can be passed to functions like
+    /// `vkSetDebugUtilsObjectNameEXT` that identify an object by its type and raw value.
+    pub trait Handle: Copy {
+        const TYPE: ObjectType;
+        fn as_raw(self) -> uint64_t;
+    }
+
     macro_rules! vk_define_handle{
-        ($name: ident) => {
-            #[derive(Clone, Copy, Debug)]
+        ($name: ident, $object_type: ident) => {
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
             #[repr(C)]
             pub struct $name{
                 ptr: *mut u8
@@ -3461,40 +4294,48 @@ pub mod types {
                     }
                 }
             }
+
+            impl Handle for $name {
+                const TYPE: ObjectType = ObjectType::$object_type;
+                fn as_raw(self) -> uint64_t {
+                    self.ptr as uint64_t
+                }
+            }
         }
     }
 
-    vk_define_handle!(Instance);
-    vk_define_handle!(Device);
-    vk_define_handle!(PhysicalDevice);
-    vk_define_handle!(Queue);
-    vk_define_handle!(CommandBuffer);
-
-    handle_nondispatchable!(Semaphore);
-    handle_nondispatchable!(Fence);
-    handle_nondispatchable!(DeviceMemory);
-    handle_nondispatchable!(Buffer);
-    handle_nondispatchable!(Image);
-    handle_nondispatchable!(Event);
-    handle_nondispatchable!(QueryPool);
-    handle_nondispatchable!(BufferView);
-    handle_nondispatchable!(ImageView);
-    handle_nondispatchable!(ShaderModule);
-    handle_nondispatchable!(PipelineCache);
-    handle_nondispatchable!(PipelineLayout);
-    handle_nondispatchable!(RenderPass);
-    handle_nondispatchable!(Pipeline);
-    handle_nondispatchable!(DescriptorSetLayout);
-    handle_nondispatchable!(Sampler);
-    handle_nondispatchable!(DescriptorPool);
-    handle_nondispatchable!(DescriptorSet);
-    handle_nondispatchable!(Framebuffer);
-    handle_nondispatchable!(CommandPool);
-    handle_nondispatchable!(SurfaceKHR);
-    handle_nondispatchable!(SwapchainKHR);
-    handle_nondispatchable!(DisplayKHR);
-    handle_nondispatchable!(DisplayModeKHR);
-    handle_nondispatchable!(DebugReportCallbackEXT);
+    vk_define_handle!(Instance, Instance);
+    vk_define_handle!(Device, Device);
+    vk_define_handle!(PhysicalDevice, PhysicalDevice);
+    vk_define_handle!(Queue, Queue);
+    vk_define_handle!(CommandBuffer, CommandBuffer);
+
+    handle_nondispatchable!(Semaphore, Semaphore);
+    handle_nondispatchable!(Fence, Fence);
+    handle_nondispatchable!(DeviceMemory, DeviceMemory);
+    handle_nondispatchable!(Buffer, Buffer);
+    handle_nondispatchable!(Image, Image);
+    handle_nondispatchable!(Event, Event);
+    handle_nondispatchable!(QueryPool, QueryPool);
+    handle_nondispatchable!(BufferView, BufferView);
+    handle_nondispatchable!(ImageView, ImageView);
+    handle_nondispatchable!(ShaderModule, ShaderModule);
+    handle_nondispatchable!(PipelineCache, PipelineCache);
+    handle_nondispatchable!(PipelineLayout, PipelineLayout);
+    handle_nondispatchable!(RenderPass, RenderPass);
+    handle_nondispatchable!(Pipeline, Pipeline);
+    handle_nondispatchable!(DescriptorSetLayout, DescriptorSetLayout);
+    handle_nondispatchable!(Sampler, Sampler);
+    handle_nondispatchable!(DescriptorPool, DescriptorPool);
+    handle_nondispatchable!(DescriptorSet, DescriptorSet);
+    handle_nondispatchable!(Framebuffer, Framebuffer);
+    handle_nondispatchable!(CommandPool, CommandPool);
+    handle_nondispatchable!(SurfaceKHR, SurfaceKhr);
+    handle_nondispatchable!(SwapchainKHR, SwapchainKhr);
+    handle_nondispatchable!(DisplayKHR, DisplayKhr);
+    handle_nondispatchable!(DisplayModeKHR, DisplayModeKhr);
+    handle_nondispatchable!(DebugReportCallbackEXT, DebugReportCallbackExt);
+    handle_nondispatchable!(DebugUtilsMessengerEXT, DebugUtilsMessengerExt);
 
     pub const FORMAT_FEATURE_SAMPLED_IMAGE_BIT: FormatFeatureFlags =
         FormatFeatureFlags { flags: 0b1 };
@@ -3831,6 +4672,24 @@ pub mod types {
         DebugReportFlagsEXT { flags: 0b10000 };
     vk_bitflags_wrapped!(DebugReportFlagsEXT, 0b11111, Flags);
 
+    pub const DEBUG_UTILS_MESSAGE_SEVERITY_VERBOSE_BIT_EXT: DebugUtilsMessageSeverityFlagsEXT =
+        DebugUtilsMessageSeverityFlagsEXT { flags: 0b1 };
+    pub const DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT: DebugUtilsMessageSeverityFlagsEXT =
+        DebugUtilsMessageSeverityFlagsEXT { flags: 0b10000 };
+    pub const DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT: DebugUtilsMessageSeverityFlagsEXT =
+        DebugUtilsMessageSeverityFlagsEXT { flags: 0b100000000 };
+    pub const DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT: DebugUtilsMessageSeverityFlagsEXT =
+        DebugUtilsMessageSeverityFlagsEXT { flags: 0b1000000000000 };
+    vk_bitflags_wrapped!(DebugUtilsMessageSeverityFlagsEXT, 0b1000100010001, Flags);
+
+    pub const DEBUG_UTILS_MESSAGE_TYPE_GENERAL_BIT_EXT: DebugUtilsMessageTypeFlagsEXT =
+        DebugUtilsMessageTypeFlagsEXT { flags: 0b1 };
+    pub const DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT: DebugUtilsMessageTypeFlagsEXT =
+        DebugUtilsMessageTypeFlagsEXT { flags: 0b10 };
+    pub const DEBUG_UTILS_MESSAGE_TYPE_PERFORMANCE_BIT_EXT: DebugUtilsMessageTypeFlagsEXT =
+        DebugUtilsMessageTypeFlagsEXT { flags: 0b100 };
+    vk_bitflags_wrapped!(DebugUtilsMessageTypeFlagsEXT, 0b111, Flags);
+
 
     pub type PFN_vkAllocationFunction = unsafe extern "system" fn(*mut c_void,
                                                                   size_t,
@@ -3870,6 +4729,13 @@ pub mod types {
                                                                       *mut c_void)
                                                                       -> Bool32;
 
+    pub type PFN_vkDebugUtilsMessengerCallbackEXT =
+        unsafe extern "system" fn(DebugUtilsMessageSeverityFlagsEXT,
+                                  DebugUtilsMessageTypeFlagsEXT,
+                                  *const DebugUtilsMessengerCallbackDataEXT,
+                                  *mut c_void)
+                                  -> Bool32;
+
 
 }
 // FIX: Need better error handling for extensions
@@ -5009,6 +5875,66 @@ pub mod cmds {
         p_layer_prefix: *const c_char,
         p_message: *const c_char,
     ) -> ();
+}
+    vk_functions!{
+    DebugUtilsFn,
+    "vkSetDebugUtilsObjectNameEXT", set_debug_utils_object_name_ext(
+        device: Device,
+        p_name_info: *const DebugUtilsObjectNameInfoEXT,
+    ) -> Result;
+
+    "vkSetDebugUtilsObjectTagEXT", set_debug_utils_object_tag_ext(
+        device: Device,
+        p_tag_info: *const DebugUtilsObjectTagInfoEXT,
+    ) -> Result;
+
+    "vkQueueBeginDebugUtilsLabelEXT", queue_begin_debug_utils_label_ext(
+        queue: Queue,
+        p_label_info: *const DebugUtilsLabelEXT,
+    ) -> ();
+
+    "vkQueueEndDebugUtilsLabelEXT", queue_end_debug_utils_label_ext(
+        queue: Queue,
+    ) -> ();
+
+    "vkQueueInsertDebugUtilsLabelEXT", queue_insert_debug_utils_label_ext(
+        queue: Queue,
+        p_label_info: *const DebugUtilsLabelEXT,
+    ) -> ();
+
+    "vkCmdBeginDebugUtilsLabelEXT", cmd_begin_debug_utils_label_ext(
+        command_buffer: CommandBuffer,
+        p_label_info: *const DebugUtilsLabelEXT,
+    ) -> ();
+
+    "vkCmdEndDebugUtilsLabelEXT", cmd_end_debug_utils_label_ext(
+        command_buffer: CommandBuffer,
+    ) -> ();
+
+    "vkCmdInsertDebugUtilsLabelEXT", cmd_insert_debug_utils_label_ext(
+        command_buffer: CommandBuffer,
+        p_label_info: *const DebugUtilsLabelEXT,
+    ) -> ();
+
+    "vkCreateDebugUtilsMessengerEXT", create_debug_utils_messenger_ext(
+        instance: Instance,
+        p_create_info: *const DebugUtilsMessengerCreateInfoEXT,
+        p_allocator: *const AllocationCallbacks,
+        p_messenger: *mut DebugUtilsMessengerEXT,
+    ) -> Result;
+
+    "vkDestroyDebugUtilsMessengerEXT", destroy_debug_utils_messenger_ext(
+        instance: Instance,
+        messenger: DebugUtilsMessengerEXT,
+        p_allocator: *const AllocationCallbacks,
+    ) -> ();
+
+    "vkSubmitDebugUtilsMessageEXT", submit_debug_utils_message_ext(
+        instance: Instance,
+        message_severity: DebugUtilsMessageSeverityFlagsEXT,
+        message_types: DebugUtilsMessageTypeFlagsEXT,
+        p_callback_data: *const DebugUtilsMessengerCallbackDataEXT,
+    ) -> ();
 }
     vk_functions!{
     Win32SurfaceFn,
@@ -5150,3 +6076,151 @@ pub mod cmds {
     ) -> Result;
 }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DriverVersion, ParseVersionError, PhysicalDeviceFeatures, Version, VENDOR_ID_NVIDIA,
+    };
+
+    #[test]
+    fn parse_version() {
+        assert_eq!("1.0.61".parse(), Ok(Version::new(1, 0, 61)));
+        assert_eq!(" 1.1 ".parse(), Ok(Version::new(1, 1, 0)));
+        assert_eq!("1023.1023.4095".parse(), Ok(Version::new(1023, 1023, 4095)));
+    }
+
+    #[test]
+    fn parse_version_errors() {
+        assert_eq!("1".parse::<Version>(), Err(ParseVersionError::TooFewParts));
+        assert_eq!("1.0.0.0".parse::<Version>(), Err(ParseVersionError::TooManyParts));
+        assert_eq!("1.x.0".parse::<Version>(), Err(ParseVersionError::InvalidNumber));
+        assert_eq!("1..0".parse::<Version>(), Err(ParseVersionError::InvalidNumber));
+        assert_eq!("1024.0".parse::<Version>(), Err(ParseVersionError::OutOfRange));
+        assert_eq!("1.0.4096".parse::<Version>(), Err(ParseVersionError::OutOfRange));
+    }
+
+    #[test]
+    fn version_fields() {
+        let version = Version::new(1, 2, 3);
+        assert_eq!(version.raw(), (1 << 22) | (2 << 12) | 3);
+        assert_eq!((version.major(), version.minor(), version.patch()), (1, 2, 3));
+        assert_eq!(version.to_string(), "1.2.3");
+        assert!(Version::new(1, 1, 0) > Version::new(1, 0, 70));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn version_macros() {
+        assert_eq!(vk_make_version!(1, 0, 36), Version::new(1, 0, 36).raw());
+        assert_eq!(
+            vk_version_major!(1) | vk_version_minor!(2) | vk_version_patch!(3),
+            Version::new(1, 2, 3).raw()
+        );
+    }
+
+    #[test]
+    fn nvidia_driver_version() {
+        // 390.87.0.0, as reported by the NVIDIA driver.
+        let raw = (390 << 22) | (87 << 14);
+        assert_eq!(
+            DriverVersion::new(VENDOR_ID_NVIDIA, raw),
+            DriverVersion::Nvidia {
+                major: 390,
+                minor: 87,
+                secondary: 0,
+                tertiary: 0,
+            }
+        );
+        let raw = (418 << 22) | (56 << 14) | (3 << 6) | 5;
+        assert_eq!(DriverVersion::new(VENDOR_ID_NVIDIA, raw).to_string(), "418.56.3.5");
+    }
+
+    #[test]
+    fn standard_driver_version() {
+        let raw = Version::new(18, 1, 5).raw();
+        assert_eq!(
+            DriverVersion::new(0x1002, raw),
+            DriverVersion::Standard(Version::new(18, 1, 5))
+        );
+        assert_eq!(DriverVersion::new(0x1002, raw).to_string(), "18.1.5");
+    }
+
+    fn wanted() -> PhysicalDeviceFeatures {
+        PhysicalDeviceFeatures {
+            geometry_shader: 1,
+            sampler_anisotropy: 1,
+            shader_float64: 1,
+            ..Default::default()
+        }
+    }
+
+    fn supported() -> PhysicalDeviceFeatures {
+        PhysicalDeviceFeatures {
+            geometry_shader: 1,
+            // Any value that isn't 0 is set.
+            sampler_anisotropy: 2,
+            wide_lines: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn feature_set_operations() {
+        assert_eq!(
+            wanted().intersection(&supported()),
+            PhysicalDeviceFeatures {
+                geometry_shader: 1,
+                sampler_anisotropy: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            wanted().union(&supported()),
+            PhysicalDeviceFeatures {
+                geometry_shader: 1,
+                sampler_anisotropy: 1,
+                shader_float64: 1,
+                wide_lines: 1,
+                ..Default::default()
+            }
+        );
+        assert!(!wanted().is_subset_of(&supported()));
+        assert!(wanted()
+            .intersection(&supported())
+            .is_subset_of(&supported()));
+        assert!(wanted().is_subset_of(&wanted().union(&supported())));
+        assert!(PhysicalDeviceFeatures::default().is_subset_of(&supported()));
+    }
+
+    #[test]
+    fn missing_features() {
+        assert_eq!(wanted().missing_from(&supported()), vec!["shader_float64"]);
+        assert_eq!(supported().missing_from(&wanted()), vec!["wide_lines"]);
+        assert!(wanted().missing_from(&wanted()).is_empty());
+        assert_eq!(
+            wanted().missing_from(&PhysicalDeviceFeatures::default()),
+            vec!["geometry_shader", "sampler_anisotropy", "shader_float64"]
+        );
+    }
+
+    #[test]
+    fn feature_iter() {
+        let features = supported();
+        assert_eq!(
+            features.iter().count(),
+            PhysicalDeviceFeatures::FIELD_NAMES.len()
+        );
+        let names: Vec<_> = features.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, PhysicalDeviceFeatures::FIELD_NAMES);
+        let set: Vec<_> = features
+            .iter()
+            .filter(|&(_, set)| set)
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            set,
+            vec!["geometry_shader", "wide_lines", "sampler_anisotropy"]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Surface creation from the native handles that windowing libraries expose.
+//!
+//! ```rust,ignore
+//! let handle = RawWindowHandle::Xlib {
+//!     display: window.get_xlib_display().unwrap() as *mut vk::Display,
+//!     window: window.get_xlib_window().unwrap() as vk::Window,
+//! };
+//! let extension_names: Vec<*const i8> = required_surface_extensions(&handle)
+//!     .iter()
+//!     .map(|name| name.as_ptr())
+//!     .collect();
+//! // create the instance with `extension_names` enabled
+//! let surface = create_surface(&entry, &instance, handle, None)?;
+//! ```
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::ptr;
+use vk;
+use version::{EntryV1_0, InstanceV1_0};
+use extensions::{AndroidSurface, IOSSurface, MacOSSurface, MirSurface, Surface, WaylandSurface,
+                 Win32Surface, XcbSurface, XlibSurface};
+
+/// A native window, together with the display connection it belongs to where the window system
+/// needs one.
+#[derive(Clone, Copy, Debug)]
+pub enum RawWindowHandle {
+    Xlib {
+        display: *mut vk::Display,
+        window: vk::Window,
+    },
+    Xcb {
+        connection: *mut vk::xcb_connection_t,
+        window: vk::xcb_window_t,
+    },
+    Wayland {
+        display: *mut vk::wl_display,
+        surface: *mut vk::wl_surface,
+    },
+    Mir {
+        connection: *mut vk::MirConnection,
+        surface: *mut vk::MirSurface,
+    },
+    Win32 {
+        hinstance: vk::HINSTANCE,
+        hwnd: vk::HWND,
+    },
+    Android { window: *mut vk::ANativeWindow },
+    /// `view` is a `UIView` that is backed by a `CAMetalLayer`.
+    IOS { view: *const vk::c_void },
+    /// `view` is an `NSView` that is backed by a `CAMetalLayer`.
+    MacOS { view: *const vk::c_void },
+}
+
+#[derive(Debug)]
+pub enum SurfaceError {
+    LoadError(Vec<&'static str>),
+    VkError(vk::Result),
+}
+
+impl fmt::Display for SurfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SurfaceError::{:?}", self)
+    }
+}
+
+impl Error for SurfaceError {
+    fn description(&self) -> &str {
+        "SurfaceError"
+    }
+}
+
+/// The instance extensions that `create_surface` needs for `handle`.
+pub fn required_surface_extensions(handle: &RawWindowHandle) -> Vec<&'static CStr> {
+    let platform_extension = match *handle {
+        RawWindowHandle::Xlib { .. } => XlibSurface::name(),
+        RawWindowHandle::Xcb { .. } => XcbSurface::name(),
+        RawWindowHandle::Wayland { .. } => WaylandSurface::name(),
+        RawWindowHandle::Mir { .. } => MirSurface::name(),
+        RawWindowHandle::Win32 { .. } => Win32Surface::name(),
+        RawWindowHandle::Android { .. } => AndroidSurface::name(),
+        RawWindowHandle::IOS { .. } => IOSSurface::name(),
+        RawWindowHandle::MacOS { .. } => MacOSSurface::name(),
+    };
+    vec![Surface::name(), platform_extension]
+}
+
+/// Creates a surface for `handle` with the surface extension of its window system. The instance
+/// has to be created with the extensions from `required_surface_extensions`.
+pub unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(
+    entry: &E,
+    instance: &I,
+    handle: RawWindowHandle,
+    allocation_callbacks: Option<&vk::AllocationCallbacks>,
+) -> Result<vk::SurfaceKHR, SurfaceError> {
+    let surface = match handle {
+        RawWindowHandle::Xlib { display, window } => {
+            let create_info = vk::XlibSurfaceCreateInfoKHR {
+                s_type: vk::StructureType::XlibSurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                dpy: display,
+                window: window,
+            };
+            XlibSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_xlib_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::Xcb { connection, window } => {
+            let create_info = vk::XcbSurfaceCreateInfoKHR {
+                s_type: vk::StructureType::XcbSurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                connection: connection,
+                window: window,
+            };
+            XcbSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_xcb_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::Wayland { display, surface } => {
+            let create_info = vk::WaylandSurfaceCreateInfoKHR {
+                s_type: vk::StructureType::WaylandSurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                display: display,
+                surface: surface,
+            };
+            WaylandSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_wayland_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::Mir {
+            connection,
+            surface,
+        } => {
+            let create_info = vk::MirSurfaceCreateInfoKHR {
+                s_type: vk::StructureType::MirSurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                connection: connection,
+                mir_surface: surface,
+            };
+            MirSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_mir_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::Win32 { hinstance, hwnd } => {
+            let create_info = vk::Win32SurfaceCreateInfoKHR {
+                s_type: vk::StructureType::Win32SurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                hinstance: hinstance,
+                hwnd: hwnd,
+            };
+            Win32Surface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_win32_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::Android { window } => {
+            let create_info = vk::AndroidSurfaceCreateInfoKHR {
+                s_type: vk::StructureType::AndroidSurfaceCreateInfoKhr,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                window: window,
+            };
+            AndroidSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_android_surface_khr(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::IOS { view } => {
+            let create_info = vk::IOSSurfaceCreateInfoMVK {
+                s_type: vk::StructureType::IOSSurfaceCreateInfoMvk,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                p_view: view,
+            };
+            IOSSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_ios_surface_mvk(&create_info, allocation_callbacks)
+        }
+        RawWindowHandle::MacOS { view } => {
+            let create_info = vk::MacOSSurfaceCreateInfoMVK {
+                s_type: vk::StructureType::MacOSSurfaceCreateInfoMvk,
+                p_next: ptr::null(),
+                flags: Default::default(),
+                p_view: view,
+            };
+            MacOSSurface::new(entry, instance)
+                .map_err(|err| SurfaceError::LoadError(err))?
+                .create_macos_surface_mvk(&create_info, allocation_callbacks)
+        }
+    };
+    surface.map_err(|err| SurfaceError::VkError(err))
+}
@@ -1,9 +1,11 @@
 extern crate ash;
 #[macro_use]
+extern crate ash_derive;
 extern crate examples;
 extern crate image;
 
 use ash::vk;
+use ash::vertex::Vertex as VertexInput;
 use std::default::Default;
 use std::ptr;
 use std::ffi::CString;
@@ -13,9 +15,10 @@ use std::fs::File;
 use std::io::Read;
 use examples::*;
 use ash::util::*;
+use ash::memory::{find_memory_type_index, MemoryTypeRequest};
 use std::mem::align_of;
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Vertex)]
 struct Vertex {
     pos: [f32; 4],
     uv: [f32; 2],
@@ -133,10 +136,12 @@ fn main() {
         let index_buffer = base.device.create_buffer(&index_buffer_info, None).unwrap();
         let index_buffer_memory_req = base.device.get_buffer_memory_requirements(index_buffer);
         let index_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &index_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the index buffer.");
         let index_allocate_info = vk::MemoryAllocateInfo {
             s_type: vk::StructureType::MemoryAllocateInfo,
@@ -155,11 +160,8 @@ fn main() {
                 vk::MemoryMapFlags::empty(),
             )
             .unwrap();
-        let mut index_slice = Align::new(
-            index_ptr,
-            align_of::<u32>() as u64,
-            index_buffer_memory_req.size,
-        );
+        let mut index_slice: Align<u32> =
+            Align::from_memory_requirements(index_ptr, &index_buffer_memory_req);
         index_slice.copy_from_slice(&index_buffer_data);
         base.device.unmap_memory(index_buffer_memory);
         base.device
@@ -201,10 +203,12 @@ fn main() {
             vertex_input_buffer,
         );
         let vertex_input_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &vertex_input_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the vertex buffer.");
 
         let vertex_buffer_allocate_info = vk::MemoryAllocateInfo {
@@ -257,10 +261,12 @@ fn main() {
             uniform_color_buffer,
         );
         let uniform_color_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &uniform_color_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the vertex buffer.");
 
         let uniform_color_buffer_allocate_info = vk::MemoryAllocateInfo {
@@ -307,10 +313,12 @@ fn main() {
         let image_buffer = base.device.create_buffer(&image_buffer_info, None).unwrap();
         let image_buffer_memory_req = base.device.get_buffer_memory_requirements(image_buffer);
         let image_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &image_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the vertex buffer.");
 
         let image_buffer_allocate_info = vk::MemoryAllocateInfo {
@@ -367,10 +375,10 @@ fn main() {
             .unwrap();
         let texture_memory_req = base.device.get_image_memory_requirements(texture_image);
         let texture_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &texture_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT,
+                &[MemoryTypeRequest::new(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT)],
             ).expect("Unable to find suitable memory index for depth image.");
 
         let texture_allocate_info = vk::MemoryAllocateInfo {
@@ -686,27 +694,9 @@ fn main() {
                 stage: vk::SHADER_STAGE_FRAGMENT_BIT,
             },
         ];
-        let vertex_input_binding_descriptions = [
-            vk::VertexInputBindingDescription {
-                binding: 0,
-                stride: mem::size_of::<Vertex>() as u32,
-                input_rate: vk::VertexInputRate::Vertex,
-            },
-        ];
-        let vertex_input_attribute_descriptions = [
-            vk::VertexInputAttributeDescription {
-                location: 0,
-                binding: 0,
-                format: vk::Format::R32g32b32a32Sfloat,
-                offset: offset_of!(Vertex, pos) as u32,
-            },
-            vk::VertexInputAttributeDescription {
-                location: 1,
-                binding: 0,
-                format: vk::Format::R32g32b32a32Sfloat,
-                offset: offset_of!(Vertex, uv) as u32,
-            },
-        ];
+        let vertex_input_binding_descriptions =
+            [Vertex::binding_description(0, vk::VertexInputRate::Vertex)];
+        let vertex_input_attribute_descriptions = Vertex::attribute_descriptions(0);
         let vertex_input_state_info = vk::PipelineVertexInputStateCreateInfo {
             s_type: vk::StructureType::PipelineVertexInputStateCreateInfo,
             p_next: ptr::null(),
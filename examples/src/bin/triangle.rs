@@ -1,8 +1,10 @@
 extern crate ash;
 #[macro_use]
+extern crate ash_derive;
 extern crate examples;
 
 use ash::vk;
+use ash::vertex::Vertex as VertexInput;
 use std::default::Default;
 use std::ptr;
 use std::ffi::CString;
@@ -12,9 +14,9 @@ use std::fs::File;
 use std::io::Read;
 use examples::*;
 use ash::util::*;
-use std::mem::align_of;
+use ash::memory::{find_memory_type_index, MemoryTypeRequest};
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, Vertex)]
 struct Vertex {
     pos: [f32; 4],
     color: [f32; 4],
@@ -125,10 +127,12 @@ fn main() {
         let index_buffer = base.device.create_buffer(&index_buffer_info, None).unwrap();
         let index_buffer_memory_req = base.device.get_buffer_memory_requirements(index_buffer);
         let index_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &index_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the index buffer.");
         let index_allocate_info = vk::MemoryAllocateInfo {
             s_type: vk::StructureType::MemoryAllocateInfo,
@@ -139,21 +143,24 @@ fn main() {
         let index_buffer_memory = base.device
             .allocate_memory(&index_allocate_info, None)
             .unwrap();
-        let index_ptr = base.device
-            .map_memory(
+        let index_memory_flags = base.device_memory_properties.memory_types
+            [index_buffer_memory_index as usize]
+            .property_flags;
+        let non_coherent_atom_size = base.instance
+            .get_physical_device_properties(base.pdevice)
+            .limits
+            .non_coherent_atom_size;
+        let mut index_mapping = base.device
+            .map_memory_guard(
                 index_buffer_memory,
                 0,
                 index_buffer_memory_req.size,
-                vk::MemoryMapFlags::empty(),
+                index_memory_flags,
+                non_coherent_atom_size,
             )
             .unwrap();
-        let mut index_slice = Align::new(
-            index_ptr,
-            align_of::<u32>() as u64,
-            index_buffer_memory_req.size,
-        );
-        index_slice.copy_from_slice(&index_buffer_data);
-        base.device.unmap_memory(index_buffer_memory);
+        index_mapping.copy_from_slice(0, &index_buffer_data);
+        index_mapping.unmap().unwrap();
         base.device
             .bind_buffer_memory(index_buffer, index_buffer_memory, 0)
             .unwrap();
@@ -175,10 +182,12 @@ fn main() {
             vertex_input_buffer,
         );
         let vertex_input_buffer_memory_index =
-            find_memorytype_index(
+            find_memory_type_index(
                 &vertex_input_buffer_memory_req,
                 &base.device_memory_properties,
-                vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT,
+                &[MemoryTypeRequest::new(
+                    vk::MEMORY_PROPERTY_HOST_VISIBLE_BIT | vk::MEMORY_PROPERTY_HOST_COHERENT_BIT,
+                )],
             ).expect("Unable to find suitable memorytype for the vertex buffer.");
 
         let vertex_buffer_allocate_info = vk::MemoryAllocateInfo {
@@ -212,11 +221,8 @@ fn main() {
                 vk::MemoryMapFlags::empty(),
             )
             .unwrap();
-        let mut vert_align = Align::new(
-            vert_ptr,
-            align_of::<Vertex>() as u64,
-            vertex_input_buffer_memory_req.size,
-        );
+        let mut vert_align: Align<Vertex> =
+            Align::from_memory_requirements(vert_ptr, &vertex_input_buffer_memory_req);
         vert_align.copy_from_slice(&vertices);
         base.device.unmap_memory(vertex_input_buffer_memory);
         base.device
@@ -289,27 +295,9 @@ fn main() {
                 stage: vk::SHADER_STAGE_FRAGMENT_BIT,
             },
         ];
-        let vertex_input_binding_descriptions = [
-            vk::VertexInputBindingDescription {
-                binding: 0,
-                stride: mem::size_of::<Vertex>() as u32,
-                input_rate: vk::VertexInputRate::Vertex,
-            },
-        ];
-        let vertex_input_attribute_descriptions = [
-            vk::VertexInputAttributeDescription {
-                location: 0,
-                binding: 0,
-                format: vk::Format::R32g32b32a32Sfloat,
-                offset: offset_of!(Vertex, pos) as u32,
-            },
-            vk::VertexInputAttributeDescription {
-                location: 1,
-                binding: 0,
-                format: vk::Format::R32g32b32a32Sfloat,
-                offset: offset_of!(Vertex, color) as u32,
-            },
-        ];
+        let vertex_input_binding_descriptions =
+            [Vertex::binding_description(0, vk::VertexInputRate::Vertex)];
+        let vertex_input_attribute_descriptions = Vertex::attribute_descriptions(0);
         let vertex_input_state_info = vk::PipelineVertexInputStateCreateInfo {
             s_type: vk::StructureType::PipelineVertexInputStateCreateInfo,
             p_next: ptr::null(),
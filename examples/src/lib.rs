@@ -13,25 +13,14 @@ use ash::Entry;
 use ash::Instance;
 use ash::Device;
 pub use ash::version::{V1_0, InstanceV1_0, DeviceV1_0, EntryV1_0};
-use ash::extensions::{Swapchain, XlibSurface, Surface, DebugReport, Win32Surface};
+use ash::extensions::{Swapchain, Surface, DebugReport, DebugReportCallback};
+use ash::window::{create_surface, required_surface_extensions, RawWindowHandle};
+use ash::builder::InstanceBuilder;
+use ash::memory::{find_memory_type_index, MemoryTypeRequest};
 use std::ptr;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 use std::ops::Drop;
 
-// Simple offset_of macro akin to C++ offsetof
-#[macro_export]
-macro_rules! offset_of{
-    ($base: path, $field: ident) => {
-        {
-            #[allow(unused_unsafe)]
-            unsafe{
-                let b: $base = mem::uninitialized();
-                (&b.$field as *const _ as isize) - (&b as *const _ as isize)
-            }
-        }
-    }
-}
-
 pub fn record_submit_commandbuffer<D: DeviceV1_0, F: FnOnce(&D, vk::CommandBuffer)>(device: &D,
                                                              command_buffer: vk::CommandBuffer,
                                                              submit_queue: vk::Queue,
@@ -80,105 +69,23 @@ pub fn record_submit_commandbuffer<D: DeviceV1_0, F: FnOnce(&D, vk::CommandBuffe
 }
 
 #[cfg(all(unix, not(target_os = "android")))]
-unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(entry: &E,
-                                                        instance: &I,
-                                                        window: &winit::Window)
-                                                        -> Result<vk::SurfaceKHR, vk::Result> {
+fn window_handle(window: &winit::Window) -> RawWindowHandle {
     use winit::os::unix::WindowExt;
-    let x11_display = window.get_xlib_display().unwrap();
-    let x11_window = window.get_xlib_window().unwrap();
-    let x11_create_info = vk::XlibSurfaceCreateInfoKHR {
-        s_type: vk::StructureType::XlibSurfaceCreateInfoKhr,
-        p_next: ptr::null(),
-        flags: Default::default(),
-        window: x11_window as vk::Window,
-        dpy: x11_display as *mut vk::Display,
-    };
-    let xlib_surface_loader = XlibSurface::new(entry, instance)
-        .expect("Unable to load xlib surface");
-    xlib_surface_loader.create_xlib_surface_khr(&x11_create_info, None)
+    RawWindowHandle::Xlib {
+        display: window.get_xlib_display().unwrap() as *mut vk::Display,
+        window: window.get_xlib_window().unwrap() as vk::Window,
+    }
 }
 
 #[cfg(windows)]
-unsafe fn create_surface<E: EntryV1_0, I: InstanceV1_0>(entry: &E,
-                                                        instance: &I,
-                                                        window: &winit::Window)
-                                                        -> Result<vk::SurfaceKHR, vk::Result> {
+fn window_handle(window: &winit::Window) -> RawWindowHandle {
     use winit::os::windows::WindowExt;
     let hwnd = window.get_hwnd() as *mut winapi::windef::HWND__;
     let hinstance = unsafe { user32::GetWindow(hwnd, 0) as *const vk::c_void };
-    let win32_create_info = vk::Win32SurfaceCreateInfoKHR {
-        s_type: vk::StructureType::Win32SurfaceCreateInfoKhr,
-        p_next: ptr::null(),
-        flags: Default::default(),
+    RawWindowHandle::Win32 {
         hinstance: hinstance,
         hwnd: hwnd as *const vk::c_void,
-    };
-    let win32_surface_loader = Win32Surface::new(entry, instance)
-        .expect("Unable to load win32 surface");
-    win32_surface_loader.create_win32_surface_khr(&win32_create_info, None)
-}
-
-#[cfg(all(unix, not(target_os = "android")))]
-fn extension_names() -> Vec<*const i8> {
-    vec![Surface::name().as_ptr(), XlibSurface::name().as_ptr(), DebugReport::name().as_ptr()]
-}
-
-#[cfg(all(windows))]
-fn extension_names() -> Vec<*const i8> {
-    vec![Surface::name().as_ptr(), Win32Surface::name().as_ptr(), DebugReport::name().as_ptr()]
-}
-
-unsafe extern "system" fn vulkan_debug_callback(_: vk::DebugReportFlagsEXT,
-                                                _: vk::DebugReportObjectTypeEXT,
-                                                _: vk::uint64_t,
-                                                _: vk::size_t,
-                                                _: vk::int32_t,
-                                                _: *const vk::c_char,
-                                                p_message: *const vk::c_char,
-                                                _: *mut vk::c_void)
-                                                -> u32 {
-    println!("{:?}", CStr::from_ptr(p_message));
-    1
-}
-
-
-pub fn find_memorytype_index(memory_req: &vk::MemoryRequirements,
-                             memory_prop: &vk::PhysicalDeviceMemoryProperties,
-                             flags: vk::MemoryPropertyFlags)
-                             -> Option<u32> {
-    // Try to find an exactly matching memory flag
-    let best_suitable_index =
-        find_memorytype_index_f(memory_req,
-                                memory_prop,
-                                flags,
-                                |property_flags, flags| property_flags == flags);
-    if best_suitable_index.is_some() {
-        return best_suitable_index;
-    }
-    // Otherwise find a memory flag that works
-    find_memorytype_index_f(memory_req,
-                            memory_prop,
-                            flags,
-                            |property_flags, flags| property_flags & flags == flags)
-}
-
-pub fn find_memorytype_index_f<F: Fn(vk::MemoryPropertyFlags, vk::MemoryPropertyFlags) -> bool>
-    (memory_req: &vk::MemoryRequirements,
-     memory_prop: &vk::PhysicalDeviceMemoryProperties,
-     flags: vk::MemoryPropertyFlags,
-     f: F)
-     -> Option<u32> {
-    let mut memory_type_bits = memory_req.memory_type_bits;
-    for (index, ref memory_type) in memory_prop.memory_types.iter().enumerate() {
-        if memory_type_bits & 1 == 1 {
-            if f(memory_type.property_flags, flags) {
-                return Some(index as u32);
-            }
-        }
-        memory_type_bits = memory_type_bits >> 1;
     }
-    None
 }
 
 fn resize_callback(width: u32, height: u32) {
@@ -193,7 +100,7 @@ pub struct ExampleBase {
     pub swapchain_loader: Swapchain,
     pub debug_report_loader: DebugReport,
     pub window: winit::Window,
-    pub debug_call_back: vk::DebugReportCallbackEXT,
+    pub debug_call_back: Option<DebugReportCallback>,
 
     pub pdevice: vk::PhysicalDevice,
     pub device_memory_properties: vk::PhysicalDeviceMemoryProperties,
@@ -243,50 +150,31 @@ impl ExampleBase {
                 .unwrap();
             let entry = Entry::new().unwrap();
             let app_name = CString::new("VulkanTriangle").unwrap();
-            let raw_name = app_name.as_ptr();
-
-            let layer_names = [CString::new("VK_LAYER_LUNARG_standard_validation").unwrap()];
-            let layers_names_raw: Vec<*const i8> = layer_names.iter()
-                .map(|raw_name| raw_name.as_ptr())
-                .collect();
-            let extension_names_raw = extension_names();
-            let appinfo = vk::ApplicationInfo {
-                p_application_name: raw_name,
-                s_type: vk::StructureType::ApplicationInfo,
-                p_next: ptr::null(),
-                application_version: 0,
-                p_engine_name: raw_name,
-                engine_version: 0,
-                api_version: vk_make_version!(1, 0, 36),
-            };
-            let create_info = vk::InstanceCreateInfo {
-                s_type: vk::StructureType::InstanceCreateInfo,
-                p_next: ptr::null(),
-                flags: Default::default(),
-                p_application_info: &appinfo,
-                pp_enabled_layer_names: layers_names_raw.as_ptr(),
-                enabled_layer_count: layers_names_raw.len() as u32,
-                pp_enabled_extension_names: extension_names_raw.as_ptr(),
-                enabled_extension_count: extension_names_raw.len() as u32,
-            };
-            let instance: Instance<V1_0> = entry.create_instance(&create_info, None)
+            let validation_layer = CString::new("VK_LAYER_LUNARG_standard_validation").unwrap();
+            let raw_window_handle = window_handle(&window);
+            let (instance, names): (Instance<V1_0>, _) = InstanceBuilder::new()
+                .application_name(&app_name)
+                .engine_name(&app_name)
+                .api_version(vk::Version::new(1, 0, 36))
+                .optional_layer(&validation_layer)
+                .extensions(required_surface_extensions(&raw_window_handle))
+                .extension(DebugReport::name())
+                .build(&entry, None)
                 .expect("Instance creation error");
-            let debug_info = vk::DebugReportCallbackCreateInfoEXT {
-                s_type: vk::StructureType::DebugReportCallbackCreateInfoExt,
-                p_next: ptr::null(),
-                flags: vk::DEBUG_REPORT_ERROR_BIT_EXT | vk::DEBUG_REPORT_WARNING_BIT_EXT |
-                       vk::DEBUG_REPORT_PERFORMANCE_WARNING_BIT_EXT,
-                pfn_callback: vulkan_debug_callback,
-                p_user_data: ptr::null_mut(),
-            };
-            let debug_report_loader = DebugReport::new(&entry, &instance)
+            for layer in &names.dropped_layers {
+                println!("{:?} is not available", layer);
+            }
+            let debug_report_loader = instance.load::<DebugReport>()
                 .expect("Unable to load debug report");
-            let debug_call_back =
-                debug_report_loader.create_debug_report_callback_ext(&debug_info, None)
-                    .unwrap();
-            let surface = create_surface(&entry, &instance, &window).unwrap();
+            let debug_call_back = debug_report_loader.create_debug_report_callback(
+                    vk::DEBUG_REPORT_ERROR_BIT_EXT | vk::DEBUG_REPORT_WARNING_BIT_EXT |
+                    vk::DEBUG_REPORT_PERFORMANCE_WARNING_BIT_EXT,
+                    |message| println!("{}", message),
+                    None)
+                .unwrap();
+            let surface = create_surface(&entry, &instance, raw_window_handle, None).unwrap();
             let pdevices = instance.enumerate_physical_devices().expect("Physical device error");
-            let surface_loader = Surface::new(&entry, &instance)
+            let surface_loader = instance.load::<Surface>()
                 .expect("Unable to load the Surface extension");
             let (pdevice, queue_family_index) = pdevices.iter()
                 .map(|pdevice| {
@@ -384,7 +272,7 @@ impl ExampleBase {
                 .cloned()
                 .find(|&mode| mode == vk::PresentModeKHR::Mailbox)
                 .unwrap_or(vk::PresentModeKHR::Fifo);
-            let swapchain_loader = Swapchain::new(&instance, &device)
+            let swapchain_loader = device.load::<Swapchain>()
                 .expect("Unable to load swapchain");
             let swapchain_create_info = vk::SwapchainCreateInfoKHR {
                 s_type: vk::StructureType::SwapchainCreateInfoKhr,
@@ -479,9 +367,9 @@ impl ExampleBase {
             let depth_image = device.create_image(&depth_image_create_info, None).unwrap();
             let depth_image_memory_req = device.get_image_memory_requirements(depth_image);
             let depth_image_memory_index =
-                find_memorytype_index(&depth_image_memory_req,
+                find_memory_type_index(&depth_image_memory_req,
                                       &device_memory_properties,
-                                      vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT)
+                                      &[MemoryTypeRequest::new(vk::MEMORY_PROPERTY_DEVICE_LOCAL_BIT)])
                     .expect("Unable to find suitable memory index for depth image.");
 
             let depth_image_allocate_info = vk::MemoryAllocateInfo {
@@ -583,7 +471,7 @@ impl ExampleBase {
                 present_complete_semaphore: present_complete_semaphore,
                 rendering_complete_semaphore: rendering_complete_semaphore,
                 surface: surface,
-                debug_call_back: debug_call_back,
+                debug_call_back: Some(debug_call_back),
                 debug_report_loader: debug_report_loader,
                 depth_image_memory: depth_image_memory,
             }
@@ -607,7 +495,9 @@ impl Drop for ExampleBase {
             self.swapchain_loader.destroy_swapchain_khr(self.swapchain, None);
             self.device.destroy_device(None);
             self.surface_loader.destroy_surface_khr(self.surface, None);
-            self.debug_report_loader.destroy_debug_report_callback_ext(self.debug_call_back, None);
+            if let Some(debug_call_back) = self.debug_call_back.take() {
+                self.debug_report_loader.destroy_debug_report_callback(debug_call_back, None);
+            }
             self.instance.destroy_instance(None);
         }
     }